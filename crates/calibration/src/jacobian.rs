@@ -1,5 +1,5 @@
 use nalgebra::{Const, Dyn, Matrix, Owned, SVector};
-use types::field_dimensions::FieldDimensions;
+use types::{camera_calibration::RobustLoss, field_dimensions::FieldDimensions};
 
 use crate::{
     corrections::{Corrections, AMOUNT_OF_PARAMETERS},
@@ -16,6 +16,7 @@ pub fn calculate_jacobian_from_parameters(
     parameters: &Corrections,
     measurements: &[Measurement],
     field_dimensions: &FieldDimensions,
+    loss: RobustLoss,
 ) -> Option<Jacobian> {
     let columns = (0..AMOUNT_OF_PARAMETERS)
         .map(|index| {
@@ -28,10 +29,12 @@ pub fn calculate_jacobian_from_parameters(
                     &upper_support_parameters,
                     measurements,
                     field_dimensions,
+                    loss,
                 )? - calculate_residuals_from_parameters(
                     &lower_support_parameters,
                     measurements,
                     field_dimensions,
+                    loss,
                 )?) / (2.0 * EPSILON),
             )
         })
@@ -1,8 +1,14 @@
-use corrections::Corrections;
-use levenberg_marquardt::LevenbergMarquardt;
+use corrections::{Corrections, AMOUNT_OF_PARAMETERS};
+use levenberg_marquardt::{LeastSquaresProblem, LevenbergMarquardt};
 use measurement::Measurement;
+use nalgebra::{SMatrix, SVector};
 use problem::CalibrationProblem;
-use types::field_dimensions::FieldDimensions;
+use residuals::{calculate_residuals_from_parameters, Residual, Residuals};
+use types::{
+    camera_calibration::{CalibrationVerdict, RejectionReason, RobustLoss},
+    field_dimensions::FieldDimensions,
+    parameters::CalibrationAcceptanceParameters,
+};
 
 pub mod corrections;
 pub mod jacobian;
@@ -11,15 +17,269 @@ pub mod measurement;
 pub mod problem;
 pub mod residuals;
 
+#[derive(Clone, Debug)]
+pub struct Solution {
+    pub corrections: Corrections,
+    pub verdict: CalibrationVerdict,
+    pub initial_root_mean_square_residual: Option<f32>,
+    pub root_mean_square_residual: Option<f32>,
+    /// Root mean square of the residuals of each measurement, `None` if its lines do not project
+    pub measurement_residuals: Vec<Option<f32>>,
+    pub number_of_outliers: usize,
+    /// Covariance of the correction angles in radians, ordered like the parameters of [`Corrections`]
+    pub covariance: Option<SMatrix<f32, AMOUNT_OF_PARAMETERS, AMOUNT_OF_PARAMETERS>>,
+    pub number_of_evaluations: usize,
+    pub is_converged: bool,
+}
+
+impl Solution {
+    /// Standard deviations of the correction angles in radians
+    pub fn standard_deviations(&self) -> Option<SVector<f32, AMOUNT_OF_PARAMETERS>> {
+        self.covariance.map(|covariance| {
+            covariance
+                .diagonal()
+                .map(|variance| variance.max(0.0).sqrt())
+        })
+    }
+}
+
+/// Solves for the corrections and judges whether the result is trustworthy
+///
+/// Residuals and outliers are reported without the robust loss so that they stay comparable
+/// between losses.
 pub fn solve(
     initial_corrections: Corrections,
     measurements: Vec<Measurement>,
     field_dimensions: FieldDimensions,
-) -> Corrections {
-    let problem = CalibrationProblem::new(initial_corrections, measurements, field_dimensions);
+    loss: RobustLoss,
+    acceptance: &CalibrationAcceptanceParameters,
+) -> Solution {
+    let initial_root_mean_square_residual = calculate_residuals_from_parameters(
+        &initial_corrections,
+        &measurements,
+        &field_dimensions,
+        RobustLoss::Squared,
+    )
+    .map(|residuals| root_mean_square(residuals.as_slice()));
+    if measurements.len() < acceptance.minimum_number_of_measurements {
+        return Solution {
+            corrections: initial_corrections,
+            verdict: CalibrationVerdict::Rejected {
+                reason: RejectionReason::TooFewMeasurements,
+            },
+            initial_root_mean_square_residual,
+            root_mean_square_residual: initial_root_mean_square_residual,
+            measurement_residuals: measurement_residuals(
+                &initial_corrections,
+                &measurements,
+                &field_dimensions,
+            ),
+            number_of_outliers: 0,
+            covariance: None,
+            number_of_evaluations: 0,
+            is_converged: false,
+        };
+    }
+
+    let problem =
+        CalibrationProblem::new(initial_corrections, measurements, field_dimensions, loss);
     let (result, report) = LevenbergMarquardt::new().minimize(problem);
-    println!("Report: {report:?}");
     let corrections = result.get_corrections();
-    println!("Corrections: {corrections:?}");
-    corrections
+    let root_mean_square_residual = calculate_residuals_from_parameters(
+        &corrections,
+        result.measurements(),
+        result.field_dimensions(),
+        RobustLoss::Squared,
+    )
+    .map(|residuals| root_mean_square(residuals.as_slice()));
+    let measurement_residuals = measurement_residuals(
+        &corrections,
+        result.measurements(),
+        result.field_dimensions(),
+    );
+    let number_of_outliers = measurement_residuals
+        .iter()
+        .filter(|residual| match residual {
+            Some(residual) => *residual > acceptance.outlier_residual_threshold,
+            None => true,
+        })
+        .count();
+    let covariance = result
+        .residuals()
+        .zip(result.jacobian())
+        .and_then(|(residuals, jacobian)| covariance(&residuals, &jacobian));
+    let is_converged = report.termination.was_successful();
+
+    let mut solution = Solution {
+        corrections,
+        verdict: CalibrationVerdict::Accepted,
+        initial_root_mean_square_residual,
+        root_mean_square_residual,
+        measurement_residuals,
+        number_of_outliers,
+        covariance,
+        number_of_evaluations: report.number_of_evaluations,
+        is_converged,
+    };
+    solution.verdict = judge(&solution, acceptance);
+    solution
+}
+
+fn measurement_residuals(
+    corrections: &Corrections,
+    measurements: &[Measurement],
+    field_dimensions: &FieldDimensions,
+) -> Vec<Option<f32>> {
+    measurements
+        .iter()
+        .map(|measurement| {
+            let residuals: Vec<f32> =
+                Residuals::calculate_from(corrections, measurement, field_dimensions)
+                    .ok()?
+                    .into();
+            Some(root_mean_square(&residuals))
+        })
+        .collect()
+}
+
+/// Estimates the covariance from the Jacobian and the residual variance at the solution
+fn covariance(
+    residuals: &Residual,
+    jacobian: &jacobian::Jacobian,
+) -> Option<SMatrix<f32, AMOUNT_OF_PARAMETERS, AMOUNT_OF_PARAMETERS>> {
+    let degrees_of_freedom = residuals.len().checked_sub(AMOUNT_OF_PARAMETERS)?;
+    if degrees_of_freedom == 0 {
+        return None;
+    }
+    let residual_variance = residuals.norm_squared() / degrees_of_freedom as f32;
+    let information: SMatrix<f32, AMOUNT_OF_PARAMETERS, AMOUNT_OF_PARAMETERS> =
+        jacobian.transpose() * jacobian;
+    information
+        .try_inverse()
+        .map(|inverse| inverse * residual_variance)
+}
+
+fn judge(solution: &Solution, acceptance: &CalibrationAcceptanceParameters) -> CalibrationVerdict {
+    let residual_is_too_large = match solution.root_mean_square_residual {
+        Some(residual) => residual > acceptance.maximum_root_mean_square_residual,
+        None => true,
+    };
+    let uncertainty_is_too_large = match solution.standard_deviations() {
+        Some(standard_deviations) => {
+            standard_deviations.max().to_degrees() > acceptance.maximum_standard_deviation
+        }
+        None => true,
+    };
+    let reason = if !solution.is_converged {
+        Some(RejectionReason::NotConverged)
+    } else if residual_is_too_large {
+        Some(RejectionReason::ResidualTooLarge)
+    } else if uncertainty_is_too_large {
+        Some(RejectionReason::UncertaintyTooLarge)
+    } else if solution.number_of_outliers as f32
+        > acceptance.maximum_outlier_ratio * solution.measurement_residuals.len() as f32
+    {
+        Some(RejectionReason::TooManyOutliers)
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => CalibrationVerdict::Rejected { reason },
+        None => CalibrationVerdict::Accepted,
+    }
+}
+
+fn root_mean_square(residuals: &[f32]) -> f32 {
+    if residuals.is_empty() {
+        return 0.0;
+    }
+    (residuals
+        .iter()
+        .map(|residual| residual.powi(2))
+        .sum::<f32>()
+        / residuals.len() as f32)
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::DVector;
+
+    use super::*;
+
+    fn acceptance() -> CalibrationAcceptanceParameters {
+        CalibrationAcceptanceParameters {
+            minimum_number_of_measurements: 2,
+            maximum_root_mean_square_residual: 0.05,
+            maximum_standard_deviation: 1.0,
+            outlier_residual_threshold: 0.1,
+            maximum_outlier_ratio: 0.25,
+        }
+    }
+
+    fn solution(
+        measurement_residuals: Vec<Option<f32>>,
+        number_of_outliers: usize,
+        standard_deviation: f32,
+    ) -> Solution {
+        Solution {
+            corrections: Corrections::default(),
+            verdict: CalibrationVerdict::Accepted,
+            initial_root_mean_square_residual: Some(0.2),
+            root_mean_square_residual: Some(0.02),
+            number_of_outliers,
+            measurement_residuals,
+            covariance: Some(SMatrix::identity() * standard_deviation.powi(2)),
+            number_of_evaluations: 10,
+            is_converged: true,
+        }
+    }
+
+    #[test]
+    fn good_solutions_are_accepted() {
+        let solution = solution(
+            vec![Some(0.01), Some(0.02), Some(0.03), Some(0.2)],
+            1,
+            0.001,
+        );
+
+        assert_eq!(
+            judge(&solution, &acceptance()),
+            CalibrationVerdict::Accepted
+        );
+    }
+
+    #[test]
+    fn uncertain_or_inconsistent_solutions_are_rejected() {
+        let uncertain = solution(vec![Some(0.01), Some(0.02)], 0, 0.1);
+        let inconsistent = solution(vec![Some(0.01), None, Some(0.2)], 2, 0.001);
+
+        assert_eq!(
+            judge(&uncertain, &acceptance()),
+            CalibrationVerdict::Rejected {
+                reason: RejectionReason::UncertaintyTooLarge
+            }
+        );
+        assert_eq!(
+            judge(&inconsistent, &acceptance()),
+            CalibrationVerdict::Rejected {
+                reason: RejectionReason::TooManyOutliers
+            }
+        );
+    }
+
+    #[test]
+    fn covariance_scales_with_residual_variance() {
+        let mut jacobian = jacobian::Jacobian::zeros(20);
+        jacobian
+            .rows_mut(0, AMOUNT_OF_PARAMETERS)
+            .fill_with_identity();
+        let residuals = DVector::from_element(20, 0.1);
+
+        let covariance = covariance(&residuals, &jacobian).unwrap();
+
+        let expected_variance = 20.0 * 0.01 / 11.0;
+        assert!((covariance[(0, 0)] - expected_variance).abs() < 0.0001);
+        assert!(covariance[(0, 1)].abs() < 0.0001);
+    }
 }
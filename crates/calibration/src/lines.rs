@@ -1,11 +1,12 @@
 use nalgebra::Point2;
 use projection::Projection;
+use serde::{Deserialize, Serialize};
 use types::{
     camera_matrix::CameraMatrix,
     line::{Line, Line2},
 };
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Lines {
     pub border_line: Line2,
     pub goal_box_line: Line2,
@@ -13,6 +14,62 @@ pub struct Lines {
 }
 
 impl Lines {
+    /// Identifies the goal line, the goal box line and a line connecting both among lines on the ground
+    ///
+    /// The goal line and the goal box line are the pair of parallel lines whose distance matches
+    /// the goal box area length best, the goal line being the one further away from the robot.
+    /// The connecting line is the longest line orthogonal to them.
+    pub fn from_lines_in_ground(
+        lines_in_ground: &[Line2],
+        goal_box_area_length: f32,
+        maximum_angle_deviation: f32,
+        maximum_distance_deviation: f32,
+    ) -> Option<Self> {
+        let (border_line, goal_box_line) = lines_in_ground
+            .iter()
+            .flat_map(|first| lines_in_ground.iter().map(move |second| (first, second)))
+            .filter(|(first, second)| {
+                first.distance_to_point(Point2::origin())
+                    > second.distance_to_point(Point2::origin())
+                    && first.signed_acute_angle(**second).abs() <= maximum_angle_deviation
+            })
+            .map(|(first, second)| {
+                let distance_deviation =
+                    (first.distance_to_point(second.center()) - goal_box_area_length).abs();
+                (first, second, distance_deviation)
+            })
+            .filter(|(_, _, distance_deviation)| *distance_deviation <= maximum_distance_deviation)
+            .min_by(|(_, _, left), (_, _, right)| left.total_cmp(right))
+            .map(|(first, second, _)| (*first, *second))?;
+        let connecting_line = lines_in_ground
+            .iter()
+            .filter(|line| {
+                border_line.signed_acute_angle_to_orthogonal(**line).abs()
+                    <= maximum_angle_deviation
+            })
+            .max_by(|left, right| left.length().total_cmp(&right.length()))
+            .copied()?;
+        Some(Self {
+            border_line,
+            goal_box_line,
+            connecting_line,
+        })
+    }
+
+    pub fn to_image(&self, matrix: &CameraMatrix) -> Option<Self> {
+        let project = |line: Line2| {
+            Some(Line(
+                matrix.ground_to_pixel(line.0).ok()?,
+                matrix.ground_to_pixel(line.1).ok()?,
+            ))
+        };
+        Some(Self {
+            border_line: project(self.border_line)?,
+            goal_box_line: project(self.goal_box_line)?,
+            connecting_line: project(self.connecting_line)?,
+        })
+    }
+
     pub fn to_projected(&self, matrix: &CameraMatrix) -> Result<Self, LinesError> {
         Ok(Lines {
             border_line: project_line_and_map_error(matrix, self.border_line, "border line")?,
@@ -55,3 +112,53 @@ fn project_point_and_map_error(
         .pixel_to_ground(point)
         .map_err(|source| LinesError::NotProjected { source, which })
 }
+
+#[cfg(test)]
+mod tests {
+    use nalgebra::point;
+
+    use super::*;
+
+    #[test]
+    fn goal_box_lines_are_identified() {
+        let border_line = Line(point![2.0, -1.5], point![2.0, 1.5]);
+        let goal_box_line = Line(point![1.4, -1.1], point![1.4, 1.1]);
+        let connecting_line = Line(point![1.4, 1.1], point![2.0, 1.1]);
+        let short_connecting_line = Line(point![1.5, -1.1], point![1.9, -1.1]);
+        let penalty_area_line = Line(point![0.35, -1.0], point![0.35, 1.0]);
+
+        let lines = Lines::from_lines_in_ground(
+            &[
+                penalty_area_line,
+                short_connecting_line,
+                goal_box_line,
+                connecting_line,
+                border_line,
+            ],
+            0.6,
+            0.1,
+            0.1,
+        )
+        .unwrap();
+
+        assert_eq!(lines.border_line.0, border_line.0);
+        assert_eq!(lines.goal_box_line.0, goal_box_line.0);
+        assert_eq!(lines.connecting_line.0, connecting_line.0);
+    }
+
+    #[test]
+    fn lines_without_goal_box_are_rejected() {
+        let lines = Lines::from_lines_in_ground(
+            &[
+                Line(point![2.0, -1.5], point![2.0, 1.5]),
+                Line(point![0.35, -1.0], point![0.35, 1.0]),
+                Line(point![0.35, 1.0], point![2.0, 1.0]),
+            ],
+            0.6,
+            0.1,
+            0.1,
+        );
+
+        assert!(lines.is_none());
+    }
+}
@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use types::{camera_matrix::CameraMatrix, camera_position::CameraPosition};
 
 use crate::lines::Lines;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Measurement {
     pub position: CameraPosition,
     pub matrix: CameraMatrix,
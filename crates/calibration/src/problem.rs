@@ -1,6 +1,6 @@
 use levenberg_marquardt::LeastSquaresProblem;
 use nalgebra::{Const, Dyn, Owned, SVector};
-use types::field_dimensions::FieldDimensions;
+use types::{camera_calibration::RobustLoss, field_dimensions::FieldDimensions};
 
 use crate::{
     corrections::{Corrections, AMOUNT_OF_PARAMETERS},
@@ -13,6 +13,7 @@ pub struct CalibrationProblem {
     parameters: Corrections,
     measurements: Vec<Measurement>,
     field_dimensions: FieldDimensions,
+    loss: RobustLoss,
 }
 
 impl CalibrationProblem {
@@ -20,17 +21,27 @@ impl CalibrationProblem {
         initial_corrections: Corrections,
         measurements: Vec<Measurement>,
         field_dimensions: FieldDimensions,
+        loss: RobustLoss,
     ) -> Self {
         Self {
             parameters: initial_corrections,
             measurements,
             field_dimensions,
+            loss,
         }
     }
 
     pub fn get_corrections(&self) -> Corrections {
         self.parameters
     }
+
+    pub fn measurements(&self) -> &[Measurement] {
+        &self.measurements
+    }
+
+    pub fn field_dimensions(&self) -> &FieldDimensions {
+        &self.field_dimensions
+    }
 }
 
 impl LeastSquaresProblem<f32, Dyn, Const<AMOUNT_OF_PARAMETERS>> for CalibrationProblem {
@@ -39,30 +50,28 @@ impl LeastSquaresProblem<f32, Dyn, Const<AMOUNT_OF_PARAMETERS>> for CalibrationP
     type ParameterStorage = Owned<f32, Const<AMOUNT_OF_PARAMETERS>>;
 
     fn set_params(&mut self, parameters: &SVector<f32, AMOUNT_OF_PARAMETERS>) {
-        println!("set_params({parameters:?})");
         self.parameters = parameters.into();
     }
 
     fn params(&self) -> SVector<f32, AMOUNT_OF_PARAMETERS> {
-        println!("params()");
         (&self.parameters).into()
     }
 
     fn residuals(&self) -> Option<Residual> {
-        println!("residuals()");
         calculate_residuals_from_parameters(
             &self.parameters,
             &self.measurements,
             &self.field_dimensions,
+            self.loss,
         )
     }
 
     fn jacobian(&self) -> Option<Jacobian> {
-        println!("jacobian()");
         calculate_jacobian_from_parameters(
             &self.parameters,
             &self.measurements,
             &self.field_dimensions,
+            self.loss,
         )
     }
 }
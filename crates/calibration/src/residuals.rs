@@ -1,5 +1,8 @@
 use nalgebra::{DVector, Dyn, Owned, Vector};
-use types::{camera_position::CameraPosition, field_dimensions::FieldDimensions};
+use types::{
+    camera_calibration::RobustLoss, camera_position::CameraPosition,
+    field_dimensions::FieldDimensions,
+};
 
 use crate::{corrections::Corrections, lines::LinesError, measurement::Measurement};
 
@@ -10,6 +13,7 @@ pub fn calculate_residuals_from_parameters(
     parameters: &Corrections,
     measurements: &[Measurement],
     field_dimensions: &FieldDimensions,
+    loss: RobustLoss,
 ) -> Option<Residual> {
     let mut residuals = Vec::new();
     for measurement in measurements {
@@ -17,7 +21,11 @@ pub fn calculate_residuals_from_parameters(
             Residuals::calculate_from(parameters, measurement, field_dimensions)
                 .ok()?
                 .into();
-        residuals.extend(residuals_part);
+        residuals.extend(
+            residuals_part
+                .into_iter()
+                .map(|residual| loss.apply(residual)),
+        );
     }
 
     Some(DVector::from_vec(residuals))
@@ -54,7 +62,7 @@ impl Residuals {
             .border_line
             .signed_acute_angle_to_orthogonal(projected_lines.connecting_line);
         let connecting_to_goal_box_angle = projected_lines
-            .border_line
+            .goal_box_line
             .signed_acute_angle_to_orthogonal(projected_lines.connecting_line);
         let distance_between_parallel_line_start_points = projected_lines
             .border_line
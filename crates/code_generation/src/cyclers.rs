@@ -1,4 +1,4 @@
-use std::{collections::HashSet, iter::once};
+use std::iter::once;
 
 use convert_case::{Case, Casing};
 use itertools::Itertools;
@@ -17,6 +17,7 @@ pub fn generate_cyclers(cyclers: &Cyclers) -> TokenStream {
         let instance_name = format_ident!("{}", instance);
         quote! {
             #instance_name {
+                timestamp: std::time::SystemTime,
                 data: std::vec::Vec<u8>,
             },
         }
@@ -76,6 +77,7 @@ fn generate_database_struct() -> TokenStream {
         pub(crate) struct Database {
             pub main_outputs: MainOutputs,
             pub additional_outputs: AdditionalOutputs,
+            pub cycle_timings: framework::CycleTimings,
         }
     }
 }
@@ -106,6 +108,7 @@ fn generate_struct(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
             #realtime_inputs
             #input_output_fields
             #node_fields
+            cycle_timings: framework::CycleTimings,
             recording_sender: std::sync::mpsc::SyncSender<crate::cyclers::RecordingFrame>,
             enable_recording: bool,
         }
@@ -179,6 +182,7 @@ fn generate_implementation(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
     let new_method = generate_new_method(cycler, cyclers);
     let start_method = generate_start_method();
     let cycle_method = generate_cycle_method(cycler, cyclers);
+    let replay_method = generate_replay_method(cycler);
 
     quote! {
         impl<HardwareInterface> Cycler<HardwareInterface>
@@ -188,6 +192,7 @@ fn generate_implementation(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
             #new_method
             #start_method
             #cycle_method
+            #replay_method
         }
     }
 }
@@ -200,6 +205,7 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
         .iter_nodes()
         .map(|node| format_ident!("{}", node.name.to_case(Case::Snake)));
     let input_output_identifiers = generate_input_output_identifiers(cycler, cyclers);
+    let node_names = cycler.iter_nodes().map(|node| &node.name);
 
     quote! {
         pub(crate) fn new(
@@ -226,6 +232,7 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                 cycler_state,
                 #input_output_identifiers
                 #(#node_identifiers,)*
+                cycle_timings: framework::CycleTimings::new(&[#(#node_names,)*]),
                 recording_sender,
                 enable_recording,
             })
@@ -368,14 +375,22 @@ fn generate_start_method() -> TokenStream {
 }
 
 fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
-    let setup_node_executions = cycler
-        .setup_nodes
-        .iter()
-        .map(|node| generate_node_execution(node, cycler, RecordingGeneration::Generate));
-    let cycle_node_executions = cycler
-        .cycle_nodes
-        .iter()
-        .map(|node| generate_node_execution(node, cycler, RecordingGeneration::Skip));
+    let setup_node_executions = cycler.setup_nodes.iter().map(|node| {
+        generate_node_execution(
+            node,
+            cycler,
+            RecordingGeneration::Generate,
+            CrossInputSource::Databases,
+        )
+    });
+    let cycle_node_executions = cycler.cycle_nodes.iter().map(|node| {
+        generate_node_execution(
+            node,
+            cycler,
+            RecordingGeneration::Skip,
+            CrossInputSource::Databases,
+        )
+    });
     let cross_inputs = get_cross_inputs(cycler);
     let cross_input_recordings = generate_cross_inputs_recording(cycler, cross_inputs);
 
@@ -423,10 +438,11 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
             );
         },
     };
+    let cycle_timings_update = generate_cycle_timings_update(cycler);
     let recording_variants = cycler.instances.iter().map(|instance| {
         let instance_name = format_ident!("{}", instance);
         quote! {
-            CyclerInstance::#instance_name => crate::cyclers::RecordingFrame::#instance_name { data: recording_frame },
+            CyclerInstance::#instance_name => crate::cyclers::RecordingFrame::#instance_name { timestamp, data: recording_frame },
         }
     });
 
@@ -446,6 +462,7 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
 
                 let enable_recording = self.enable_recording && self.hardware_interface.should_record();
                 let mut recording_frame = Vec::new(); // TODO: possible optimization: cache capacity
+                self.cycle_timings.begin_cycle();
 
                 {
                     let own_subscribed_outputs = self.own_subscribed_outputs_reader.next();
@@ -454,6 +471,7 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                 }
 
                 #post_setup
+                let cycle_start = std::time::Instant::now();
 
                 {
                     let own_subscribed_outputs = self.own_subscribed_outputs_reader.next();
@@ -461,11 +479,13 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
                     #lock_readers
                     #cross_input_recordings
                     #(#cycle_node_executions)*
+                    #cycle_timings_update
                 }
 
                 #after_remaining_nodes
 
                 if enable_recording {
+                    let timestamp = <HardwareInterface as hardware::TimeInterface>::get_now(&*self.hardware_interface);
                     self.recording_sender.try_send(match instance {
                         #(#recording_variants)*
                     }).wrap_err("failed to send recording frame")?;
@@ -477,7 +497,190 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
     }
 }
 
-fn get_cross_inputs(cycler: &Cycler) -> HashSet<Field> {
+fn generate_replay_method(cycler: &Cycler) -> TokenStream {
+    let setup_node_replays = cycler
+        .setup_nodes
+        .iter()
+        .map(|node| generate_setup_node_replay(node, cycler));
+    let cross_inputs = get_cross_inputs(cycler);
+    let cross_input_replays = generate_cross_inputs_replay(cycler, &cross_inputs);
+    let cycle_node_executions = cycler.cycle_nodes.iter().map(|node| {
+        generate_node_execution(
+            node,
+            cycler,
+            RecordingGeneration::Skip,
+            CrossInputSource::Recording(&cross_inputs),
+        )
+    });
+
+    let post_setup = match cycler.kind {
+        CyclerKind::Perception => quote! {},
+        CyclerKind::RealTime => quote! {
+            let now: std::time::SystemTime = bincode::deserialize_from(&mut frame).wrap_err("failed to replay time")?;
+        },
+    };
+    let cycle_timings_update = generate_cycle_timings_update(cycler);
+    let after_remaining_nodes = match cycler.kind {
+        CyclerKind::Perception => quote! {},
+        CyclerKind::RealTime => quote! {
+            self.historic_databases.update(
+                now,
+                self.perception_databases
+                    .get_first_timestamp_of_temporary_databases(),
+                &own_database_reference.main_outputs,
+            );
+        },
+    };
+
+    quote! {
+        #[allow(clippy::nonminimal_bool)]
+        pub(crate) fn replay(&mut self, mut frame: &[u8]) -> color_eyre::Result<()> {
+            {
+                let instance = self.instance;
+                let instance_name = format!("{instance:?}");
+                let itt_domain = ittapi::Domain::new(&instance_name);
+
+                let mut own_database = self.own_writer.next();
+                let own_database_reference = {
+                    use std::ops::DerefMut;
+                    own_database.deref_mut()
+                };
+                self.cycle_timings.begin_cycle();
+
+                {
+                    let own_subscribed_outputs = self.own_subscribed_outputs_reader.next();
+                    let parameters = self.parameters_reader.next();
+                    #(#setup_node_replays)*
+                }
+
+                #post_setup
+                let cycle_start = std::time::Instant::now();
+
+                {
+                    let own_subscribed_outputs = self.own_subscribed_outputs_reader.next();
+                    let parameters = self.parameters_reader.next();
+                    #cross_input_replays
+                    #(#cycle_node_executions)*
+                    #cycle_timings_update
+                }
+
+                #after_remaining_nodes
+            }
+            self.own_changed.notify_one();
+            Ok(())
+        }
+    }
+}
+
+fn generate_cycle_timings_update(cycler: &Cycler) -> TokenStream {
+    let cycler_module_name = format_ident!("{}", cycler.name.to_case(Case::Snake));
+    quote! {
+        self.cycle_timings.finish_cycle(cycle_start.elapsed(), &parameters.cycle_timing.#cycler_module_name);
+        if own_subscribed_outputs
+            .iter()
+            .any(|subscribed_output| framework::should_be_filled(subscribed_output, "cycle_timings"))
+        {
+            own_database_reference.cycle_timings.clone_from(&self.cycle_timings);
+        }
+    }
+}
+
+fn generate_setup_node_replay(node: &Node, cycler: &Cycler) -> TokenStream {
+    let are_required_inputs_some =
+        generate_required_input_condition(node, cycler, CrossInputSource::Databases);
+    let node_member = format_ident!("{}", node.name.to_case(Case::Snake));
+    let replay_error_message = format!("failed to replay `{}`", node.name);
+    let main_output_replays = node.contexts.main_outputs.iter().filter_map(|field| match field {
+        Field::MainOutput { name, .. } => {
+            let error_message = format!("failed to replay {name}");
+            Some(quote! {
+                own_database_reference.main_outputs.#name = bincode::deserialize_from(&mut frame).wrap_err(#error_message)?;
+            })
+        }
+        _ => None,
+    });
+    let node_state_replay = node.has_replayable_state.then(|| {
+        quote! {
+            self.#node_member = bincode::deserialize_from(&mut frame).wrap_err(#replay_error_message)?;
+        }
+    });
+    let database_updates_from_defaults = generate_database_updates_from_defaults(node);
+    quote! {
+        {
+            #node_state_replay
+            #[allow(clippy::needless_else)]
+            if #are_required_inputs_some {
+                #(#main_output_replays)*
+            }
+            else {
+                #database_updates_from_defaults
+            }
+        }
+    }
+}
+
+fn generate_cross_inputs_replay(cycler: &Cycler, cross_inputs: &[Field]) -> TokenStream {
+    cross_inputs
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let identifier = replayed_cross_input_identifier(index);
+            match field {
+                Field::CyclerState {
+                    data_type,
+                    name,
+                    path,
+                } => {
+                    let error_message = format!("failed to replay cycler state {name}");
+                    let accessor = path_to_accessor_token_stream(
+                        quote! { self.cycler_state },
+                        path,
+                        ReferenceKind::Mutable,
+                        cycler,
+                    );
+                    quote! {
+                        let #identifier: #data_type = bincode::deserialize_from(&mut frame).wrap_err(#error_message)?;
+                        *#accessor = #identifier;
+                    }
+                }
+                Field::Input {
+                    cycler_instance: Some(_),
+                    data_type,
+                    name,
+                    ..
+                } => {
+                    let error_message = format!("failed to replay input {name}");
+                    quote! {
+                        let #identifier: #data_type = bincode::deserialize_from(&mut frame).wrap_err(#error_message)?;
+                    }
+                }
+                Field::PerceptionInput {
+                    data_type, name, ..
+                } => {
+                    let error_message = format!("failed to replay perception input {name}");
+                    quote! {
+                        let #identifier: [std::collections::BTreeMap<std::time::SystemTime, std::vec::Vec<#data_type>>; 2] =
+                            bincode::deserialize_from(&mut frame).wrap_err(#error_message)?;
+                    }
+                }
+                Field::RequiredInput {
+                    cycler_instance: Some(_),
+                    data_type,
+                    name,
+                    ..
+                } => {
+                    let error_message = format!("failed to replay required input {name}");
+                    quote! {
+                        let #identifier: #data_type = bincode::deserialize_from(&mut frame).wrap_err(#error_message)?;
+                    }
+                }
+                _ => panic!("unexpected field {field:?}"),
+            }
+        })
+        .collect()
+}
+
+fn get_cross_inputs(cycler: &Cycler) -> Vec<Field> {
     cycler
         .setup_nodes
         .iter()
@@ -503,6 +706,7 @@ fn get_cross_inputs(cycler: &Cycler) -> HashSet<Field> {
                 })
                 .cloned()
         })
+        .unique()
         .collect()
 }
 
@@ -601,7 +805,7 @@ fn generate_cross_inputs_recording(
                     cycler,
                 );
                 quote! {
-                    &#accessor .unwrap()
+                    &#accessor
                 }
             }
             _ => panic!("unexpected field {field:?}"),
@@ -639,31 +843,60 @@ fn generate_node_execution(
     node: &Node,
     cycler: &Cycler,
     recording_generation: RecordingGeneration,
+    cross_input_source: CrossInputSource,
 ) -> TokenStream {
-    let are_required_inputs_some = generate_required_input_condition(node, cycler);
+    let are_required_inputs_some =
+        generate_required_input_condition(node, cycler, cross_input_source);
     let node_name = &node.name;
     let node_module = &node.module;
     let node_member = format_ident!("{}", node.name.to_case(Case::Snake));
-    let context_initializers = generate_context_initializers(node, cycler);
-    let recording_error_message = format!("failed to record `{}`", node.name);
+    let node_index = cycler
+        .iter_nodes()
+        .position(|cycler_node| cycler_node.name == node.name)
+        .expect("node is not part of its cycler");
+    let cycler_module_name = format_ident!("{}", cycler.name.to_case(Case::Snake));
+    let context_initializers = generate_context_initializers(node, cycler, cross_input_source);
+    let node_state_handling = match cross_input_source {
+        // state that cannot be deserialized is neither recorded nor replayed, the replayed node keeps its own
+        _ if !node.has_replayable_state => quote! {},
+        CrossInputSource::Databases => {
+            let recording_error_message = format!("failed to record `{}`", node.name);
+            quote! {
+                if enable_recording {
+                    bincode::serialize_into(&mut recording_frame, &self.#node_member).wrap_err(#recording_error_message)?;
+                }
+            }
+        }
+        CrossInputSource::Recording(..) => {
+            let replay_error_message = format!("failed to replay `{}`", node.name);
+            quote! {
+                self.#node_member = bincode::deserialize_from(&mut frame).wrap_err(#replay_error_message)?;
+            }
+        }
+    };
     let cycle_error_message = format!("failed to execute cycle of `{}`", node.name);
     let database_updates = generate_database_updates(node, recording_generation);
     let database_updates_from_defaults = generate_database_updates_from_defaults(node);
     quote! {
         {
-            if enable_recording {
-                bincode::serialize_into(&mut recording_frame, &self.#node_member).wrap_err(#recording_error_message)?;
-            }
+            #node_state_handling
             #[allow(clippy::needless_else)]
             if #are_required_inputs_some {
                 let main_outputs = {
                     let _task = ittapi::Task::begin(&itt_domain, #node_name);
-                    self.#node_member.cycle(
+                    let node_start = std::time::Instant::now();
+                    let main_outputs = self.#node_member.cycle(
                         #node_module::CycleContext::new(
                             #context_initializers
                         ),
                     )
-                    .wrap_err(#cycle_error_message)?
+                    .wrap_err(#cycle_error_message)?;
+                    self.cycle_timings.record_node(
+                        #node_index,
+                        node_start.elapsed(),
+                        &parameters.cycle_timing.#cycler_module_name,
+                    );
+                    main_outputs
                 };
                 #database_updates
             }
@@ -679,7 +912,34 @@ enum RecordingGeneration {
     Skip,
 }
 
-fn generate_required_input_condition(node: &Node, cycler: &Cycler) -> TokenStream {
+/// Where cross inputs (inputs from other cyclers and cycler state) are taken from
+#[derive(Clone, Copy)]
+enum CrossInputSource<'cross_inputs> {
+    Databases,
+    Recording(&'cross_inputs [Field]),
+}
+
+impl CrossInputSource<'_> {
+    fn replayed_identifier(&self, field: &Field) -> Option<Ident> {
+        match self {
+            CrossInputSource::Databases => None,
+            CrossInputSource::Recording(cross_inputs) => cross_inputs
+                .iter()
+                .position(|cross_input| cross_input == field)
+                .map(replayed_cross_input_identifier),
+        }
+    }
+}
+
+fn replayed_cross_input_identifier(index: usize) -> Ident {
+    format_ident!("replayed_cross_input_{index}")
+}
+
+fn generate_required_input_condition(
+    node: &Node,
+    cycler: &Cycler,
+    cross_input_source: CrossInputSource,
+) -> TokenStream {
     let conditions = node
         .contexts
         .cycle_context
@@ -690,6 +950,11 @@ fn generate_required_input_condition(node: &Node, cycler: &Cycler) -> TokenStrea
                 path,
                 ..
             } => {
+                if let Some(identifier) = cross_input_source.replayed_identifier(field) {
+                    return Some(quote! {
+                        #identifier .is_some()
+                    });
+                }
                 let database_prefix = match cycler_instance {
                     Some(cycler_instance) => {
                         let identifier =
@@ -718,186 +983,237 @@ fn generate_required_input_condition(node: &Node, cycler: &Cycler) -> TokenStrea
     }
 }
 
-fn generate_context_initializers(node: &Node, cycler: &Cycler) -> TokenStream {
-    let initializers = node
-            .contexts
-            .cycle_context
-            .iter()
-            .map(|field| match field {
-                Field::AdditionalOutput {  path, .. } => {
-                    let accessor = path_to_accessor_token_stream(
-                        quote!{ own_database_reference.additional_outputs },
-                        path,
-                        ReferenceKind::Mutable,
-                        cycler,
-                    );
-                    let path_string = once("additional_outputs").chain(
-                            path.segments.iter().map(|segment| segment.name.as_str())
-                        ).join(".");
-                    quote! {
-                        framework::AdditionalOutput::new(
-                            own_subscribed_outputs
-                                .iter()
-                                .any(|subscribed_output| framework::should_be_filled(subscribed_output, #path_string)),
-                            #accessor,
-                        )
-                    }
-                }
-                Field::CyclerState { path, .. } => {
-                    let accessor = path_to_accessor_token_stream(
-                        quote! { self.cycler_state },
-                        path,
-                        ReferenceKind::Mutable,
-                        cycler,
-                    );
-                    quote! {
-                        #accessor
-                    }
-                }
-                Field::HardwareInterface { .. } => quote! {
-                    &self.hardware_interface
-                },
-                Field::HistoricInput { path, .. } => {
-                    let now_accessor = path_to_accessor_token_stream(
-                        quote!{ own_database_reference.main_outputs },
-                        path,
-                        ReferenceKind::Immutable,
-                        cycler,
-                    );
-                    let historic_accessor = path_to_accessor_token_stream(
-                        quote!{ database },
-                        path,
-                        ReferenceKind::Immutable,
-                        cycler,
-                    );
-                    quote! {
-                        [(now, #now_accessor)]
-                            .into_iter()
-                            .chain(
-                                self
-                                    .historic_databases
-                                    .databases
-                                    .iter()
-                                    .map(|(system_time, database)| (
-                                        *system_time,
-                                        #historic_accessor,
-                                    ))
-                            )
-                            .collect::<std::collections::BTreeMap<_, _>>()
-                            .into()
-                    }
+fn generate_context_initializers(
+    node: &Node,
+    cycler: &Cycler,
+    cross_input_source: CrossInputSource,
+) -> TokenStream {
+    let initializers = node.contexts.cycle_context.iter().map(|field| {
+        cross_input_source
+            .replayed_identifier(field)
+            .and_then(|identifier| generate_replayed_context_initializer(field, &identifier))
+            .unwrap_or_else(|| generate_context_initializer(field, cycler))
+    });
+    quote! {
+        #(#initializers,)*
+    }
+}
+
+fn generate_replayed_context_initializer(field: &Field, identifier: &Ident) -> Option<TokenStream> {
+    match field {
+        Field::Input { path, .. } => {
+            if path.contains_optional() {
+                Some(quote! { #identifier.as_ref() })
+            } else {
+                Some(quote! { &#identifier })
+            }
+        }
+        Field::PerceptionInput { path, .. } => {
+            let reference = if path.contains_optional() {
+                quote! { value.as_ref() }
+            } else {
+                quote! { value }
+            };
+            Some(quote! {
+                framework::PerceptionInput {
+                    persistent: #identifier[0]
+                        .iter()
+                        .map(|(system_time, values)| (
+                            *system_time,
+                            values.iter().map(|value| #reference).collect(),
+                        ))
+                        .collect(),
+                    temporary: #identifier[1]
+                        .iter()
+                        .map(|(system_time, values)| (
+                            *system_time,
+                            values.iter().map(|value| #reference).collect(),
+                        ))
+                        .collect(),
                 }
-                Field::Input {
-                    cycler_instance,
-                    path,
-                    ..
-                } => {
-                    let database_prefix = match cycler_instance {
-                        Some(cycler_instance) => {
-                            let identifier =
-                                format_ident!("{}_database", cycler_instance.to_case(Case::Snake));
-                            quote! { #identifier.main_outputs }
-                        }
-                        None => {
-                            quote! { own_database_reference.main_outputs }
-                        }
-                    };
-                    let accessor = path_to_accessor_token_stream(
-                        database_prefix,
-                        path,
-                        ReferenceKind::Immutable,
-                        cycler,
-                    );
-                    quote! {
-                        #accessor
-                    }
+            })
+        }
+        Field::RequiredInput { .. } => Some(quote! {
+            #identifier.as_ref().unwrap()
+        }),
+        // cycler states are replayed into the cycler state itself
+        _ => None,
+    }
+}
+
+fn generate_context_initializer(field: &Field, cycler: &Cycler) -> TokenStream {
+    match field {
+        Field::AdditionalOutput { path, .. } => {
+            let accessor = path_to_accessor_token_stream(
+                quote! { own_database_reference.additional_outputs },
+                path,
+                ReferenceKind::Mutable,
+                cycler,
+            );
+            let path_string = once("additional_outputs")
+                .chain(path.segments.iter().map(|segment| segment.name.as_str()))
+                .join(".");
+            quote! {
+                framework::AdditionalOutput::new(
+                    own_subscribed_outputs
+                        .iter()
+                        .any(|subscribed_output| framework::should_be_filled(subscribed_output, #path_string)),
+                    #accessor,
+                )
+            }
+        }
+        Field::CyclerState { path, .. } => {
+            let accessor = path_to_accessor_token_stream(
+                quote! { self.cycler_state },
+                path,
+                ReferenceKind::Mutable,
+                cycler,
+            );
+            quote! {
+                #accessor
+            }
+        }
+        Field::HardwareInterface { .. } => quote! {
+            &self.hardware_interface
+        },
+        Field::HistoricInput { path, .. } => {
+            let now_accessor = path_to_accessor_token_stream(
+                quote! { own_database_reference.main_outputs },
+                path,
+                ReferenceKind::Immutable,
+                cycler,
+            );
+            let historic_accessor = path_to_accessor_token_stream(
+                quote! { database },
+                path,
+                ReferenceKind::Immutable,
+                cycler,
+            );
+            quote! {
+                [(now, #now_accessor)]
+                    .into_iter()
+                    .chain(
+                        self
+                            .historic_databases
+                            .databases
+                            .iter()
+                            .map(|(system_time, database)| (
+                                *system_time,
+                                #historic_accessor,
+                            ))
+                    )
+                    .collect::<std::collections::BTreeMap<_, _>>()
+                    .into()
+            }
+        }
+        Field::Input {
+            cycler_instance,
+            path,
+            ..
+        } => {
+            let database_prefix = match cycler_instance {
+                Some(cycler_instance) => {
+                    let identifier =
+                        format_ident!("{}_database", cycler_instance.to_case(Case::Snake));
+                    quote! { #identifier.main_outputs }
                 }
-                Field::MainOutput { name, .. } => {
-                    panic!("unexpected MainOutput `{name}` in cycle context")
+                None => {
+                    quote! { own_database_reference.main_outputs }
                 }
-                Field::Parameter { path, .. } => {
-                    let accessor = path_to_accessor_token_stream(
-                        quote! { parameters },
-                        path,
-                        ReferenceKind::Immutable,
-                        cycler,
-                    );
-                    quote! {
-                        #accessor
-                    }
+            };
+            let accessor = path_to_accessor_token_stream(
+                database_prefix,
+                path,
+                ReferenceKind::Immutable,
+                cycler,
+            );
+            quote! {
+                #accessor
+            }
+        }
+        Field::MainOutput { name, .. } => {
+            panic!("unexpected MainOutput `{name}` in cycle context")
+        }
+        Field::Parameter { path, .. } => {
+            let accessor = path_to_accessor_token_stream(
+                quote! { parameters },
+                path,
+                ReferenceKind::Immutable,
+                cycler,
+            );
+            quote! {
+                #accessor
+            }
+        }
+        Field::PerceptionInput {
+            cycler_instance,
+            path,
+            ..
+        } => {
+            let cycler_instance_identifier =
+                format_ident!("{}", cycler_instance.to_case(Case::Snake));
+            let accessor = path_to_accessor_token_stream(
+                quote! { database },
+                path,
+                ReferenceKind::Immutable,
+                cycler,
+            );
+            quote! {
+                framework::PerceptionInput {
+                    persistent: self
+                        .perception_databases
+                        .persistent()
+                        .map(|(system_time, databases)| (
+                            *system_time,
+                            databases
+                                .#cycler_instance_identifier
+                                .iter()
+                                .map(|database| #accessor)
+                                .collect()
+                            ,
+                        ))
+                        .collect(),
+                    temporary: self
+                        .perception_databases
+                        .temporary()
+                        .map(|(system_time, databases)| (
+                            *system_time,
+                            databases
+                                .#cycler_instance_identifier
+                                .iter()
+                                .map(|database| #accessor)
+                                .collect()
+                            ,
+                        ))
+                        .collect(),
                 }
-                Field::PerceptionInput {
-                    cycler_instance,
-                    path,
-                    ..
-                } => {
-                    let cycler_instance_identifier =
-                        format_ident!("{}", cycler_instance.to_case(Case::Snake));
-                    let accessor = path_to_accessor_token_stream(
-                        quote! { database },
-                        path,
-                        ReferenceKind::Immutable,
-                        cycler,
-                    );
-                    quote! {
-                        framework::PerceptionInput {
-                            persistent: self
-                                .perception_databases
-                                .persistent()
-                                .map(|(system_time, databases)| (
-                                    *system_time,
-                                    databases
-                                        .#cycler_instance_identifier
-                                        .iter()
-                                        .map(|database| #accessor)
-                                        .collect()
-                                    ,
-                                ))
-                                .collect(),
-                            temporary: self
-                                .perception_databases
-                                .temporary()
-                                .map(|(system_time, databases)| (
-                                    *system_time,
-                                    databases
-                                        .#cycler_instance_identifier
-                                        .iter()
-                                        .map(|database| #accessor)
-                                        .collect()
-                                    ,
-                                ))
-                                .collect(),
-                        }
-                    }
+            }
+        }
+        Field::RequiredInput {
+            cycler_instance,
+            path,
+            ..
+        } => {
+            let database_prefix = match cycler_instance {
+                Some(cycler_instance) => {
+                    let identifier =
+                        format_ident!("{}_database", cycler_instance.to_case(Case::Snake));
+                    quote! { #identifier.main_outputs }
                 }
-                Field::RequiredInput {
-                    cycler_instance,
-                    path,
-                    ..
-                } => {
-                    let database_prefix = match cycler_instance {
-                        Some(cycler_instance) => {
-                            let identifier =
-                                format_ident!("{}_database", cycler_instance.to_case(Case::Snake));
-                            quote! { #identifier.main_outputs }
-                        }
-                        None => {
-                            quote! { own_database_reference.main_outputs }
-                        }
-                    };
-                    let accessor = path_to_accessor_token_stream(
-                        database_prefix,
-                        path,
-                        ReferenceKind::Immutable,
-                        cycler,
-                    );
-                    quote! {
-                        #accessor .unwrap()
-                    }
+                None => {
+                    quote! { own_database_reference.main_outputs }
                 }
-            });
-    quote! {
-        #(#initializers,)*
+            };
+            let accessor = path_to_accessor_token_stream(
+                database_prefix,
+                path,
+                ReferenceKind::Immutable,
+                cycler,
+            );
+            quote! {
+                #accessor .unwrap()
+            }
+        }
     }
 }
 
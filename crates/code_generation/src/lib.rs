@@ -2,6 +2,7 @@ use cyclers::generate_cyclers;
 use perception_databases::generate_perception_databases;
 use proc_macro2::TokenStream;
 use quote::quote;
+use replayer::generate_replayer_struct;
 use run::generate_run_function;
 use source_analyzer::{cyclers::Cyclers, structs::Structs};
 use structs::generate_structs;
@@ -9,6 +10,7 @@ use structs::generate_structs;
 mod accessor;
 pub mod cyclers;
 pub mod perception_databases;
+pub mod replayer;
 pub mod run;
 pub mod structs;
 pub mod write_to_file;
@@ -18,6 +20,7 @@ pub fn generate(cyclers: &Cyclers, structs: &Structs) -> TokenStream {
     let generated_run = generate_run_function(cyclers);
     let generated_structs = generate_structs(structs);
     let generated_perception_databases = generate_perception_databases(cyclers);
+    let generated_replayer = generate_replayer_struct(cyclers);
 
     quote! {
         mod cyclers {
@@ -32,5 +35,8 @@ pub fn generate(cyclers: &Cyclers, structs: &Structs) -> TokenStream {
         mod perception_databases {
             #generated_perception_databases
         }
+        pub mod replayer {
+            #generated_replayer
+        }
     }
 }
@@ -0,0 +1,115 @@
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use source_analyzer::cyclers::Cyclers;
+
+use crate::run::{generate_cycler_constructors, generate_future_queues, generate_multiple_buffers};
+
+pub fn generate_replayer_struct(cyclers: &Cyclers) -> TokenStream {
+    let cycler_fields = cyclers.instances().map(|(cycler, instance)| {
+        let cycler_variable_identifier =
+            format_ident!("{}_cycler", instance.to_case(Case::Snake));
+        let cycler_module_name = format_ident!("{}", cycler.name.to_case(Case::Snake));
+        quote! {
+            #cycler_variable_identifier: crate::cyclers::#cycler_module_name::Cycler<HardwareInterface>,
+        }
+    });
+    let cycler_identifiers = cyclers
+        .instances()
+        .map(|(_cycler, instance)| format_ident!("{}_cycler", instance.to_case(Case::Snake)));
+    let construct_multiple_buffers = generate_multiple_buffers(cyclers);
+    let construct_future_queues = generate_future_queues(cyclers);
+    // 2 communication writer slots + n reader slots for other cyclers
+    let number_of_parameter_slots = 2 + cyclers.number_of_instances();
+    let construct_cyclers = generate_cycler_constructors(cyclers);
+    let instance_replays = generate_instance_replays(cyclers);
+
+    quote! {
+        pub struct Replayer<HardwareInterface> {
+            communication_server: communication::server::Runtime<crate::structs::Parameters>,
+            recording_indices: std::collections::BTreeMap<String, framework::RecordingIndex>,
+            #(#cycler_fields)*
+        }
+
+        impl<HardwareInterface> Replayer<HardwareInterface>
+        where
+            HardwareInterface: crate::HardwareInterface + Send + Sync + 'static
+        {
+            #[allow(clippy::redundant_clone, clippy::too_many_arguments)]
+            pub fn new(
+                hardware_interface: std::sync::Arc<HardwareInterface>,
+                addresses: Option<impl tokio::net::ToSocketAddrs + std::marker::Send + std::marker::Sync + 'static>,
+                parameters_directory: impl std::convert::AsRef<std::path::Path> + std::marker::Send + std::marker::Sync + 'static,
+                body_id: String,
+                head_id: String,
+                keep_running: tokio_util::sync::CancellationToken,
+                recording_indices: std::collections::BTreeMap<String, framework::RecordingIndex>,
+            ) -> color_eyre::Result<Self> {
+                use color_eyre::eyre::WrapErr;
+
+                #construct_multiple_buffers
+                #construct_future_queues
+                // Replayed cycles are never recorded again, the receiving end is not needed
+                let (recording_sender, _recording_receiver) = std::sync::mpsc::sync_channel(0);
+                let cycler_instances_to_be_recorded = std::collections::HashSet::<String>::new();
+
+                let communication_server = communication::server::Runtime::start(
+                    addresses, parameters_directory, body_id, head_id, #number_of_parameter_slots, keep_running)
+                    .wrap_err("failed to start communication server")?;
+
+                #construct_cyclers
+
+                Ok(Self {
+                    communication_server,
+                    recording_indices,
+                    #(#cycler_identifiers,)*
+                })
+            }
+
+            pub fn get_recording_indices(&self) -> &std::collections::BTreeMap<String, framework::RecordingIndex> {
+                &self.recording_indices
+            }
+
+            pub fn get_parameters_changed(&self) -> std::sync::Arc<tokio::sync::Notify> {
+                self.communication_server.get_parameters_changed()
+            }
+
+            /// Replays the latest recorded frame at or before `timestamp` of every recorded cycler instance
+            pub fn seek(&mut self, timestamp: std::time::SystemTime) -> color_eyre::Result<()> {
+                use color_eyre::eyre::WrapErr;
+
+                #instance_replays
+                Ok(())
+            }
+
+            pub fn join(self) -> color_eyre::Result<()> {
+                use color_eyre::eyre::WrapErr;
+
+                match self.communication_server.join() {
+                    Ok(result) => result.wrap_err("communication server exited with error"),
+                    Err(error) => color_eyre::eyre::bail!("failed to join communication server: {error:?}"),
+                }
+            }
+        }
+    }
+}
+
+fn generate_instance_replays(cyclers: &Cyclers) -> TokenStream {
+    cyclers
+        .instances()
+        .map(|(_cycler, instance)| {
+            let cycler_variable_identifier =
+                format_ident!("{}_cycler", instance.to_case(Case::Snake));
+            let read_error_message = format!("failed to read recorded frame of `{instance}`");
+            let replay_error_message = format!("failed to replay recorded frame of `{instance}`");
+            quote! {
+                if let Some(recording_index) = self.recording_indices.get_mut(#instance) {
+                    if let Some(frame_index) = recording_index.find_latest_frame_up_to(timestamp) {
+                        let frame = recording_index.read_frame(frame_index).wrap_err(#read_error_message)?;
+                        self.#cycler_variable_identifier.replay(&frame.data).wrap_err(#replay_error_message)?;
+                    }
+                }
+            }
+        })
+        .collect()
+}
@@ -79,7 +79,7 @@ pub fn generate_run_function(cyclers: &Cyclers) -> TokenStream {
     }
 }
 
-fn generate_multiple_buffers(cyclers: &Cyclers) -> TokenStream {
+pub(crate) fn generate_multiple_buffers(cyclers: &Cyclers) -> TokenStream {
     // 2 writer slots + n-1 reader slots for other cyclers + 1 reader slot for communication
     let slots_for_real_time_cyclers: TokenStream = repeat(quote! { Default::default(), })
         .take(2 + cyclers.number_of_instances())
@@ -103,7 +103,7 @@ fn generate_multiple_buffers(cyclers: &Cyclers) -> TokenStream {
     }).collect()
 }
 
-fn generate_future_queues(cyclers: &Cyclers) -> TokenStream {
+pub(crate) fn generate_future_queues(cyclers: &Cyclers) -> TokenStream {
     cyclers
         .instances_with(CyclerKind::Perception)
         .map(|(_cycler, instance)| {
@@ -135,9 +135,20 @@ fn generate_recording_thread(cyclers: &Cyclers) -> TokenStream {
     let frame_writes = cyclers.instances().map(|(_cycler, instance)| {
         let instance_name = format_ident!("{}", instance);
         let instance_name_snake_case = format_ident!("{}", instance.to_case(Case::Snake));
+        let metadata_error_message =
+            format!("failed to write frame metadata into recording file for {instance}");
         let error_message = format!("failed to write into recording file for {instance}");
         quote! {
-            crate::cyclers::RecordingFrame::#instance_name { data } => #instance_name_snake_case.write_all(data.as_slice()).wrap_err(#error_message)?,
+            crate::cyclers::RecordingFrame::#instance_name { timestamp, data } => {
+                bincode::serialize_into(
+                    &mut #instance_name_snake_case,
+                    &framework::RecordingFrameMetadata {
+                        timestamp,
+                        length: data.len(),
+                    },
+                ).wrap_err(#metadata_error_message)?;
+                #instance_name_snake_case.write_all(data.as_slice()).wrap_err(#error_message)?;
+            }
         }
     });
 
@@ -167,7 +178,7 @@ fn generate_recording_thread(cyclers: &Cyclers) -> TokenStream {
     }
 }
 
-fn generate_cycler_constructors(cyclers: &Cyclers) -> TokenStream {
+pub(crate) fn generate_cycler_constructors(cyclers: &Cyclers) -> TokenStream {
     cyclers.instances().map(|(cycler, instance)| {
         let instance_name_snake_case = instance.to_case(Case::Snake);
         let cycler_database_changed_identifier = format_ident!("{instance_name_snake_case}_changed");
@@ -5,16 +5,22 @@ use context_attribute::context;
 use filtering::kalman_filter::KalmanFilter;
 use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput};
 use geometry::circle::Circle;
-use nalgebra::{matrix, vector, Isometry2, Matrix2, Matrix2x4, Matrix4, Matrix4x2, Point2};
+use nalgebra::{
+    distance, matrix, point, vector, Isometry2, Matrix2, Matrix2x4, Matrix4, Matrix4x2, Point2,
+};
 use projection::Projection;
 use serde::{Deserialize, Serialize};
+use spl_network_messages::SubState;
 use types::{
     ball::Ball,
-    ball_filter::Hypothesis,
+    ball_event::BallEvent,
+    ball_filter::{Hypothesis, PREDICTION_CYCLE_TIME},
     ball_position::BallPosition,
+    ball_trajectory::BallTrajectory,
     camera_matrix::{CameraMatrices, CameraMatrix},
     cycle_time::CycleTime,
     field_dimensions::FieldDimensions,
+    game_controller_state::GameControllerState,
     limb::{is_above_limbs, Limb, ProjectedLimbs},
     multivariate_normal_distribution::MultivariateNormalDistribution,
     parameters::BallFilterParameters,
@@ -23,6 +29,9 @@ use types::{
 #[derive(Deserialize, Serialize)]
 pub struct BallFilter {
     hypotheses: Vec<Hypothesis>,
+    ball_event: Option<BallEvent>,
+    last_reliable_ball_in_field: Option<(Point2<f32>, SystemTime)>,
+    last_sub_state: Option<SubState>,
 }
 
 #[context]
@@ -43,6 +52,7 @@ pub struct CycleContext {
 
     camera_matrices: RequiredInput<Option<CameraMatrices>, "camera_matrices?">,
     cycle_time: Input<CycleTime, "cycle_time">,
+    game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
 
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     ball_filter_configuration: Parameter<BallFilterParameters, "ball_filter">,
@@ -50,18 +60,27 @@ pub struct CycleContext {
     balls_bottom: PerceptionInput<Option<Vec<Ball>>, "VisionBottom", "balls?">,
     balls_top: PerceptionInput<Option<Vec<Ball>>, "VisionTop", "balls?">,
     projected_limbs: PerceptionInput<Option<ProjectedLimbs>, "VisionBottom", "projected_limbs?">,
+
+    // the pose of the last cycle, localization depends on the game controller state filter which
+    // depends on the ball
+    robot_to_field: CyclerState<Isometry2<f32>, "robot_to_field">,
 }
 
 #[context]
 #[derive(Default)]
 pub struct MainOutputs {
+    pub ball_event: MainOutput<Option<BallEvent>>,
     pub ball_position: MainOutput<Option<BallPosition>>,
+    pub ball_trajectory: MainOutput<Option<BallTrajectory>>,
 }
 
 impl BallFilter {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             hypotheses: Vec::new(),
+            ball_event: None,
+            last_reliable_ball_in_field: None,
+            last_sub_state: None,
         })
     }
 
@@ -116,6 +135,15 @@ impl BallFilter {
             );
 
             for ball in balls {
+                // a ball outside of the field is out of play until the referee puts it back
+                let is_out_of_play = matches!(self.ball_event, Some(BallEvent::LeftField { .. }))
+                    && is_outside_field(
+                        *context.robot_to_field * ball.position,
+                        context.field_dimensions,
+                    );
+                if is_out_of_play {
+                    continue;
+                }
                 self.update_hypotheses_with_measurement(
                     ball.position,
                     *detection_time,
@@ -134,6 +162,28 @@ impl BallFilter {
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
         let persistent_updates = Self::persistent_balls_in_control_cycle(&context);
         self.advance_all_hypotheses(persistent_updates, &context);
+        let projected_limbs_bottom = context
+            .projected_limbs
+            .persistent
+            .values()
+            .last()
+            .and_then(|limbs| limbs.last())
+            .and_then(|limbs| *limbs);
+        self.detect_ball_event(
+            *context.robot_to_field,
+            context.cycle_time.start_time,
+            context.game_controller_state,
+            context.field_dimensions,
+            context.ball_filter_configuration,
+            |position_on_ground| {
+                is_in_view(
+                    position_on_ground,
+                    Some(context.camera_matrices),
+                    projected_limbs_bottom,
+                    context.field_dimensions.ball_radius,
+                )
+            },
+        );
 
         context
             .ball_filter_hypotheses
@@ -174,11 +224,114 @@ impl BallFilter {
             hypothesis.selected_ball_position(context.ball_filter_configuration)
         });
 
+        let ball_trajectory = ball_position.map(|ball_position| {
+            BallTrajectory::from_ball_filter(ball_position, context.ball_filter_configuration)
+        });
+
         Ok(MainOutputs {
+            ball_event: self.ball_event.into(),
             ball_position: ball_position.into(),
+            ball_trajectory: ball_trajectory.into(),
         })
     }
 
+    fn detect_ball_event(
+        &mut self,
+        robot_to_field: Isometry2<f32>,
+        now: SystemTime,
+        game_controller_state: Option<&GameControllerState>,
+        field_dimensions: &FieldDimensions,
+        configuration: &BallFilterParameters,
+        is_in_view: impl Fn(Point2<f32>) -> bool,
+    ) {
+        let best_ball = self.find_best_hypothesis().map(|hypothesis| {
+            (
+                robot_to_field * hypothesis.selected_ball_position(configuration).position,
+                hypothesis.last_update,
+                hypothesis.validity,
+            )
+        });
+        match best_ball {
+            Some((position_in_field, last_update, _validity))
+                if is_outside_field(position_in_field, field_dimensions) =>
+            {
+                if !matches!(self.ball_event, Some(BallEvent::LeftField { .. })) {
+                    self.ball_event = Some(BallEvent::LeftField {
+                        position_in_field,
+                        time: last_update,
+                    });
+                }
+                self.hypotheses.retain(|hypothesis| {
+                    !is_outside_field(
+                        robot_to_field * hypothesis.selected_ball_position(configuration).position,
+                        field_dimensions,
+                    )
+                });
+                self.last_reliable_ball_in_field = None;
+            }
+            Some((position_in_field, last_update, validity)) => {
+                let was_seen_after_event = self
+                    .ball_event
+                    .is_some_and(|ball_event| last_update > ball_event.time());
+                if was_seen_after_event {
+                    self.ball_event = None;
+                }
+                if validity >= configuration.reliable_ball_minimum_validity {
+                    self.last_reliable_ball_in_field = Some((position_in_field, last_update));
+                }
+            }
+            None => {}
+        }
+
+        // the reliable ball is gone without timing out although it should have been seen, thus it
+        // vanished while being looked at instead of just being occluded or out of view
+        if let Some((last_position_in_field, last_seen)) = self.last_reliable_ball_in_field {
+            let is_still_tracked = self.hypotheses.iter().any(|hypothesis| {
+                distance(
+                    &(robot_to_field * hypothesis.selected_ball_position(configuration).position),
+                    &last_position_in_field,
+                ) < configuration.hypothesis_merge_distance
+            });
+            if !is_still_tracked {
+                let has_timed_out = now
+                    .duration_since(last_seen)
+                    .is_ok_and(|age| age >= configuration.hypothesis_timeout);
+                let should_be_visible =
+                    is_in_view(robot_to_field.inverse() * last_position_in_field);
+                if !has_timed_out && should_be_visible {
+                    self.ball_event = Some(BallEvent::Removed {
+                        last_position_in_field,
+                        time: now,
+                    });
+                }
+                self.last_reliable_ball_in_field = None;
+            }
+        }
+
+        let sub_state =
+            game_controller_state.and_then(|game_controller_state| game_controller_state.sub_state);
+        if sub_state != self.last_sub_state {
+            if let (
+                Some(sub_state),
+                Some(BallEvent::LeftField {
+                    position_in_field, ..
+                }),
+            ) = (sub_state, self.ball_event)
+            {
+                if let Some(placement) =
+                    set_play_ball_placement(sub_state, position_in_field, field_dimensions)
+                {
+                    self.ball_event = Some(BallEvent::PlacedBack {
+                        position_in_field: placement,
+                        sub_state,
+                        time: now,
+                    });
+                }
+            }
+            self.last_sub_state = sub_state;
+        }
+    }
+
     fn decay_hypotheses(
         &mut self,
         camera_matrices: Option<&CameraMatrices>,
@@ -187,24 +340,12 @@ impl BallFilter {
         configuration: &BallFilterParameters,
     ) {
         for hypothesis in self.hypotheses.iter_mut() {
-            let ball_in_view = match (camera_matrices.as_ref(), projected_limbs.as_ref()) {
-                (Some(camera_matrices), Some(projected_limbs)) => {
-                    is_visible_to_camera(
-                        hypothesis,
-                        &camera_matrices.bottom,
-                        ball_radius,
-                        &projected_limbs.limbs,
-                        configuration,
-                    ) || is_visible_to_camera(
-                        hypothesis,
-                        &camera_matrices.top,
-                        ball_radius,
-                        &[],
-                        configuration,
-                    )
-                }
-                _ => false,
-            };
+            let ball_in_view = is_in_view(
+                hypothesis.selected_ball_position(configuration).position,
+                camera_matrices,
+                projected_limbs,
+                ball_radius,
+            );
 
             let decay_factor = if ball_in_view {
                 configuration.visible_validity_exponential_decay_factor
@@ -222,7 +363,7 @@ impl BallFilter {
         process_noise: Matrix4<f32>,
     ) {
         for hypothesis in self.hypotheses.iter_mut() {
-            let cycle_time = 0.012;
+            let cycle_time = PREDICTION_CYCLE_TIME;
             let constant_velocity_prediction = matrix![
                 1.0, 0.0, cycle_time, 0.0;
                 0.0, 1.0, 0.0, cycle_time;
@@ -404,6 +545,41 @@ impl BallFilter {
     }
 }
 
+/// The ball is out once it completely crossed the outer edge of a side or goal line
+fn is_outside_field(position_in_field: Point2<f32>, field_dimensions: &FieldDimensions) -> bool {
+    let margin = field_dimensions.line_width / 2.0 + field_dimensions.ball_radius;
+    position_in_field.x.abs() > field_dimensions.length / 2.0 + margin
+        || position_in_field.y.abs() > field_dimensions.width / 2.0 + margin
+}
+
+/// Where the referee puts the ball for a set play after it left the field at the given position
+fn set_play_ball_placement(
+    sub_state: SubState,
+    left_field_at: Point2<f32>,
+    field_dimensions: &FieldDimensions,
+) -> Option<Point2<f32>> {
+    let side_x = left_field_at.x.signum();
+    let side_y = left_field_at.y.signum();
+    match sub_state {
+        SubState::KickIn => Some(point![
+            left_field_at.x.clamp(
+                -field_dimensions.length / 2.0,
+                field_dimensions.length / 2.0
+            ),
+            side_y * field_dimensions.width / 2.0
+        ]),
+        SubState::CornerKick => Some(point![
+            side_x * field_dimensions.length / 2.0,
+            side_y * field_dimensions.width / 2.0
+        ]),
+        SubState::GoalKick => Some(point![
+            side_x * (field_dimensions.length / 2.0 - field_dimensions.goal_box_area_length),
+            side_y * field_dimensions.goal_box_area_width / 2.0
+        ]),
+        SubState::PushingFreeKick | SubState::PenaltyKick => None,
+    }
+}
+
 fn project_to_image(
     ball_position: &[BallPosition],
     camera_matrix: &CameraMatrix,
@@ -426,14 +602,32 @@ fn project_to_image(
         .collect()
 }
 
+/// Whether a ball at the given ground position would be seen by one of the cameras
+fn is_in_view(
+    position_on_ground: Point2<f32>,
+    camera_matrices: Option<&CameraMatrices>,
+    projected_limbs: Option<&ProjectedLimbs>,
+    ball_radius: f32,
+) -> bool {
+    match (camera_matrices, projected_limbs) {
+        (Some(camera_matrices), Some(projected_limbs)) => {
+            is_visible_to_camera(
+                position_on_ground,
+                &camera_matrices.bottom,
+                ball_radius,
+                &projected_limbs.limbs,
+            ) || is_visible_to_camera(position_on_ground, &camera_matrices.top, ball_radius, &[])
+        }
+        _ => false,
+    }
+}
+
 fn is_visible_to_camera(
-    hypothesis: &Hypothesis,
+    position_on_ground: Point2<f32>,
     camera_matrix: &CameraMatrix,
     ball_radius: f32,
     projected_limbs: &[Limb],
-    configuration: &BallFilterParameters,
 ) -> bool {
-    let position_on_ground = hypothesis.selected_ball_position(configuration).position;
     let position_in_image =
         match camera_matrix.ground_with_z_to_pixel(position_on_ground, ball_radius) {
             Ok(position_in_image) => position_in_image,
@@ -443,3 +637,227 @@ fn is_visible_to_camera(
         && (0.0..480.0).contains(&position_in_image.y)
         && is_above_limbs(position_in_image, projected_limbs)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use approx::assert_relative_eq;
+    use nalgebra::{Isometry3, Vector4};
+    use spl_network_messages::{GamePhase, GameState, Half, Team};
+
+    use super::*;
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            ball_radius: 0.05,
+            length: 9.0,
+            width: 6.0,
+            line_width: 0.05,
+            goal_box_area_length: 0.6,
+            goal_box_area_width: 2.2,
+            ..Default::default()
+        }
+    }
+
+    fn configuration() -> BallFilterParameters {
+        BallFilterParameters {
+            hypothesis_timeout: Duration::from_secs(5),
+            hypothesis_merge_distance: 1.0,
+            resting_ball_velocity_threshold: 0.25,
+            reliable_ball_minimum_validity: 3.0,
+            ..Default::default()
+        }
+    }
+
+    fn hypothesis_at(position: Point2<f32>, validity: f32, last_update: SystemTime) -> Hypothesis {
+        let state = MultivariateNormalDistribution {
+            mean: vector![position.x, position.y, 0.0, 0.0],
+            covariance: Matrix4::from_diagonal(&Vector4::repeat(0.1)),
+        };
+        Hypothesis {
+            moving_state: state,
+            resting_state: state,
+            validity,
+            last_update,
+        }
+    }
+
+    #[test]
+    fn ball_is_out_once_it_fully_crossed_a_line() {
+        let field_dimensions = field_dimensions();
+
+        assert!(!is_outside_field(point![4.5, 0.0], &field_dimensions));
+        assert!(!is_outside_field(point![4.57, 2.0], &field_dimensions));
+        assert!(is_outside_field(point![4.6, 2.0], &field_dimensions));
+        assert!(is_outside_field(point![-1.0, -3.1], &field_dimensions));
+    }
+
+    #[test]
+    fn set_play_placement_depends_on_where_ball_left_field() {
+        let field_dimensions = field_dimensions();
+
+        assert_relative_eq!(
+            set_play_ball_placement(SubState::KickIn, point![1.2, -3.3], &field_dimensions)
+                .unwrap(),
+            point![1.2, -3.0]
+        );
+        assert_relative_eq!(
+            set_play_ball_placement(SubState::CornerKick, point![4.7, 1.0], &field_dimensions)
+                .unwrap(),
+            point![4.5, 3.0]
+        );
+        assert_relative_eq!(
+            set_play_ball_placement(SubState::GoalKick, point![-4.7, -1.0], &field_dimensions)
+                .unwrap(),
+            point![-3.9, -1.1]
+        );
+        assert!(set_play_ball_placement(
+            SubState::PushingFreeKick,
+            point![4.7, 1.0],
+            &field_dimensions
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn ball_leaving_field_is_placed_back_for_kick_in() {
+        let field_dimensions = field_dimensions();
+        let configuration = configuration();
+        let robot_to_field = Isometry2::translation(1.0, 2.0);
+        let now = UNIX_EPOCH + Duration::from_secs(100);
+        let mut ball_filter = BallFilter::new(CreationContext {}).unwrap();
+        ball_filter.hypotheses = vec![hypothesis_at(point![0.5, 1.2], 5.0, now)];
+
+        ball_filter.detect_ball_event(
+            robot_to_field,
+            now,
+            None,
+            &field_dimensions,
+            &configuration,
+            |_| true,
+        );
+
+        assert!(ball_filter.hypotheses.is_empty());
+        assert!(matches!(
+            ball_filter.ball_event,
+            Some(BallEvent::LeftField { position_in_field, .. })
+                if position_in_field == point![1.5, 3.2]
+        ));
+
+        let kick_in = GameControllerState {
+            game_state: GameState::Ready,
+            game_phase: GamePhase::Normal,
+            kicking_team: Team::Hulks,
+            last_game_state_change: now,
+            half: Half::First,
+            remaining_time_in_half: Duration::ZERO,
+            penalties: Default::default(),
+            remaining_amount_of_messages: 1200,
+            sub_state: Some(SubState::KickIn),
+            hulks_team_is_home_after_coin_toss: true,
+        };
+        ball_filter.detect_ball_event(
+            robot_to_field,
+            now + Duration::from_secs(1),
+            Some(&kick_in),
+            &field_dimensions,
+            &configuration,
+            |_| true,
+        );
+
+        assert!(matches!(
+            ball_filter.ball_event,
+            Some(BallEvent::PlacedBack {
+                position_in_field,
+                sub_state: SubState::KickIn,
+                ..
+            }) if position_in_field == point![1.5, 3.0]
+        ));
+    }
+
+    /// Bottom camera looking down onto the ground in front of the robot, top camera looking
+    /// above the horizon
+    fn camera_matrices() -> CameraMatrices {
+        let camera_matrix = |pitch: f32| {
+            CameraMatrix::from_normalized_focal_and_center(
+                vector![0.95, 1.27],
+                point![0.5, 0.5],
+                vector![640.0, 480.0],
+                Isometry3::identity(),
+                Isometry3::identity(),
+                Isometry3::new(vector![0.0, 0.0, 0.5], vector![0.0, pitch, 0.0]),
+            )
+        };
+        CameraMatrices {
+            top: camera_matrix(-0.5),
+            bottom: camera_matrix(0.3),
+        }
+    }
+
+    fn vanish_reliable_ball(
+        position: Point2<f32>,
+        projected_limbs: &ProjectedLimbs,
+    ) -> Option<BallEvent> {
+        let field_dimensions = field_dimensions();
+        let configuration = configuration();
+        let camera_matrices = camera_matrices();
+        let is_ball_in_view = |position_on_ground| {
+            is_in_view(
+                position_on_ground,
+                Some(&camera_matrices),
+                Some(projected_limbs),
+                field_dimensions.ball_radius,
+            )
+        };
+        let now = UNIX_EPOCH + Duration::from_secs(100);
+        let mut ball_filter = BallFilter::new(CreationContext {}).unwrap();
+        ball_filter.hypotheses = vec![hypothesis_at(position, 5.0, now)];
+
+        ball_filter.detect_ball_event(
+            Isometry2::identity(),
+            now,
+            None,
+            &field_dimensions,
+            &configuration,
+            is_ball_in_view,
+        );
+        assert!(ball_filter.ball_event.is_none());
+
+        ball_filter.hypotheses.clear();
+        ball_filter.detect_ball_event(
+            Isometry2::identity(),
+            now + Duration::from_millis(500),
+            None,
+            &field_dimensions,
+            &configuration,
+            is_ball_in_view,
+        );
+        ball_filter.ball_event
+    }
+
+    #[test]
+    fn vanished_reliable_ball_is_reported_as_removed() {
+        let ball_event = vanish_reliable_ball(point![2.0, 0.0], &ProjectedLimbs::default());
+
+        assert!(matches!(
+            ball_event,
+            Some(BallEvent::Removed { last_position_in_field, .. })
+                if last_position_in_field == point![2.0, 0.0]
+        ));
+    }
+
+    #[test]
+    fn vanished_ball_out_of_view_or_occluded_is_not_reported() {
+        let behind_robot = vanish_reliable_ball(point![-2.0, 0.0], &ProjectedLimbs::default());
+        let occluding_limbs = ProjectedLimbs {
+            limbs: vec![Limb {
+                pixel_polygon: vec![point![0.0, 0.0], point![640.0, 0.0]],
+            }],
+        };
+        let behind_limbs = vanish_reliable_ball(point![2.0, 0.0], &occluding_limbs);
+
+        assert!(behind_robot.is_none());
+        assert!(behind_limbs.is_none());
+    }
+}
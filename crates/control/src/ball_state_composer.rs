@@ -8,10 +8,10 @@ use nalgebra::{point, Isometry2, Point2, Vector2};
 use serde::{Deserialize, Serialize};
 use spl_network_messages::{SubState, Team};
 use types::{
-    ball_position::BallPosition, cycle_time::CycleTime, field_dimensions::FieldDimensions,
-    filtered_game_controller_state::FilteredGameControllerState,
+    ball_position::BallPosition, ball_trajectory::BallTrajectory, cycle_time::CycleTime,
+    field_dimensions::FieldDimensions, filtered_game_controller_state::FilteredGameControllerState,
     penalty_shot_direction::PenaltyShotDirection, primary_state::PrimaryState, support_foot::Side,
-    world_state::BallState,
+    team_ball::TeamBall, world_state::BallState,
 };
 
 #[derive(Deserialize, Serialize)]
@@ -26,9 +26,10 @@ pub struct CreationContext {}
 pub struct CycleContext {
     cycle_time: Input<CycleTime, "cycle_time">,
     ball_position: Input<Option<BallPosition>, "ball_position?">,
+    ball_trajectory: Input<Option<BallTrajectory>, "ball_trajectory?">,
     penalty_shot_direction: Input<Option<PenaltyShotDirection>, "penalty_shot_direction?">,
     robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
-    team_ball: Input<Option<BallPosition>, "team_ball?">,
+    team_ball: Input<Option<TeamBall>, "fused_team_ball?">,
     primary_state: Input<PrimaryState, "primary_state">,
     filtered_game_controller_state:
         Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
@@ -59,15 +60,17 @@ impl BallStateComposer {
                 ball_position.position,
                 robot_to_field * ball_position.position,
                 ball_position.velocity,
+                context.ball_trajectory.copied(),
                 ball_position.last_seen,
                 &mut self.last_ball_field_side,
                 context.penalty_shot_direction.copied(),
             )),
-            (None, Some(ball_position), Some(robot_to_field)) => Some(create_ball_state(
-                robot_to_field.inverse() * ball_position.position,
-                ball_position.position,
-                ball_position.velocity,
-                ball_position.last_seen,
+            (None, Some(team_ball), Some(robot_to_field)) => Some(create_ball_state(
+                robot_to_field.inverse() * team_ball.position,
+                team_ball.position,
+                robot_to_field.inverse() * team_ball.velocity,
+                None,
+                team_ball.last_seen,
                 &mut self.last_ball_field_side,
                 context.penalty_shot_direction.copied(),
             )),
@@ -101,6 +104,7 @@ impl BallStateComposer {
                     robot_to_field.inverse() * penalty_spot_location,
                     penalty_spot_location,
                     Vector2::zeros(),
+                    None,
                     context.cycle_time.start_time,
                     &mut self.last_ball_field_side,
                     context.penalty_shot_direction.copied(),
@@ -110,6 +114,7 @@ impl BallStateComposer {
                 robot_to_field.inverse() * Point2::origin(),
                 Point2::origin(),
                 Vector2::zeros(),
+                None,
                 context.cycle_time.start_time,
                 &mut self.last_ball_field_side,
                 context.penalty_shot_direction.copied(),
@@ -128,6 +133,7 @@ fn create_ball_state(
     ball_in_ground: Point2<f32>,
     ball_in_field: Point2<f32>,
     ball_in_ground_velocity: Vector2<f32>,
+    trajectory_in_ground: Option<BallTrajectory>,
     last_seen_ball: SystemTime,
     last_ball_field_side: &mut Side,
     penalty_shot_direction: Option<PenaltyShotDirection>,
@@ -145,6 +151,7 @@ fn create_ball_state(
         ball_in_ground,
         ball_in_field,
         ball_in_ground_velocity,
+        trajectory_in_ground,
         last_seen_ball,
         field_side,
         penalty_shot_direction,
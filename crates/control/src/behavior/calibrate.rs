@@ -1,12 +1,25 @@
 use types::{
-    motion_command::{HeadMotion::Unstiff, MotionCommand},
+    camera_calibration::CalibrationCommand,
+    motion_command::{HeadMotion, MotionCommand},
     primary_state::PrimaryState,
     world_state::WorldState,
 };
 
-pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
+pub fn execute(
+    world_state: &WorldState,
+    calibration_command: Option<&CalibrationCommand>,
+) -> Option<MotionCommand> {
     match world_state.robot.primary_state {
-        PrimaryState::Calibration => Some(MotionCommand::Stand { head: Unstiff }),
+        PrimaryState::Calibration => {
+            let head = match calibration_command {
+                Some(command) => HeadMotion::Angles {
+                    yaw: command.head_position.yaw,
+                    pitch: command.head_position.pitch,
+                },
+                None => HeadMotion::Unstiff,
+            };
+            Some(MotionCommand::Stand { head })
+        }
         _ => None,
     }
 }
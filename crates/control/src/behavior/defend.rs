@@ -5,6 +5,7 @@ use geometry::look_at::LookAt;
 use nalgebra::{distance, point, vector, Isometry2, Point2};
 use spl_network_messages::{GamePhase, SubState, Team};
 use types::{
+    ball_trajectory::BallTrajectory,
     field_dimensions::FieldDimensions,
     filtered_game_controller_state::FilteredGameControllerState,
     line::Line,
@@ -212,12 +213,24 @@ fn defend_goal_pose(
     };
 
     let position_to_defend = point![-field_dimensions.length / 2.0 - 1.0, 0.0];
-    let defend_pose = block_on_line(
-        ball.ball_in_field,
-        position_to_defend,
-        -field_dimensions.length / 2.0 + keeper_x_offset,
-        -0.7..0.7,
-    );
+    let defense_line_x = -field_dimensions.length / 2.0 + keeper_x_offset;
+    let defend_pose = ball
+        .trajectory_in_ground
+        .and_then(|trajectory_in_ground| {
+            block_trajectory_on_line(
+                robot_to_field * trajectory_in_ground,
+                defense_line_x,
+                -0.7..0.7,
+            )
+        })
+        .unwrap_or_else(|| {
+            block_on_line(
+                ball.ball_in_field,
+                position_to_defend,
+                defense_line_x,
+                -0.7..0.7,
+            )
+        });
     Some(robot_to_field.inverse() * defend_pose)
 }
 
@@ -293,6 +306,35 @@ fn block_on_line(
     }
 }
 
+/// Blocks where a ball rolling towards the own goal is going to cross the defense line
+fn block_trajectory_on_line(
+    trajectory: BallTrajectory,
+    defense_line_x: f32,
+    defense_line_y_range: Range<f32>,
+) -> Option<Isometry2<f32>> {
+    let rest_position = trajectory.rest_position();
+    let crosses_defense_line =
+        trajectory.position.x > defense_line_x && rest_position.x <= defense_line_x;
+    if !crosses_defense_line {
+        return None;
+    }
+    let defense_line = Line(
+        point![defense_line_x, defense_line_y_range.start],
+        point![defense_line_x, defense_line_y_range.end],
+    );
+    let crossing_point = defense_line.intersection(&Line(trajectory.position, rest_position));
+    let defense_position = point![
+        defense_line_x,
+        crossing_point
+            .y
+            .clamp(defense_line_y_range.start, defense_line_y_range.end)
+    ];
+    Some(Isometry2::new(
+        defense_position.coords,
+        defense_position.look_at(&trajectory.position).angle(),
+    ))
+}
+
 fn penalty_kick_defender_radius(
     distance_to_target: f32,
     filtered_game_controller_state: Option<FilteredGameControllerState>,
@@ -312,3 +354,54 @@ fn penalty_kick_defender_radius(
         distance_to_target
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::Vector2;
+
+    use super::*;
+
+    fn rolling_ball(position: Point2<f32>, velocity: Vector2<f32>) -> BallTrajectory {
+        BallTrajectory {
+            position,
+            velocity,
+            velocity_decay_rate: 0.5,
+            resting_speed: 0.25,
+        }
+    }
+
+    #[test]
+    fn ball_rolling_over_the_defense_line_is_blocked_where_it_crosses() {
+        let trajectory = rolling_ball(point![-2.0, 0.3], vector![-3.0, -0.3]);
+
+        let pose = block_trajectory_on_line(trajectory, -4.0, -0.7..0.7).unwrap();
+
+        assert_relative_eq!(pose.translation.vector, vector![-4.0, 0.1], epsilon = 0.001);
+        assert_relative_eq!(pose.rotation.angle(), 0.2_f32.atan2(2.0), epsilon = 0.001);
+    }
+
+    #[test]
+    fn crossing_outside_of_the_defense_line_is_blocked_at_its_end() {
+        let trajectory = rolling_ball(point![-2.0, 0.3], vector![-3.0, -2.25]);
+
+        let pose = block_trajectory_on_line(trajectory, -4.0, -0.7..0.7).unwrap();
+
+        assert_relative_eq!(
+            pose.translation.vector,
+            vector![-4.0, -0.7],
+            epsilon = 0.001
+        );
+    }
+
+    #[test]
+    fn balls_not_reaching_the_defense_line_are_not_blocked() {
+        let resting_before = rolling_ball(point![-2.0, 0.0], vector![-0.5, 0.0]);
+        let rolling_away = rolling_ball(point![-2.0, 0.0], vector![3.0, 0.0]);
+        let behind_the_line = rolling_ball(point![-4.2, 0.0], vector![-1.0, 0.0]);
+
+        assert!(block_trajectory_on_line(resting_before, -4.0, -0.7..0.7).is_none());
+        assert!(block_trajectory_on_line(rolling_away, -4.0, -0.7..0.7).is_none());
+        assert!(block_trajectory_on_line(behind_the_line, -4.0, -0.7..0.7).is_none());
+    }
+}
@@ -61,11 +61,14 @@ pub fn execute(
                 return None;
             }
 
-            let ball_line = Line(
-                ball.ball_in_ground,
-                ball.ball_in_ground + ball.ball_in_ground_velocity,
-            );
-            let interception_point = ball_line.project_point(Point2::origin());
+            let interception_point = match ball.trajectory_in_ground {
+                Some(trajectory) => trajectory.interception(Point2::origin()).position,
+                None => Line(
+                    ball.ball_in_ground,
+                    ball.ball_in_ground + ball.ball_in_ground_velocity,
+                )
+                .project_point(Point2::origin()),
+            };
 
             if interception_point.coords.norm() > parameters.maximum_intercept_distance {
                 return None;
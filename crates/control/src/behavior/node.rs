@@ -1,13 +1,15 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use color_eyre::Result;
 use context_attribute::context;
 use framework::{AdditionalOutput, MainOutput};
 use nalgebra::{point, Point2, Vector2};
 use serde::{Deserialize, Serialize};
-use spl_network_messages::{GamePhase, SubState, Team};
+use spl_network_messages::{GamePhase, Intention, SubState, Team};
 use types::{
     action::Action,
+    ball_event::BallEvent,
+    camera_calibration::CalibrationCommand,
     cycle_time::CycleTime,
     field_dimensions::FieldDimensions,
     filtered_game_controller_state::FilteredGameControllerState,
@@ -25,7 +27,7 @@ use types::{
     world_state::WorldState,
 };
 
-use crate::dribble_path_planner;
+use crate::{dribble_path_planner, path_planner::WalkingSpeed};
 
 use super::{
     calibrate,
@@ -56,6 +58,9 @@ pub struct CycleContext {
     has_ground_contact: Input<bool, "has_ground_contact">,
     world_state: Input<WorldState, "world_state">,
     cycle_time: Input<CycleTime, "cycle_time">,
+    calibration_command: Input<Option<CalibrationCommand>, "calibration_command?">,
+
+    intention: CyclerState<Intention, "intention">,
 
     parameters: Parameter<BehaviorParameters, "behavior">,
     in_walk_kicks: Parameter<InWalkKicksParameters, "in_walk_kicks">,
@@ -63,6 +68,7 @@ pub struct CycleContext {
     lost_ball_parameters: Parameter<LostBallParameters, "behavior.lost_ball">,
     intercept_ball_parameters: Parameter<InterceptBallParameters, "behavior.intercept_ball">,
     maximum_step_size: Parameter<Step, "step_planner.max_step_size">,
+    base_step_duration: Parameter<Duration, "walking_engine.base_step_duration">,
     striker_set_position: Parameter<Vector2<f32>, "behavior.role_positions.striker_set_position">,
 }
 
@@ -71,6 +77,7 @@ pub struct CycleContext {
 pub struct MainOutputs {
     pub motion_command: MainOutput<MotionCommand>,
     pub dribble_path: MainOutput<Option<Vec<PathSegment>>>,
+    pub dribble_arrival_time: MainOutput<Option<Duration>>,
 }
 
 impl Behavior {
@@ -85,14 +92,25 @@ impl Behavior {
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
         let world_state = context.world_state;
         if let Some(command) = &context.parameters.injected_motion_command {
+            *context.intention = Intention::default();
             return Ok(MainOutputs {
                 motion_command: command.clone().into(),
                 dribble_path: None.into(),
+                dribble_arrival_time: None.into(),
             });
         }
 
-        if let Some(ball_state) = &world_state.ball {
-            self.absolute_last_known_ball_position = ball_state.ball_in_field;
+        match (&world_state.ball, world_state.ball_event) {
+            (Some(ball_state), _) => {
+                self.absolute_last_known_ball_position = ball_state.ball_in_field;
+            }
+            (
+                None,
+                Some(BallEvent::PlacedBack {
+                    position_in_field, ..
+                }),
+            ) => self.absolute_last_known_ball_position = position_in_field,
+            (None, _) => {}
         }
 
         let now = context.cycle_time.start_time;
@@ -183,6 +201,7 @@ impl Behavior {
             &world_state.obstacles,
             &context.parameters.path_planning,
             &self.last_motion_command,
+            WalkingSpeed::from_step_size(context.maximum_step_size, *context.base_step_duration),
         );
         let walk_and_stand = WalkAndStand::new(
             world_state,
@@ -206,12 +225,16 @@ impl Behavior {
             &mut dribble_path_obstacles,
         );
 
-        let dribble_path = dribble_path_planner::plan(
+        let timed_dribble_path = dribble_path_planner::plan(
             &walk_path_planner,
             world_state,
             &context.parameters.dribbling,
             &mut dribble_path_obstacles_output,
         );
+        let dribble_arrival_time = timed_dribble_path
+            .as_ref()
+            .map(|timed_path| timed_path.arrival_time);
+        let dribble_path = timed_dribble_path.map(|timed_path| timed_path.path);
         context
             .dribble_path_obstacles
             .fill_if_subscribed(|| dribble_path_obstacles.clone().unwrap_or_default());
@@ -234,7 +257,9 @@ impl Behavior {
                         *context.intercept_ball_parameters,
                         *context.maximum_step_size,
                     ),
-                    Action::Calibrate => calibrate::execute(world_state),
+                    Action::Calibrate => {
+                        calibrate::execute(world_state, context.calibration_command)
+                    }
                     Action::DefendGoal => defend.goal(&mut context.path_obstacles),
                     Action::DefendKickOff => defend.kick_off(&mut context.path_obstacles),
                     Action::DefendLeft => defend.left(&mut context.path_obstacles),
@@ -342,6 +367,7 @@ impl Behavior {
                 )
             });
         context.active_action.fill_if_subscribed(|| *action);
+        *context.intention = intention_of(action, &motion_command, world_state);
 
         self.last_motion_command = motion_command.clone();
 
@@ -354,6 +380,36 @@ impl Behavior {
         Ok(MainOutputs {
             motion_command: motion_command.into(),
             dribble_path: dribble_path.into(),
+            dribble_arrival_time: dribble_arrival_time.into(),
         })
     }
 }
+
+fn intention_of(
+    action: &Action,
+    motion_command: &MotionCommand,
+    world_state: &WorldState,
+) -> Intention {
+    let robot_to_field = match world_state.robot.robot_to_field {
+        Some(robot_to_field) => robot_to_field,
+        None => return Intention::default(),
+    };
+    let kick_target = match action {
+        Action::Dribble => world_state
+            .kick_decisions
+            .as_ref()
+            .and_then(|kick_decisions| kick_decisions.first())
+            .map(|kick_decision| robot_to_field * kick_decision.target),
+        _ => None,
+    };
+    let walk_target = match motion_command {
+        MotionCommand::Walk { path, .. } => path
+            .last()
+            .map(|segment| robot_to_field * segment.end_point()),
+        _ => None,
+    };
+    Intention {
+        kick_target,
+        walk_target,
+    }
+}
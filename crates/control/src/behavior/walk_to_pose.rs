@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use filtering::hysteresis::less_than_with_hysteresis;
 use framework::AdditionalOutput;
 use nalgebra::{point, Isometry2, Point2, UnitComplex};
@@ -15,13 +17,14 @@ use types::{
     world_state::WorldState,
 };
 
-use crate::path_planner::PathPlanner;
+use crate::path_planner::{PathPlanner, TimedPath, WalkingSpeed};
 
 pub struct WalkPathPlanner<'cycle> {
     field_dimensions: &'cycle FieldDimensions,
     obstacles: &'cycle [Obstacle],
     parameters: &'cycle PathPlanningParameters,
     last_motion_command: &'cycle MotionCommand,
+    walking_speed: WalkingSpeed,
 }
 
 impl<'cycle> WalkPathPlanner<'cycle> {
@@ -30,12 +33,14 @@ impl<'cycle> WalkPathPlanner<'cycle> {
         obstacles: &'cycle [Obstacle],
         parameters: &'cycle PathPlanningParameters,
         last_motion_command: &'cycle MotionCommand,
+        walking_speed: WalkingSpeed,
     ) -> Self {
         Self {
             field_dimensions,
             obstacles,
             parameters,
             last_motion_command,
+            walking_speed,
         }
     }
     #[allow(clippy::too_many_arguments)]
@@ -49,12 +54,41 @@ impl<'cycle> WalkPathPlanner<'cycle> {
         rule_obstacles: &[RuleObstacle],
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     ) -> Vec<PathSegment> {
+        self.plan_with_arrival_time(
+            target_in_robot,
+            robot_to_field,
+            ball_obstacle,
+            ball_obstacle_radius_factor,
+            obstacles,
+            rule_obstacles,
+            path_obstacles_output,
+        )
+        .path
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn plan_with_arrival_time(
+        &self,
+        target_in_robot: Point2<f32>,
+        robot_to_field: Isometry2<f32>,
+        ball_obstacle: Option<Point2<f32>>,
+        ball_obstacle_radius_factor: f32,
+        obstacles: &[Obstacle],
+        rule_obstacles: &[RuleObstacle],
+        path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+    ) -> TimedPath {
         let mut planner = PathPlanner::default();
         planner.with_last_motion(
             self.last_motion_command,
             self.parameters.rotation_penalty_factor,
         );
-        planner.with_obstacles(obstacles, self.parameters.robot_radius_at_hip_height);
+        planner.with_moving_obstacles(
+            obstacles,
+            self.parameters.robot_radius_at_hip_height,
+            Point2::origin(),
+            self.walking_speed.translation,
+            self.parameters.obstacle_prediction_horizon,
+        );
         planner.with_rule_obstacles(
             robot_to_field.inverse(),
             rule_obstacles,
@@ -91,10 +125,17 @@ impl<'cycle> WalkPathPlanner<'cycle> {
             ];
 
         let path = planner
-            .plan(Point2::origin(), clamped_target_in_robot)
+            .plan_with_arrival_time(
+                Point2::origin(),
+                clamped_target_in_robot,
+                &self.walking_speed,
+            )
             .unwrap();
         path_obstacles_output.fill_if_subscribed(|| planner.obstacles.clone());
-        path.unwrap_or_else(|| direct_path(Point2::origin(), Point2::origin()))
+        path.unwrap_or_else(|| TimedPath {
+            path: direct_path(Point2::origin(), Point2::origin()),
+            arrival_time: Duration::ZERO,
+        })
     }
 
     pub fn walk_with_obstacle_avoiding_arms(
@@ -112,19 +153,32 @@ impl<'cycle> WalkPathPlanner<'cycle> {
         }
     }
 
+    /// Holds the hand close to the body next to obstacles and behind the back in duels
     fn arm_motion_with_obstacles(&self, side: Side) -> ArmMotion {
-        if self.obstacles.iter().any(|obstacle| {
+        let obstacles_on_side = self.obstacles.iter().filter(|obstacle| {
             let is_on_relevant_side = match side {
                 Side::Left => obstacle.position.y.is_sign_positive(),
                 Side::Right => obstacle.position.y.is_sign_negative(),
             };
             is_on_relevant_side
-                && obstacle.position.x.abs() < 0.5
-                && obstacle.position.y.abs() < 0.5
-        }) {
-            ArmMotion::PullTight
-        } else {
-            ArmMotion::Swing
+                && obstacle.position.x.abs() < self.parameters.arm_obstacle_distance
+                && obstacle.position.y.abs() < self.parameters.arm_obstacle_distance
+        });
+        let closest_distance = obstacles_on_side
+            .map(|obstacle| obstacle.position.coords.norm())
+            .min_by(f32::total_cmp);
+        match closest_distance {
+            Some(distance) if distance < self.parameters.duel_distance => ArmMotion::PullTight,
+            Some(_) => {
+                let hand_position = self.parameters.obstacle_avoiding_hand_position;
+                ArmMotion::HoldHand {
+                    hand_in_robot: match side {
+                        Side::Left => hand_position,
+                        Side::Right => point![hand_position.x, -hand_position.y, hand_position.z],
+                    },
+                }
+            }
+            None => ArmMotion::Swing,
         }
     }
 }
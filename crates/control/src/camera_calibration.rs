@@ -0,0 +1,309 @@
+use std::{
+    thread::{spawn, JoinHandle},
+    time::SystemTime,
+};
+
+use calibration::{corrections::Corrections, lines::Lines, measurement::Measurement, solve};
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{AdditionalOutput, MainOutput, PerceptionInput};
+use log::warn;
+use nalgebra::{Rotation3, Vector3};
+use serde::{Deserialize, Serialize};
+use types::{
+    camera_calibration::{
+        CalibrationCommand, CalibrationPhase, CalibrationResult, CalibrationVerdict, RobustLoss,
+    },
+    camera_matrix::CameraMatrix,
+    camera_position::CameraPosition,
+    cycle_time::CycleTime,
+    field_dimensions::FieldDimensions,
+    line_data::LineData,
+    parameters::{
+        CalibrationAcceptanceParameters, CameraCalibrationParameters, CameraMatrixParameters,
+    },
+    primary_state::PrimaryState,
+    sensor_data::SensorData,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct CameraCalibration {
+    phase: CalibrationPhase,
+    measurements: Vec<Measurement>,
+    result: Option<CalibrationResult>,
+    #[serde(skip)]
+    solver: Option<JoinHandle<CalibrationResult>>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    calibration_phase: AdditionalOutput<CalibrationPhase, "calibration_phase">,
+    calibration_result: AdditionalOutput<Option<CalibrationResult>, "calibration_result">,
+
+    cycle_time: Input<CycleTime, "cycle_time">,
+    primary_state: Input<PrimaryState, "primary_state">,
+    sensor_data: Input<SensorData, "sensor_data">,
+
+    camera_matrix_bottom: PerceptionInput<Option<CameraMatrix>, "VisionBottom", "camera_matrix?">,
+    camera_matrix_top: PerceptionInput<Option<CameraMatrix>, "VisionTop", "camera_matrix?">,
+    line_data_bottom: PerceptionInput<Option<LineData>, "VisionBottom", "line_data?">,
+    line_data_top: PerceptionInput<Option<LineData>, "VisionTop", "line_data?">,
+
+    bottom_camera_matrix_parameters:
+        Parameter<CameraMatrixParameters, "camera_matrix_parameters.vision_bottom">,
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    parameters: Parameter<CameraCalibrationParameters, "camera_calibration">,
+    robot_rotation_correction:
+        Parameter<Vector3<f32>, "camera_matrix_parameters.robot_rotation_correction">,
+    top_camera_matrix_parameters:
+        Parameter<CameraMatrixParameters, "camera_matrix_parameters.vision_top">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub calibration_command: MainOutput<Option<CalibrationCommand>>,
+}
+
+impl CameraCalibration {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            phase: CalibrationPhase::Inactive,
+            measurements: Vec::new(),
+            result: None,
+            solver: None,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        self.phase = match (self.phase, *context.primary_state) {
+            (CalibrationPhase::Inactive, PrimaryState::Calibration) => {
+                self.measurements.clear();
+                self.solver = None;
+                CalibrationPhase::MovingHead {
+                    head_position_index: 0,
+                }
+            }
+            (_, PrimaryState::Calibration) => self.phase,
+            _ => CalibrationPhase::Inactive,
+        };
+
+        self.phase = match self.phase {
+            CalibrationPhase::MovingHead {
+                head_position_index,
+            } => match context.parameters.head_positions.get(head_position_index) {
+                Some(head_position) => {
+                    let current_head_position = context.sensor_data.positions.head;
+                    let is_reached = (current_head_position.yaw - head_position.yaw).abs()
+                        <= context.parameters.head_position_tolerance
+                        && (current_head_position.pitch - head_position.pitch).abs()
+                            <= context.parameters.head_position_tolerance;
+                    if is_reached {
+                        CalibrationPhase::Measuring {
+                            head_position_index,
+                            started_at: now,
+                        }
+                    } else {
+                        self.phase
+                    }
+                }
+                None => self.finish(&context),
+            },
+            CalibrationPhase::Measuring {
+                head_position_index,
+                started_at,
+            } => {
+                let measuring_start = started_at + context.parameters.settle_duration;
+                self.collect_measurements(&context, measuring_start);
+                if now >= measuring_start + context.parameters.measurement_duration {
+                    CalibrationPhase::MovingHead {
+                        head_position_index: head_position_index + 1,
+                    }
+                } else {
+                    self.phase
+                }
+            }
+            CalibrationPhase::Solving => self.poll_solver(),
+            CalibrationPhase::Inactive | CalibrationPhase::Finished => self.phase,
+        };
+
+        let calibration_command = match self.phase {
+            CalibrationPhase::MovingHead {
+                head_position_index,
+            }
+            | CalibrationPhase::Measuring {
+                head_position_index,
+                ..
+            } => context
+                .parameters
+                .head_positions
+                .get(head_position_index)
+                .map(|head_position| CalibrationCommand {
+                    head_position: *head_position,
+                }),
+            CalibrationPhase::Inactive | CalibrationPhase::Solving | CalibrationPhase::Finished => {
+                None
+            }
+        };
+
+        context.calibration_phase.fill_if_subscribed(|| self.phase);
+        context
+            .calibration_result
+            .fill_if_subscribed(|| self.result.clone());
+        Ok(MainOutputs {
+            calibration_command: calibration_command.into(),
+        })
+    }
+
+    fn collect_measurements(&mut self, context: &CycleContext, measuring_start: SystemTime) {
+        for (position, camera_matrices, line_data) in [
+            (
+                CameraPosition::Top,
+                &context.camera_matrix_top,
+                &context.line_data_top,
+            ),
+            (
+                CameraPosition::Bottom,
+                &context.camera_matrix_bottom,
+                &context.line_data_bottom,
+            ),
+        ] {
+            let frames = camera_matrices
+                .persistent
+                .iter()
+                .zip(line_data.persistent.values())
+                .filter(|((detection_time, _), _)| **detection_time >= measuring_start)
+                .flat_map(|((_, camera_matrices), line_data)| {
+                    camera_matrices.iter().zip(line_data)
+                });
+            for (camera_matrix, line_data) in frames {
+                let (Some(camera_matrix), Some(line_data)) = (camera_matrix, line_data) else {
+                    continue;
+                };
+                let Some(lines) = Lines::from_lines_in_ground(
+                    &line_data.lines_in_robot,
+                    context.field_dimensions.goal_box_area_length,
+                    context.parameters.maximum_line_angle_deviation,
+                    context.parameters.maximum_line_distance_deviation,
+                )
+                .and_then(|lines| lines.to_image(camera_matrix)) else {
+                    continue;
+                };
+                self.measurements.push(Measurement {
+                    position,
+                    matrix: (*camera_matrix).clone(),
+                    lines,
+                });
+            }
+        }
+    }
+
+    /// Solving takes longer than a cycle, so it runs in the background until it is polled as done
+    fn finish(&mut self, context: &CycleContext) -> CalibrationPhase {
+        let measurements = self.measurements.drain(..).collect();
+        let field_dimensions = context.field_dimensions.clone();
+        let robust_loss = context.parameters.robust_loss;
+        let acceptance = context.parameters.acceptance.clone();
+        let robot_rotation_correction = *context.robot_rotation_correction;
+        let top_extrinsic_rotations = context.top_camera_matrix_parameters.extrinsic_rotations;
+        let bottom_extrinsic_rotations =
+            context.bottom_camera_matrix_parameters.extrinsic_rotations;
+        self.solver = Some(spawn(move || {
+            calibrate(
+                measurements,
+                field_dimensions,
+                robust_loss,
+                &acceptance,
+                robot_rotation_correction,
+                top_extrinsic_rotations,
+                bottom_extrinsic_rotations,
+            )
+        }));
+        CalibrationPhase::Solving
+    }
+
+    fn poll_solver(&mut self) -> CalibrationPhase {
+        match self.solver.take() {
+            Some(solver) if !solver.is_finished() => {
+                self.solver = Some(solver);
+                CalibrationPhase::Solving
+            }
+            Some(solver) => {
+                match solver.join() {
+                    Ok(result) => self.result = Some(result),
+                    Err(_) => warn!("Camera calibration solver panicked"),
+                }
+                CalibrationPhase::Finished
+            }
+            None => CalibrationPhase::Finished,
+        }
+    }
+}
+
+fn calibrate(
+    measurements: Vec<Measurement>,
+    field_dimensions: FieldDimensions,
+    robust_loss: RobustLoss,
+    acceptance: &CalibrationAcceptanceParameters,
+    robot_rotation_correction: Vector3<f32>,
+    top_extrinsic_rotations: Vector3<f32>,
+    bottom_extrinsic_rotations: Vector3<f32>,
+) -> CalibrationResult {
+    let number_of_measurements = measurements.len();
+    let solution = solve(
+        Corrections::default(),
+        measurements,
+        field_dimensions,
+        robust_loss,
+        acceptance,
+    );
+    if let CalibrationVerdict::Rejected { reason } = solution.verdict {
+        warn!(
+            "Camera calibration with {number_of_measurements} measurements was rejected: {reason:?}"
+        );
+    }
+    let standard_deviations = solution.standard_deviations().map(|standard_deviations| {
+        let standard_deviations = standard_deviations.map(f32::to_degrees);
+        [
+            standard_deviations.fixed_rows::<3>(0).into_owned(),
+            standard_deviations.fixed_rows::<3>(3).into_owned(),
+            standard_deviations.fixed_rows::<3>(6).into_owned(),
+        ]
+    });
+    let corrections = solution.corrections;
+    CalibrationResult {
+        verdict: solution.verdict,
+        number_of_measurements,
+        number_of_outliers: solution.number_of_outliers,
+        number_of_evaluations: solution.number_of_evaluations,
+        is_converged: solution.is_converged,
+        initial_root_mean_square_residual: solution.initial_root_mean_square_residual,
+        root_mean_square_residual: solution.root_mean_square_residual,
+        measurement_residuals: solution.measurement_residuals,
+        standard_deviations,
+        robot_rotation_correction: to_degrees(
+            from_degrees(robot_rotation_correction) * corrections.correction_in_robot,
+        ),
+        top_extrinsic_rotations: to_degrees(
+            from_degrees(top_extrinsic_rotations) * corrections.correction_in_camera_top.inverse(),
+        ),
+        bottom_extrinsic_rotations: to_degrees(
+            from_degrees(bottom_extrinsic_rotations)
+                * corrections.correction_in_camera_bottom.inverse(),
+        ),
+    }
+}
+
+fn from_degrees(rotations: Vector3<f32>) -> Rotation3<f32> {
+    let rotations = rotations.map(f32::to_radians);
+    Rotation3::from_euler_angles(rotations.x, rotations.y, rotations.z)
+}
+
+fn to_degrees(rotation: Rotation3<f32>) -> Vector3<f32> {
+    let (roll, pitch, yaw) = rotation.euler_angles();
+    Vector3::new(roll, pitch, yaw).map(f32::to_degrees)
+}
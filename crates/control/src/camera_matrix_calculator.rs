@@ -30,6 +30,8 @@ pub struct CycleContext {
     bottom_camera_matrix_parameters:
         Parameter<CameraMatrixParameters, "camera_matrix_parameters.vision_bottom">,
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    robot_rotation_correction:
+        Parameter<Vector3<f32>, "camera_matrix_parameters.robot_rotation_correction">,
     top_camera_matrix_parameters:
         Parameter<CameraMatrixParameters, "camera_matrix_parameters.vision_top">,
 
@@ -77,6 +79,15 @@ impl CameraMatrixCalculator {
             *context.robot_to_ground,
         );
 
+        let robot_rotation_correction = context
+            .robot_rotation_correction
+            .map(|angle: f32| angle.to_radians());
+        let correction_in_robot = Rotation3::from_euler_angles(
+            robot_rotation_correction.x,
+            robot_rotation_correction.y,
+            robot_rotation_correction.z,
+        ) * *context.correction_in_robot;
+
         let field_dimensions = context.field_dimensions;
         context
             .projected_field_lines
@@ -88,14 +99,10 @@ impl CameraMatrixCalculator {
             });
         Ok(MainOutputs {
             camera_matrices: Some(CameraMatrices {
-                top: top_camera_matrix.to_corrected(
-                    *context.correction_in_robot,
-                    *context.correction_in_camera_top,
-                ),
-                bottom: bottom_camera_matrix.to_corrected(
-                    *context.correction_in_robot,
-                    *context.correction_in_camera_bottom,
-                ),
+                top: top_camera_matrix
+                    .to_corrected(correction_in_robot, *context.correction_in_camera_top),
+                bottom: bottom_camera_matrix
+                    .to_corrected(correction_in_robot, *context.correction_in_camera_bottom),
             })
             .into(),
         })
@@ -4,17 +4,17 @@ use spl_network_messages::Team;
 use std::f32::consts::PI;
 use types::{
     filtered_game_controller_state::FilteredGameControllerState, parameters::DribblingParameters,
-    path_obstacles::PathObstacle, planned_path::PathSegment, world_state::WorldState,
+    path_obstacles::PathObstacle, world_state::WorldState,
 };
 
-use crate::behavior::walk_to_pose::WalkPathPlanner;
+use crate::{behavior::walk_to_pose::WalkPathPlanner, path_planner::TimedPath};
 
 pub fn plan(
     walk_path_planner: &WalkPathPlanner,
     world_state: &WorldState,
     dribbling_parameters: &DribblingParameters,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
-) -> Option<Vec<PathSegment>> {
+) -> Option<TimedPath> {
     let kick_decisions = world_state.kick_decisions.as_ref()?;
     let best_kick_decision = kick_decisions.first()?;
     let ball = world_state.ball?;
@@ -61,7 +61,7 @@ pub fn plan(
         world_state.rule_obstacles.as_slice()
     };
 
-    Some(walk_path_planner.plan(
+    Some(walk_path_planner.plan_with_arrival_time(
         best_pose * Point2::origin(),
         robot_to_field,
         ball_obstacle,
@@ -7,7 +7,10 @@ use nalgebra::Isometry2;
 use serde::{Deserialize, Serialize};
 use spl_network_messages::HulkMessage;
 use types::{
+    ball_event::BallEvent,
     ball_position::BallPosition,
+    ball_trajectory::BallTrajectory,
+    camera_calibration::CalibrationCommand,
     cycle_time::CycleTime,
     fall_state::FallState,
     filtered_whistle::FilteredWhistle,
@@ -41,7 +44,10 @@ pub struct CycleContext {
 #[context]
 #[derive(Default)]
 pub struct MainOutputs {
+    pub ball_event: MainOutput<Option<BallEvent>>,
     pub ball_position: MainOutput<Option<BallPosition>>,
+    pub ball_trajectory: MainOutput<Option<BallTrajectory>>,
+    pub calibration_command: MainOutput<Option<CalibrationCommand>>,
     pub cycle_time: MainOutput<CycleTime>,
     pub fall_state: MainOutput<FallState>,
     pub filtered_whistle: MainOutput<FilteredWhistle>,
@@ -62,6 +62,8 @@ impl GameControllerFilter {
                 game_phase: game_controller_state_message.game_phase,
                 kicking_team: game_controller_state_message.kicking_team,
                 last_game_state_change: self.last_game_state_change.unwrap(),
+                half: game_controller_state_message.half,
+                remaining_time_in_half: game_controller_state_message.remaining_time_in_half,
                 penalties: game_controller_state_message.hulks_team.clone().into(),
                 remaining_amount_of_messages: game_controller_state_message
                     .hulks_team
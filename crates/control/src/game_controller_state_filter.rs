@@ -64,6 +64,8 @@ impl GameControllerStateFilter {
             opponent_game_state: game_states.opponent,
             game_phase: context.game_controller_state.game_phase,
             kicking_team: context.game_controller_state.kicking_team,
+            half: context.game_controller_state.half,
+            remaining_time_in_half: context.game_controller_state.remaining_time_in_half,
             penalties: context.game_controller_state.penalties,
             remaining_number_of_messages: context
                 .game_controller_state
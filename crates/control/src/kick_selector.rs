@@ -239,6 +239,7 @@ fn generate_decisions_for_instant_kicks(
                     kicking_side,
                     kick_pose,
                     strength: default_kick_strength,
+                    target,
                 })
             } else {
                 None
@@ -396,6 +397,7 @@ fn kick_decisions_from_targets(
                     kicking_side,
                     kick_pose,
                     strength: strength.unwrap_or(default_strength),
+                    target: position,
                 }
             })
             .collect(),
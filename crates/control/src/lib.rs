@@ -4,6 +4,7 @@ pub mod ball_filter;
 pub mod ball_state_composer;
 pub mod behavior;
 pub mod button_filter;
+pub mod camera_calibration;
 pub mod camera_matrix_calculator;
 pub mod center_of_mass_provider;
 pub mod dribble_path_planner;
@@ -18,6 +19,7 @@ pub mod kinematics_provider;
 pub mod led_status;
 pub mod localization;
 pub mod localization_recorder;
+pub mod message_budget;
 pub mod motion;
 pub mod obstacle_filter;
 pub mod odometry;
@@ -31,6 +33,7 @@ pub mod sensor_data_receiver;
 pub mod sole_pressure_filter;
 pub mod sonar_filter;
 pub mod support_foot_estimation;
+pub mod team_ball_filter;
 pub mod time_to_reach_kick_position;
 pub mod visual_referee_filter;
 pub mod whistle_filter;
@@ -1,29 +1,39 @@
 use std::{
     f32::consts::{FRAC_PI_2, PI},
     mem::take,
+    time::SystemTime,
 };
 
 use approx::assert_relative_eq;
 use color_eyre::{eyre::WrapErr, Result};
 use context_attribute::context;
-use filtering::pose_filter::PoseFilter;
+use filtering::{particle_filter::ParticleFilter, pose_filter::PoseFilter};
 use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput};
 use nalgebra::{
     distance, matrix, point, vector, Isometry2, Matrix, Matrix2, Matrix3, Point2, Rotation2,
     Translation2, Vector2, Vector3,
 };
 use ordered_float::NotNan;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
 use spl_network_messages::{GamePhase, Penalty, PlayerNumber, Team};
 use types::{
     field_dimensions::FieldDimensions,
-    field_marks::{field_marks_from_field_dimensions, CorrespondencePoints, Direction, FieldMark},
+    field_marks::{
+        field_marks_from_field_dimensions, goal_post_positions_from_field_dimensions,
+        line_intersections_from_field_dimensions, penalty_mark_positions_from_field_dimensions,
+        CorrespondencePoints, Direction, FieldMark,
+    },
     filtered_game_controller_state::FilteredGameControllerState,
+    goal_post::GoalPost,
     initial_pose::InitialPose,
     line::{Line, Line2},
-    line_data::LineData,
-    localization::{ScoredPose, Update},
+    line_data::{IntersectionKind, LineData, LineIntersection},
+    localization::{LocalizationMode, Particle, ScoredPose, Update},
     multivariate_normal_distribution::MultivariateNormalDistribution,
+    parameters::ParticleFilterParameters,
+    penalty_mark::PenaltyMark,
     players::Players,
     primary_state::PrimaryState,
     support_foot::Side,
@@ -32,16 +42,22 @@ use types::{
 #[derive(Deserialize, Serialize)]
 pub struct Localization {
     field_marks: Vec<FieldMark>,
+    penalty_mark_positions: Vec<Point2<f32>>,
+    goal_post_positions: Vec<Point2<f32>>,
+    line_intersections: Vec<LineIntersection>,
     last_primary_state: PrimaryState,
     hypotheses: Vec<ScoredPose>,
     hypotheses_when_entered_playing: Vec<ScoredPose>,
     is_penalized_with_motion_in_set: bool,
     was_picked_up_while_penalized_with_motion_in_set: bool,
+    particle_filter: ParticleFilter,
+    random_number_generator: ChaCha8Rng,
 }
 
 #[context]
 pub struct CreationContext {
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    particle_filter: Parameter<ParticleFilterParameters, "localization.particle_filter">,
 }
 
 #[context]
@@ -49,6 +65,7 @@ pub struct CycleContext {
     correspondence_lines: AdditionalOutput<Vec<Line2>, "localization.correspondence_lines">,
     fit_errors: AdditionalOutput<Vec<Vec<Vec<Vec<f32>>>>, "localization.fit_errors">,
     measured_lines_in_field: AdditionalOutput<Vec<Line2>, "localization.measured_lines_in_field">,
+    particles: AdditionalOutput<Vec<Particle>, "localization.particles">,
     pose_hypotheses: AdditionalOutput<Vec<ScoredPose>, "localization.pose_hypotheses">,
     updates: AdditionalOutput<Vec<Vec<Update>>, "localization.updates">,
 
@@ -62,6 +79,8 @@ pub struct CycleContext {
 
     circle_measurement_noise: Parameter<Vector2<f32>, "localization.circle_measurement_noise">,
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    goal_post_measurement_noise:
+        Parameter<Vector2<f32>, "localization.goal_post_measurement_noise">,
     good_matching_threshold: Parameter<f32, "localization.good_matching_threshold">,
     gradient_convergence_threshold: Parameter<f32, "localization.gradient_convergence_threshold">,
     gradient_descent_step_size: Parameter<f32, "localization.gradient_descent_step_size">,
@@ -69,6 +88,8 @@ pub struct CycleContext {
         Parameter<f32, "localization.hypothesis_prediction_score_reduction_factor">,
     hypothesis_retain_factor: Parameter<f32, "localization.hypothesis_retain_factor">,
     hypothesis_score_base_increase: Parameter<f32, "localization.hypothesis_score_base_increase">,
+    intersection_measurement_noise:
+        Parameter<Vector2<f32>, "localization.intersection_measurement_noise">,
     initial_hypothesis_covariance:
         Parameter<Matrix3<f32>, "localization.initial_hypothesis_covariance">,
     initial_hypothesis_score: Parameter<f32, "localization.initial_hypothesis_score">,
@@ -79,18 +100,36 @@ pub struct CycleContext {
         Parameter<usize, "localization.maximum_amount_of_gradient_descent_iterations">,
     maximum_amount_of_outer_iterations:
         Parameter<usize, "localization.maximum_amount_of_outer_iterations">,
+    maximum_goal_post_association_distance:
+        Parameter<f32, "localization.maximum_goal_post_association_distance">,
+    maximum_intersection_association_distance:
+        Parameter<f32, "localization.maximum_intersection_association_distance">,
+    maximum_penalty_mark_association_distance:
+        Parameter<f32, "localization.maximum_penalty_mark_association_distance">,
     minimum_fit_error: Parameter<f32, "localization.minimum_fit_error">,
+    mode: Parameter<LocalizationMode, "localization.mode">,
     odometry_noise: Parameter<Vector3<f32>, "localization.odometry_noise">,
+    particle_filter: Parameter<ParticleFilterParameters, "localization.particle_filter">,
+    penalty_mark_measurement_noise:
+        Parameter<Vector2<f32>, "localization.penalty_mark_measurement_noise">,
     player_number: Parameter<PlayerNumber, "player_number">,
     score_per_good_match: Parameter<f32, "localization.score_per_good_match">,
+    use_goal_post_measurements: Parameter<bool, "localization.use_goal_post_measurements">,
+    use_intersection_measurements: Parameter<bool, "localization.use_intersection_measurements">,
     use_line_measurements: Parameter<bool, "localization.use_line_measurements">,
+    use_penalty_mark_measurements: Parameter<bool, "localization.use_penalty_mark_measurements">,
     injected_robot_to_field_of_home_after_coin_toss_before_second_half: Parameter<
         Option<Isometry2<f32>>,
         "injected_robot_to_field_of_home_after_coin_toss_before_second_half?",
     >,
 
+    goal_posts_bottom: PerceptionInput<Option<Vec<GoalPost>>, "VisionBottom", "goal_posts?">,
+    goal_posts_top: PerceptionInput<Option<Vec<GoalPost>>, "VisionTop", "goal_posts?">,
     line_data_bottom: PerceptionInput<Option<LineData>, "VisionBottom", "line_data?">,
     line_data_top: PerceptionInput<Option<LineData>, "VisionTop", "line_data?">,
+    penalty_marks_bottom:
+        PerceptionInput<Option<Vec<PenaltyMark>>, "VisionBottom", "penalty_marks?">,
+    penalty_marks_top: PerceptionInput<Option<Vec<PenaltyMark>>, "VisionTop", "penalty_marks?">,
 
     robot_to_field: CyclerState<Isometry2<f32>, "robot_to_field">,
 }
@@ -112,11 +151,20 @@ impl Localization {
                     context.field_dimensions,
                 ))
                 .collect(),
+            penalty_mark_positions: penalty_mark_positions_from_field_dimensions(
+                context.field_dimensions,
+            ),
+            goal_post_positions: goal_post_positions_from_field_dimensions(
+                context.field_dimensions,
+            ),
+            line_intersections: line_intersections_from_field_dimensions(context.field_dimensions),
             last_primary_state: PrimaryState::Unstiff,
             hypotheses: vec![],
             hypotheses_when_entered_playing: vec![],
             is_penalized_with_motion_in_set: false,
             was_picked_up_while_penalized_with_motion_in_set: false,
+            particle_filter: ParticleFilter::default(),
+            random_number_generator: ChaCha8Rng::seed_from_u64(context.particle_filter.random_seed),
         })
     }
 
@@ -126,7 +174,7 @@ impl Localization {
         game_phase: Option<GamePhase>,
         context: &CycleContext,
         penalty: &Option<Penalty>,
-    ) {
+    ) -> bool {
         match (self.last_primary_state, primary_state, game_phase) {
             (PrimaryState::Initial, PrimaryState::Ready, _) => {
                 let initial_pose = generate_initial_pose(
@@ -139,6 +187,7 @@ impl Localization {
                     *context.initial_hypothesis_score,
                 )];
                 self.hypotheses_when_entered_playing = self.hypotheses.clone();
+                true
             }
             (
                 PrimaryState::Set,
@@ -158,6 +207,7 @@ impl Localization {
                     *context.initial_hypothesis_score,
                 )];
                 self.hypotheses_when_entered_playing = self.hypotheses.clone();
+                true
             }
             (
                 PrimaryState::Set,
@@ -174,9 +224,11 @@ impl Localization {
                     *context.initial_hypothesis_score,
                 )];
                 self.hypotheses_when_entered_playing = self.hypotheses.clone();
+                true
             }
             (PrimaryState::Set, PrimaryState::Playing, _) => {
                 self.hypotheses_when_entered_playing = self.hypotheses.clone();
+                false
             }
             (PrimaryState::Playing, PrimaryState::Penalized, _) => {
                 match penalty {
@@ -186,9 +238,12 @@ impl Localization {
                     Some(_) => {}
                     None => {}
                 };
+                false
             }
             (PrimaryState::Penalized, _, _) if primary_state != PrimaryState::Penalized => {
                 if self.is_penalized_with_motion_in_set {
+                    let hypotheses_were_reset =
+                        self.was_picked_up_while_penalized_with_motion_in_set;
                     if self.was_picked_up_while_penalized_with_motion_in_set {
                         self.hypotheses = take(&mut self.hypotheses_when_entered_playing);
 
@@ -206,6 +261,7 @@ impl Localization {
                     }
                     self.is_penalized_with_motion_in_set = false;
                     self.was_picked_up_while_penalized_with_motion_in_set = false;
+                    hypotheses_were_reset
                 } else {
                     let penalized_poses = generate_penalized_poses(context.field_dimensions);
                     self.hypotheses = penalized_poses
@@ -219,6 +275,7 @@ impl Localization {
                         })
                         .collect();
                     self.hypotheses_when_entered_playing = self.hypotheses.clone();
+                    true
                 }
             }
             (PrimaryState::Unstiff, _, _) => {
@@ -234,8 +291,9 @@ impl Localization {
                     })
                     .collect();
                 self.hypotheses_when_entered_playing = self.hypotheses.clone();
+                true
             }
-            _ => {}
+            _ => false,
         }
     }
 
@@ -262,6 +320,24 @@ impl Localization {
             let current_odometry_to_last_odometry = context
                 .current_odometry_to_last_odometry
                 .get(line_data_top_timestamp);
+            let measured_penalty_marks_in_robot = collect_measured_positions_in_robot(
+                &context.penalty_marks_top,
+                &context.penalty_marks_bottom,
+                line_data_top_timestamp,
+                |penalty_mark| penalty_mark.position,
+            );
+            let measured_goal_posts_in_robot = collect_measured_positions_in_robot(
+                &context.goal_posts_top,
+                &context.goal_posts_bottom,
+                line_data_top_timestamp,
+                |goal_post| goal_post.position,
+            );
+            let measured_intersections_in_robot: Vec<_> = line_data_top
+                .iter()
+                .chain(line_data_bottom.iter())
+                .flatten()
+                .flat_map(|line_data| line_data.intersections_in_robot.iter().copied())
+                .collect();
 
             let mut fit_errors_per_hypothesis = vec![];
             for (hypothesis_index, scored_state) in self.hypotheses.iter_mut().enumerate() {
@@ -274,6 +350,46 @@ impl Localization {
                     .wrap_err("failed to predict pose filter")?;
                     scored_state.score *= *context.hypothesis_prediction_score_reduction_factor;
                 }
+                // intersections are only associated with references of the same kind and thereby
+                // correct the pose before ambiguous line associations are made
+                if *context.use_intersection_measurements {
+                    for kind in IntersectionKind::ALL {
+                        let number_of_good_matches = update_with_point_measurements(
+                            &mut scored_state.state,
+                            &intersection_positions_of_kind(&measured_intersections_in_robot, kind),
+                            &intersection_positions_of_kind(&self.line_intersections, kind),
+                            *context.maximum_intersection_association_distance,
+                            *context.good_matching_threshold,
+                            context.intersection_measurement_noise,
+                        )?;
+                        scored_state.score +=
+                            number_of_good_matches as f32 * *context.score_per_good_match;
+                    }
+                }
+                if *context.use_penalty_mark_measurements {
+                    let number_of_good_matches = update_with_point_measurements(
+                        &mut scored_state.state,
+                        &measured_penalty_marks_in_robot,
+                        &self.penalty_mark_positions,
+                        *context.maximum_penalty_mark_association_distance,
+                        *context.good_matching_threshold,
+                        context.penalty_mark_measurement_noise,
+                    )?;
+                    scored_state.score +=
+                        number_of_good_matches as f32 * *context.score_per_good_match;
+                }
+                if *context.use_goal_post_measurements {
+                    let number_of_good_matches = update_with_goal_post_measurements(
+                        &mut scored_state.state,
+                        &measured_goal_posts_in_robot,
+                        &self.goal_post_positions,
+                        *context.maximum_goal_post_association_distance,
+                        *context.good_matching_threshold,
+                        context.goal_post_measurement_noise,
+                    )?;
+                    scored_state.score +=
+                        number_of_good_matches as f32 * *context.score_per_good_match;
+                }
                 if *context.use_line_measurements {
                     let robot_to_field = scored_state.state.as_isometry();
                     let current_measured_lines_in_field: Vec<_> = line_data_top
@@ -475,8 +591,18 @@ impl Localization {
             .filtered_game_controller_state
             .map(|game_controller_state| game_controller_state.game_phase);
 
-        self.reset_state(primary_state, game_phase, &context, &penalty);
+        let hypotheses_were_reset = self.reset_state(primary_state, game_phase, &context, &penalty);
         self.last_primary_state = primary_state;
+        match context.mode {
+            LocalizationMode::MultiHypothesis => {
+                self.particle_filter = ParticleFilter::default();
+            }
+            LocalizationMode::ParticleFilter => {
+                if hypotheses_were_reset || self.particle_filter.is_empty() {
+                    self.reset_particle_filter(context.particle_filter);
+                }
+            }
+        }
 
         if self.is_penalized_with_motion_in_set && !context.has_ground_contact {
             self.was_picked_up_while_penalized_with_motion_in_set = true;
@@ -484,7 +610,10 @@ impl Localization {
 
         let robot_to_field = match primary_state {
             PrimaryState::Ready | PrimaryState::Set | PrimaryState::Playing => {
-                self.update_state(&mut context)?;
+                match context.mode {
+                    LocalizationMode::MultiHypothesis => self.update_state(&mut context)?,
+                    LocalizationMode::ParticleFilter => self.update_particle_filter(&mut context),
+                }
                 Some(*context.robot_to_field)
             }
             _ => None,
@@ -515,6 +644,189 @@ impl Localization {
         })
     }
 
+    fn reset_particle_filter(&mut self, parameters: &ParticleFilterParameters) {
+        let poses: Vec<_> = self
+            .hypotheses
+            .iter()
+            .map(|scored_pose| scored_pose.state.as_isometry())
+            .collect();
+        self.particle_filter = ParticleFilter::from_poses_with_noise(
+            &poses,
+            parameters.number_of_particles,
+            parameters.initial_pose_standard_deviation,
+            &mut self.random_number_generator,
+        );
+    }
+
+    fn update_particle_filter(&mut self, context: &mut CycleContext) {
+        let parameters = context.particle_filter;
+        let mut all_measured_lines_in_robot = vec![];
+
+        let line_datas = context
+            .line_data_top
+            .persistent
+            .iter()
+            .zip(context.line_data_bottom.persistent.iter());
+        for (
+            (line_data_top_timestamp, line_data_top),
+            (line_data_bottom_timestamp, line_data_bottom),
+        ) in line_datas
+        {
+            assert_eq!(line_data_top_timestamp, line_data_bottom_timestamp);
+            if let Some(current_odometry_to_last_odometry) = context
+                .current_odometry_to_last_odometry
+                .get(line_data_top_timestamp)
+            {
+                self.particle_filter.predict(
+                    *current_odometry_to_last_odometry,
+                    parameters.odometry_noise,
+                    &mut self.random_number_generator,
+                );
+            }
+            let measured_lines_in_robot: Vec<_> = if *context.use_line_measurements {
+                line_data_top
+                    .iter()
+                    .chain(line_data_bottom.iter())
+                    .filter_map(|data| data.as_ref())
+                    .flat_map(|line_data| line_data.lines_in_robot.iter().copied())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let measured_penalty_marks_in_robot = if *context.use_penalty_mark_measurements {
+                collect_measured_positions_in_robot(
+                    &context.penalty_marks_top,
+                    &context.penalty_marks_bottom,
+                    line_data_top_timestamp,
+                    |penalty_mark| penalty_mark.position,
+                )
+            } else {
+                Vec::new()
+            };
+            let measured_goal_posts_in_robot = if *context.use_goal_post_measurements {
+                collect_measured_positions_in_robot(
+                    &context.goal_posts_top,
+                    &context.goal_posts_bottom,
+                    line_data_top_timestamp,
+                    |goal_post| goal_post.position,
+                )
+            } else {
+                Vec::new()
+            };
+            let measured_intersections_in_robot: Vec<_> = if *context.use_intersection_measurements
+            {
+                line_data_top
+                    .iter()
+                    .chain(line_data_bottom.iter())
+                    .flatten()
+                    .flat_map(|line_data| line_data.intersections_in_robot.iter().copied())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            if measured_lines_in_robot.is_empty()
+                && measured_penalty_marks_in_robot.is_empty()
+                && measured_goal_posts_in_robot.is_empty()
+                && measured_intersections_in_robot.is_empty()
+            {
+                continue;
+            }
+            if context.measured_lines_in_field.is_subscribed() {
+                all_measured_lines_in_robot.extend(measured_lines_in_robot.iter().copied());
+            }
+
+            self.particle_filter.update(
+                |robot_to_field| {
+                    let line_likelihood = if measured_lines_in_robot.is_empty() {
+                        1.0
+                    } else {
+                        get_particle_likelihood(
+                            robot_to_field,
+                            &measured_lines_in_robot,
+                            &self.field_marks,
+                            *context.line_length_acceptance_factor,
+                            parameters.measurement_noise,
+                        )
+                    };
+                    line_likelihood
+                        * get_point_likelihood(
+                            robot_to_field,
+                            &measured_penalty_marks_in_robot,
+                            &self.penalty_mark_positions,
+                            *context.maximum_penalty_mark_association_distance,
+                            context.penalty_mark_measurement_noise,
+                        )
+                        * get_goal_post_likelihood(
+                            robot_to_field,
+                            &measured_goal_posts_in_robot,
+                            &self.goal_post_positions,
+                            *context.maximum_goal_post_association_distance,
+                            context.goal_post_measurement_noise,
+                        )
+                        * IntersectionKind::ALL
+                            .into_iter()
+                            .map(|kind| {
+                                get_point_likelihood(
+                                    robot_to_field,
+                                    &intersection_positions_of_kind(
+                                        &measured_intersections_in_robot,
+                                        kind,
+                                    ),
+                                    &intersection_positions_of_kind(&self.line_intersections, kind),
+                                    *context.maximum_intersection_association_distance,
+                                    context.intersection_measurement_noise,
+                                )
+                            })
+                            .product::<f32>()
+                },
+                parameters.slow_likelihood_average_factor,
+                parameters.fast_likelihood_average_factor,
+            );
+            let is_degenerated = self.particle_filter.effective_number_of_particles()
+                < parameters.resampling_threshold * parameters.number_of_particles as f32;
+            if is_degenerated || self.particle_filter.sensor_resetting_ratio() > 0.0 {
+                self.particle_filter.resample(
+                    parameters.number_of_particles,
+                    parameters.maximum_sensor_resetting_ratio,
+                    |random_number_generator| {
+                        generate_pose_from_observation(
+                            &measured_lines_in_robot,
+                            &self.field_marks,
+                            *context.line_length_acceptance_factor,
+                            random_number_generator,
+                        )
+                    },
+                    &mut self.random_number_generator,
+                );
+            }
+        }
+
+        if let Some(estimate) = self.particle_filter.estimate() {
+            let robot_to_field = estimate.as_isometry();
+            context.measured_lines_in_field.fill_if_subscribed(|| {
+                all_measured_lines_in_robot
+                    .iter()
+                    .map(|&measured_line_in_robot| robot_to_field * measured_line_in_robot)
+                    .collect()
+            });
+            // A single hypothesis at the estimate keeps restoring hypotheses after penalties and
+            // switching back to the multi-hypothesis mode working
+            self.hypotheses = vec![ScoredPose::from_isometry(
+                robot_to_field,
+                *context.initial_hypothesis_covariance,
+                *context.initial_hypothesis_score,
+            )];
+            *context.robot_to_field = robot_to_field;
+        }
+
+        context
+            .particles
+            .fill_if_subscribed(|| self.particle_filter.particles().to_vec());
+        context
+            .pose_hypotheses
+            .fill_if_subscribed(|| self.hypotheses.clone());
+    }
+
     fn get_best_hypothesis(&self) -> Option<&ScoredPose> {
         self.hypotheses
             .iter()
@@ -865,6 +1177,355 @@ fn get_correspondence_points(
         .collect()
 }
 
+fn get_particle_likelihood(
+    robot_to_field: Isometry2<f32>,
+    measured_lines_in_robot: &[Line2],
+    field_marks: &[FieldMark],
+    line_length_acceptance_factor: f32,
+    measurement_noise: f32,
+) -> f32 {
+    let measured_lines_in_field: Vec<_> = measured_lines_in_robot
+        .iter()
+        .map(|&measured_line_in_robot| robot_to_field * measured_line_in_robot)
+        .collect();
+    let field_mark_correspondences = get_field_mark_correspondence(
+        &measured_lines_in_field,
+        Isometry2::identity(),
+        field_marks,
+        line_length_acceptance_factor,
+    );
+    if field_mark_correspondences.is_empty() {
+        return 0.0;
+    }
+    // averaged over all correspondences to stay comparable between different amounts of lines
+    let mean_squared_error = field_mark_correspondences
+        .iter()
+        .map(|field_mark_correspondence| {
+            (field_mark_correspondence.fit_error_sum() / measurement_noise).powi(2)
+        })
+        .sum::<f32>()
+        / field_mark_correspondences.len() as f32;
+    (-0.5 * mean_squared_error).exp()
+}
+
+fn collect_measured_positions_in_robot<T>(
+    detections_top: &PerceptionInput<Vec<Option<&Vec<T>>>>,
+    detections_bottom: &PerceptionInput<Vec<Option<&Vec<T>>>>,
+    timestamp: &SystemTime,
+    position: impl Fn(&T) -> Point2<f32>,
+) -> Vec<Point2<f32>> {
+    detections_top
+        .persistent
+        .get(timestamp)
+        .into_iter()
+        .chain(detections_bottom.persistent.get(timestamp))
+        .flatten()
+        .flatten()
+        .flat_map(|detections| detections.iter().map(&position))
+        .collect()
+}
+
+/// Point landmarks are compared with the closest reference, distances are capped to stay robust
+/// against false positives
+///
+/// The measurement noise is the variance along both field axes.
+fn get_point_likelihood(
+    robot_to_field: Isometry2<f32>,
+    measured_points_in_robot: &[Point2<f32>],
+    reference_positions: &[Point2<f32>],
+    maximum_association_distance: f32,
+    measurement_noise: &Vector2<f32>,
+) -> f32 {
+    if measured_points_in_robot.is_empty() {
+        return 1.0;
+    }
+    let mean_squared_error = measured_points_in_robot
+        .iter()
+        .map(|&measured_point_in_robot| {
+            let measured_point_in_field = robot_to_field * measured_point_in_robot;
+            let difference =
+                closest_reference_position(measured_point_in_field, reference_positions)
+                    .map_or(
+                        Vector2::repeat(maximum_association_distance),
+                        |(position, _distance)| measured_point_in_field - position,
+                    )
+                    .cap_magnitude(maximum_association_distance);
+            difference.component_div(measurement_noise).dot(&difference)
+        })
+        .sum::<f32>()
+        / measured_points_in_robot.len() as f32;
+    (-0.5 * mean_squared_error).exp()
+}
+
+/// Generates a pose from which a random measured line lies on a random field mark
+fn generate_pose_from_observation(
+    measured_lines_in_robot: &[Line2],
+    field_marks: &[FieldMark],
+    line_length_acceptance_factor: f32,
+    random_number_generator: &mut impl Rng,
+) -> Option<Isometry2<f32>> {
+    let measured_line = measured_lines_in_robot.choose(random_number_generator)?;
+    let field_mark = field_marks.choose(random_number_generator)?;
+    let measured_direction = measured_line.1 - measured_line.0;
+    let measured_length = measured_direction.norm();
+    if measured_length == 0.0 {
+        return None;
+    }
+    let (point_on_field_mark, field_mark_direction) = match *field_mark {
+        FieldMark::Line { line, direction: _ } => {
+            let field_mark_direction = line.1 - line.0;
+            let field_mark_length = field_mark_direction.norm();
+            if measured_length > field_mark_length * line_length_acceptance_factor {
+                return None;
+            }
+            let free_length = (field_mark_length - measured_length).max(0.0);
+            let offset = random_number_generator.gen_range(-0.5..=0.5) * free_length;
+            (
+                line.center() + field_mark_direction.normalize() * offset,
+                field_mark_direction,
+            )
+        }
+        FieldMark::Circle { center, radius } => {
+            if measured_length > radius * line_length_acceptance_factor {
+                return None;
+            }
+            let angle = random_number_generator.gen_range(-PI..PI);
+            let radial_direction = vector![angle.cos(), angle.sin()];
+            (
+                center + radius * radial_direction,
+                vector![-radial_direction.y, radial_direction.x],
+            )
+        }
+    };
+    let field_mark_direction = if random_number_generator.gen() {
+        field_mark_direction
+    } else {
+        -field_mark_direction
+    };
+    let rotation = Rotation2::rotation_between(&measured_direction, &field_mark_direction);
+    let translation = point_on_field_mark - rotation * measured_line.center();
+    Some(Isometry2::from_parts(
+        Translation2::from(translation),
+        rotation.into(),
+    ))
+}
+
+/// Updates the pose with every measured point landmark that can be associated to a reference
+///
+/// Returns the number of landmarks closer to their reference than the good matching threshold.
+fn update_with_point_measurements(
+    state: &mut MultivariateNormalDistribution<3>,
+    measured_points_in_robot: &[Point2<f32>],
+    reference_positions: &[Point2<f32>],
+    maximum_association_distance: f32,
+    good_matching_threshold: f32,
+    measurement_noise: &Vector2<f32>,
+) -> Result<usize> {
+    let mut number_of_good_matches = 0;
+    for &measured_point_in_robot in measured_points_in_robot {
+        let robot_to_field = state.as_isometry();
+        let Some((reference_position, distance)) = closest_reference_position(
+            robot_to_field * measured_point_in_robot,
+            reference_positions,
+        ) else {
+            continue;
+        };
+        if distance > maximum_association_distance {
+            continue;
+        }
+        update_with_point_measurement(
+            state,
+            measured_point_in_robot,
+            reference_position,
+            measurement_noise,
+        )?;
+        if distance < good_matching_threshold {
+            number_of_good_matches += 1;
+        }
+    }
+    Ok(number_of_good_matches)
+}
+
+/// Updates the pose with the goal posts associated by [`associate_goal_posts`]
+///
+/// Returns the number of posts closer to their reference than the good matching threshold.
+fn update_with_goal_post_measurements(
+    state: &mut MultivariateNormalDistribution<3>,
+    measured_goal_posts_in_robot: &[Point2<f32>],
+    goal_post_positions: &[Point2<f32>],
+    maximum_association_distance: f32,
+    good_matching_threshold: f32,
+    measurement_noise: &Vector2<f32>,
+) -> Result<usize> {
+    let robot_to_field = state.as_isometry();
+    let mut number_of_good_matches = 0;
+    for (measured_goal_post_in_robot, reference_position) in associate_goal_posts(
+        robot_to_field,
+        measured_goal_posts_in_robot,
+        goal_post_positions,
+    ) {
+        let distance = distance(
+            &(robot_to_field * measured_goal_post_in_robot),
+            &reference_position,
+        );
+        if distance > maximum_association_distance {
+            continue;
+        }
+        update_with_point_measurement(
+            state,
+            measured_goal_post_in_robot,
+            reference_position,
+            measurement_noise,
+        )?;
+        if distance < good_matching_threshold {
+            number_of_good_matches += 1;
+        }
+    }
+    Ok(number_of_good_matches)
+}
+
+fn update_with_point_measurement(
+    state: &mut MultivariateNormalDistribution<3>,
+    measured_point_in_robot: Point2<f32>,
+    reference_position: Point2<f32>,
+    measurement_noise: &Vector2<f32>,
+) -> Result<()> {
+    let update = get_point_translation_measurement(
+        state.as_isometry(),
+        measured_point_in_robot,
+        reference_position,
+    );
+    let distance_to_robot = measured_point_in_robot.coords.norm();
+    state
+        .update_with_2d_translation(
+            update,
+            Matrix::from_diagonal(measurement_noise) * (1.0 + distance_to_robot),
+            |state| vector![state.x, state.y],
+        )
+        .context("Failed to update pose filter")
+}
+
+/// Pairs measured goal posts with the posts of the goal on the field side of the pose
+///
+/// Both goals look the same, so goal posts cannot tell the field halves apart: a pose and its
+/// 180° rotation around the center explain the posts equally well. They only refine a pose whose
+/// side is already known, which is kept by the initial and penalized poses. Within the goal, two
+/// measured posts are paired from left to right as seen from the robot, so they never end up at
+/// the same reference post.
+fn associate_goal_posts(
+    robot_to_field: Isometry2<f32>,
+    measured_goal_posts_in_robot: &[Point2<f32>],
+    goal_post_positions: &[Point2<f32>],
+) -> Vec<(Point2<f32>, Point2<f32>)> {
+    if measured_goal_posts_in_robot.is_empty() {
+        return Vec::new();
+    }
+    let mean_measured_x_in_field = measured_goal_posts_in_robot
+        .iter()
+        .map(|&goal_post| (robot_to_field * goal_post).x)
+        .sum::<f32>()
+        / measured_goal_posts_in_robot.len() as f32;
+    let goal: Vec<_> = goal_post_positions
+        .iter()
+        .copied()
+        .filter(|position| {
+            position.x.is_sign_positive() == mean_measured_x_in_field.is_sign_positive()
+        })
+        .collect();
+    match (measured_goal_posts_in_robot, goal.as_slice()) {
+        (&[first_measured, second_measured], &[first_reference, second_reference]) => {
+            let angle_in_robot =
+                |point_in_robot: Point2<f32>| point_in_robot.y.atan2(point_in_robot.x);
+            let reference_angle_in_robot = |reference: Point2<f32>| {
+                angle_in_robot(robot_to_field.inverse_transform_point(&reference))
+            };
+            let measured_in_order =
+                angle_in_robot(first_measured) > angle_in_robot(second_measured);
+            let references_in_order = reference_angle_in_robot(first_reference)
+                > reference_angle_in_robot(second_reference);
+            if measured_in_order == references_in_order {
+                vec![
+                    (first_measured, first_reference),
+                    (second_measured, second_reference),
+                ]
+            } else {
+                vec![
+                    (first_measured, second_reference),
+                    (second_measured, first_reference),
+                ]
+            }
+        }
+        _ => measured_goal_posts_in_robot
+            .iter()
+            .filter_map(|&measured_goal_post_in_robot| {
+                closest_reference_position(robot_to_field * measured_goal_post_in_robot, &goal).map(
+                    |(reference_position, _distance)| {
+                        (measured_goal_post_in_robot, reference_position)
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+/// Like [`get_point_likelihood`], with the goal posts associated by [`associate_goal_posts`]
+fn get_goal_post_likelihood(
+    robot_to_field: Isometry2<f32>,
+    measured_goal_posts_in_robot: &[Point2<f32>],
+    goal_post_positions: &[Point2<f32>],
+    maximum_association_distance: f32,
+    measurement_noise: &Vector2<f32>,
+) -> f32 {
+    let associations = associate_goal_posts(
+        robot_to_field,
+        measured_goal_posts_in_robot,
+        goal_post_positions,
+    );
+    if associations.is_empty() {
+        return 1.0;
+    }
+    let mean_squared_error = associations
+        .iter()
+        .map(|&(measured_goal_post_in_robot, reference_position)| {
+            let difference = (robot_to_field * measured_goal_post_in_robot - reference_position)
+                .cap_magnitude(maximum_association_distance);
+            difference.component_div(measurement_noise).dot(&difference)
+        })
+        .sum::<f32>()
+        / associations.len() as f32;
+    (-0.5 * mean_squared_error).exp()
+}
+
+fn intersection_positions_of_kind(
+    intersections: &[LineIntersection],
+    kind: IntersectionKind,
+) -> Vec<Point2<f32>> {
+    intersections
+        .iter()
+        .filter(|intersection| intersection.kind == kind)
+        .map(|intersection| intersection.position)
+        .collect()
+}
+
+fn closest_reference_position(
+    measured_point_in_field: Point2<f32>,
+    reference_positions: &[Point2<f32>],
+) -> Option<(Point2<f32>, f32)> {
+    reference_positions
+        .iter()
+        .map(|&position| (position, distance(&position, &measured_point_in_field)))
+        .min_by(|(_, left_distance), (_, right_distance)| left_distance.total_cmp(right_distance))
+}
+
+/// Robot position in the field from which the measured point lies on the reference
+fn get_point_translation_measurement(
+    robot_to_field: Isometry2<f32>,
+    measured_point_in_robot: Point2<f32>,
+    reference_position: Point2<f32>,
+) -> Vector2<f32> {
+    reference_position.coords - robot_to_field.rotation * measured_point_in_robot.coords
+}
+
 fn get_translation_and_rotation_measurement(
     robot_to_field: Isometry2<f32>,
     field_mark_correspondence: FieldMarkCorrespondence,
@@ -1669,4 +2330,224 @@ mod tests {
         let update = get_2d_translation_measurement(robot_to_field, field_mark_correspondence);
         assert_relative_eq!(update, vector![0.0, -2.0], epsilon = 0.0001);
     }
+
+    #[test]
+    fn penalty_mark_measurement_moves_robot_onto_reference() {
+        let robot_to_field = Isometry2::new(vector![3.0, 0.5], FRAC_PI_2);
+        let measured_penalty_mark_in_robot = point![0.0, -1.0];
+
+        let update = get_point_translation_measurement(
+            robot_to_field,
+            measured_penalty_mark_in_robot,
+            point![3.2, 0.0],
+        );
+
+        assert_relative_eq!(update, vector![2.2, 0.0], epsilon = 0.0001);
+    }
+
+    #[test]
+    fn penalty_marks_prefer_poses_near_penalty_areas() {
+        let penalty_mark_positions = vec![point![-3.2, 0.0], point![3.2, 0.0]];
+        let measured_penalty_marks_in_robot = [point![1.0, 0.0]];
+
+        let near_penalty_area = get_point_likelihood(
+            Isometry2::translation(2.2, 0.0),
+            &measured_penalty_marks_in_robot,
+            &penalty_mark_positions,
+            0.6,
+            &vector![0.09, 0.09],
+        );
+        let at_center_circle = get_point_likelihood(
+            Isometry2::translation(-1.0, 0.0),
+            &measured_penalty_marks_in_robot,
+            &penalty_mark_positions,
+            0.6,
+            &vector![0.09, 0.09],
+        );
+        let without_penalty_marks = get_point_likelihood(
+            Isometry2::translation(-1.0, 0.0),
+            &[],
+            &penalty_mark_positions,
+            0.6,
+            &vector![0.09, 0.09],
+        );
+
+        assert_relative_eq!(near_penalty_area, 1.0);
+        assert!(at_center_circle < near_penalty_area);
+        assert_relative_eq!(without_penalty_marks, 1.0);
+    }
+
+    #[test]
+    fn goal_posts_pull_pose_onto_their_reference() {
+        let goal_post_positions = vec![point![-4.5, 0.8], point![-4.5, -0.8]];
+        let mut state = ScoredPose::from_isometry(
+            Isometry2::new(vector![-3.4, 0.0], PI),
+            Matrix3::identity() * 0.1,
+            1.0,
+        )
+        .state;
+
+        let number_of_good_matches = update_with_goal_post_measurements(
+            &mut state,
+            &[point![1.0, 0.8], point![6.0, 0.0]],
+            &goal_post_positions,
+            0.8,
+            0.5,
+            &vector![0.01, 0.01],
+        )
+        .unwrap();
+
+        assert_eq!(number_of_good_matches, 1);
+        assert!(state.mean.x < -3.4);
+        assert_relative_eq!(state.mean.y, 0.0, epsilon = 0.1);
+    }
+
+    #[test]
+    fn goal_post_pair_is_associated_from_left_to_right() {
+        let goal_post_positions = goal_post_positions_from_field_dimensions(&FieldDimensions {
+            length: 9.0,
+            goal_inner_width: 1.5,
+            goal_post_diameter: 0.1,
+            line_width: 0.05,
+            ..Default::default()
+        });
+        let positive_y_post = goal_post_positions[1];
+        let negative_y_post = goal_post_positions[0];
+        // facing the own goal from 1.5 m in front of it, the pose is off by 0.85 m so that both
+        // measured posts are closest to the post at positive y
+        let robot_to_field = Isometry2::new(vector![positive_y_post.x + 1.5, 0.85], PI);
+        let measured_positive_y_post_in_robot = point![1.5, negative_y_post.y];
+        let measured_negative_y_post_in_robot = point![1.5, positive_y_post.y];
+
+        let associations = associate_goal_posts(
+            robot_to_field,
+            &[
+                measured_negative_y_post_in_robot,
+                measured_positive_y_post_in_robot,
+            ],
+            &goal_post_positions,
+        );
+
+        assert_eq!(
+            associations,
+            vec![
+                (measured_negative_y_post_in_robot, negative_y_post),
+                (measured_positive_y_post_in_robot, positive_y_post),
+            ]
+        );
+    }
+
+    #[test]
+    fn goal_posts_cannot_tell_the_field_halves_apart() {
+        let goal_post_positions = goal_post_positions_from_field_dimensions(&FieldDimensions {
+            length: 9.0,
+            goal_inner_width: 1.5,
+            goal_post_diameter: 0.1,
+            line_width: 0.05,
+            ..Default::default()
+        });
+        let measured_goal_posts_in_robot = [point![2.0, 0.7], point![2.1, -0.9]];
+        let robot_to_field = Isometry2::new(vector![2.5, 0.2], 0.1);
+        let mirrored_robot_to_field = Isometry2::rotation(PI) * robot_to_field;
+
+        let likelihood = get_goal_post_likelihood(
+            robot_to_field,
+            &measured_goal_posts_in_robot,
+            &goal_post_positions,
+            0.8,
+            &vector![0.05, 0.05],
+        );
+        let mirrored_likelihood = get_goal_post_likelihood(
+            mirrored_robot_to_field,
+            &measured_goal_posts_in_robot,
+            &goal_post_positions,
+            0.8,
+            &vector![0.05, 0.05],
+        );
+
+        assert!(likelihood > 0.0);
+        assert_relative_eq!(likelihood, mirrored_likelihood, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn intersections_are_only_associated_with_references_of_same_kind() {
+        let line_intersections = [
+            LineIntersection {
+                position: point![-4.5, 3.0],
+                kind: IntersectionKind::L,
+            },
+            LineIntersection {
+                position: point![-4.5, 2.0],
+                kind: IntersectionKind::T,
+            },
+        ];
+        let measured_intersections_in_robot = [LineIntersection {
+            position: point![-1.5, 2.6],
+            kind: IntersectionKind::T,
+        }];
+        let mut state = ScoredPose::from_isometry(
+            Isometry2::translation(-3.0, 0.0),
+            Matrix3::identity() * 0.1,
+            1.0,
+        )
+        .state;
+
+        for kind in IntersectionKind::ALL {
+            update_with_point_measurements(
+                &mut state,
+                &intersection_positions_of_kind(&measured_intersections_in_robot, kind),
+                &intersection_positions_of_kind(&line_intersections, kind),
+                1.0,
+                0.5,
+                &vector![0.01, 0.01],
+            )
+            .unwrap();
+        }
+
+        // the closer L corner would have pulled the pose towards positive y
+        assert!(state.mean.y < -0.2);
+    }
+
+    #[test]
+    fn replayed_particle_filter_draws_the_same_particles() {
+        let parameters = ParticleFilterParameters {
+            number_of_particles: 20,
+            initial_pose_standard_deviation: vector![0.2, 0.2, 0.1],
+            odometry_noise: vector![0.01, 0.01, 0.01],
+            random_seed: 42,
+            ..Default::default()
+        };
+        let mut localization = Localization::new(CreationContext::new(
+            &FieldDimensions::default(),
+            &parameters,
+        ))
+        .unwrap();
+        localization.hypotheses = vec![ScoredPose::from_isometry(
+            Isometry2::new(vector![1.0, -2.0], FRAC_PI_4),
+            Matrix3::identity(),
+            1.0,
+        )];
+        localization.reset_particle_filter(&parameters);
+
+        let recorded_state = bincode::serialize(&localization).unwrap();
+        let mut replayed_localization: Localization =
+            bincode::deserialize(&recorded_state).unwrap();
+
+        for localization in [&mut localization, &mut replayed_localization] {
+            localization.particle_filter.predict(
+                Isometry2::new(vector![0.1, 0.0], 0.05),
+                parameters.odometry_noise,
+                &mut localization.random_number_generator,
+            );
+        }
+        let poses = |localization: &Localization| -> Vec<_> {
+            localization
+                .particle_filter
+                .particles()
+                .iter()
+                .map(|particle| particle.robot_to_field)
+                .collect()
+        };
+        assert_eq!(poses(&replayed_localization), poses(&localization));
+    }
 }
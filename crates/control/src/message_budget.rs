@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use spl_network_messages::Half;
+use types::{message_budget::MessageTrigger, parameters::SplNetworkParameters, roles::Role};
+
+pub fn remaining_game_time(
+    half: Half,
+    remaining_time_in_half: Duration,
+    half_duration: Duration,
+) -> Duration {
+    match half {
+        Half::First => remaining_time_in_half + half_duration,
+        Half::Second => remaining_time_in_half,
+    }
+}
+
+/// Messages left for periodic updates after keeping the stop threshold and the reserve for events
+pub fn messages_for_periodic_updates(
+    remaining_messages: u16,
+    parameters: &SplNetworkParameters,
+) -> u16 {
+    remaining_messages
+        .saturating_sub(parameters.remaining_amount_of_messages_to_stop_sending)
+        .saturating_sub(parameters.event_message_reserve)
+}
+
+/// Interval between periodic messages such that the budget lasts until the end of the game
+///
+/// Only the striker sends periodic messages, so the whole team budget is spread over the
+/// remaining game time. The interval is never shorter than `minimum_interval`.
+pub fn periodic_interval(
+    remaining_game_time: Duration,
+    messages_for_periodic_updates: u16,
+    minimum_interval: Duration,
+) -> Duration {
+    if messages_for_periodic_updates == 0 {
+        return Duration::MAX;
+    }
+    (remaining_game_time / u32::from(messages_for_periodic_updates)).max(minimum_interval)
+}
+
+/// Classifies why the role state machine wants to send a message
+///
+/// Role changes and a falling striker are events which are sent immediately. A striker staying
+/// striker either sends its periodic update or, before that is due, re-claims the role after
+/// winning a conflict. Only strikers and robots giving up the striker role send messages.
+pub fn classify_trigger(
+    previous_role: Role,
+    role: Role,
+    striker_fell: bool,
+    periodic_message_is_due: bool,
+    wants_to_send: bool,
+) -> Option<MessageTrigger> {
+    match (previous_role, role) {
+        (Role::Loser | Role::Searcher, Role::Striker) => Some(MessageTrigger::BallSeenAfterLoss),
+        (Role::Striker, Role::Loser) => Some(MessageTrigger::BallLost),
+        (Role::Striker, Role::Striker) if striker_fell => Some(MessageTrigger::Fallen),
+        (_, _) if !wants_to_send => None,
+        (Role::Striker, Role::Striker) if periodic_message_is_due => Some(MessageTrigger::Periodic),
+        (Role::Striker, Role::Striker) => Some(MessageTrigger::StrikerClaim),
+        (_, Role::Striker) => Some(MessageTrigger::StrikerClaim),
+        (_, _) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_is_spread_over_both_halves() {
+        let remaining_game_time = remaining_game_time(
+            Half::First,
+            Duration::from_secs(300),
+            Duration::from_secs(600),
+        );
+        assert_eq!(remaining_game_time, Duration::from_secs(900));
+        assert_eq!(
+            periodic_interval(remaining_game_time, 300, Duration::from_secs(1)),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            periodic_interval(remaining_game_time, 3000, Duration::from_secs(1)),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            periodic_interval(remaining_game_time, 0, Duration::from_secs(1)),
+            Duration::MAX
+        );
+    }
+
+    #[test]
+    fn reserve_is_kept_for_events() {
+        let parameters = SplNetworkParameters {
+            remaining_amount_of_messages_to_stop_sending: 20,
+            event_message_reserve: 50,
+            ..Default::default()
+        };
+        assert_eq!(messages_for_periodic_updates(100, &parameters), 30);
+        assert_eq!(messages_for_periodic_updates(60, &parameters), 0);
+    }
+
+    #[test]
+    fn ball_seen_after_loss_is_an_event() {
+        assert_eq!(
+            classify_trigger(Role::Searcher, Role::Striker, false, false, true),
+            Some(MessageTrigger::BallSeenAfterLoss)
+        );
+        assert_eq!(
+            classify_trigger(Role::Loser, Role::Striker, false, true, true),
+            Some(MessageTrigger::BallSeenAfterLoss)
+        );
+    }
+
+    #[test]
+    fn ball_lost_is_an_event() {
+        assert_eq!(
+            classify_trigger(Role::Striker, Role::Loser, false, false, true),
+            Some(MessageTrigger::BallLost)
+        );
+    }
+
+    #[test]
+    fn falling_striker_is_an_event() {
+        assert_eq!(
+            classify_trigger(Role::Striker, Role::Striker, true, false, false),
+            Some(MessageTrigger::Fallen)
+        );
+        assert_eq!(
+            classify_trigger(Role::Striker, Role::Striker, true, true, true),
+            Some(MessageTrigger::Fallen)
+        );
+    }
+
+    #[test]
+    fn claiming_the_striker_role_is_an_event() {
+        assert_eq!(
+            classify_trigger(Role::DefenderLeft, Role::Striker, false, false, true),
+            Some(MessageTrigger::StrikerClaim)
+        );
+        assert_eq!(
+            classify_trigger(Role::Keeper, Role::Striker, false, true, true),
+            Some(MessageTrigger::StrikerClaim)
+        );
+        assert_eq!(
+            classify_trigger(Role::Striker, Role::Striker, false, false, true),
+            Some(MessageTrigger::StrikerClaim)
+        );
+    }
+
+    #[test]
+    fn striker_sends_periodic_updates_when_due() {
+        assert_eq!(
+            classify_trigger(Role::Striker, Role::Striker, false, true, true),
+            Some(MessageTrigger::Periodic)
+        );
+    }
+
+    #[test]
+    fn nothing_is_sent_without_a_trigger() {
+        assert_eq!(
+            classify_trigger(Role::Striker, Role::Striker, false, true, false),
+            None
+        );
+        assert_eq!(
+            classify_trigger(Role::DefenderLeft, Role::Striker, false, false, false),
+            None
+        );
+        assert_eq!(
+            classify_trigger(Role::DefenderLeft, Role::DefenderLeft, false, true, false),
+            None
+        );
+        assert_eq!(
+            classify_trigger(Role::DefenderLeft, Role::DefenderRight, false, true, true),
+            None
+        );
+    }
+}
@@ -117,6 +117,10 @@ impl HeadMotion {
                 positions: *context.look_at,
                 stiffnesses,
             },
+            Some(HeadMotionCommand::Angles { yaw, pitch }) => MotorCommands {
+                positions: HeadJoints { yaw, pitch },
+                stiffnesses,
+            },
             Some(HeadMotionCommand::Unstiff) => MotorCommands {
                 positions: context.sensor_data.positions.head,
                 stiffnesses: HeadJoints::fill(0.0),
@@ -1,6 +1,8 @@
 use std::{f32::consts::FRAC_PI_2, time::Duration};
 
 use color_eyre::Result;
+use kinematics::{left_arm_angles, right_arm_angles};
+use nalgebra::Point3;
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use types::{
@@ -37,6 +39,12 @@ enum State {
     ReleasingBack {
         interpolator: SplineInterpolator<ArmJoints<f32>>,
     },
+    MovingToHold {
+        interpolator: SplineInterpolator<ArmJoints<f32>>,
+    },
+    Hold {
+        joints: ArmJoints<f32>,
+    },
 }
 
 impl SwingingArm {
@@ -66,6 +74,10 @@ impl SwingingArm {
         };
         let swinging_arm_joints = self.swinging_arm_joints(foot, config);
         let center_arm_joints = self.swinging_arm_joints(FootOffsets::zero(), config);
+        let hold_joints = match requested_arm_motion {
+            ArmMotion::HoldHand { hand_in_robot } => self.hold_arm_joints(hand_in_robot, config),
+            _ => center_arm_joints,
+        };
 
         self.state = match (&mut self.state, requested_arm_motion) {
             (State::Swing, ArmMotion::Swing) => State::Swing,
@@ -135,7 +147,36 @@ impl SwingingArm {
                 .into();
                 State::ReleasingTight { interpolator }
             }
-            (State::Back, ArmMotion::Swing) => State::ReleasingTight {
+            (State::Swing, ArmMotion::HoldHand { .. }) => State::MovingToHold {
+                interpolator: TimedSpline::try_new_transition_timed(
+                    swinging_arm_joints,
+                    hold_joints,
+                    config.moving_to_hold_duration,
+                )?
+                .into(),
+            },
+            (State::PullingBack { interpolator }, ArmMotion::HoldHand { .. })
+            | (State::ReleasingBack { interpolator }, ArmMotion::HoldHand { .. }) => {
+                State::MovingToHold {
+                    interpolator: TimedSpline::try_new_transition_timed(
+                        interpolator.value(),
+                        hold_joints,
+                        config.moving_to_hold_duration,
+                    )?
+                    .into(),
+                }
+            }
+            (State::PullingTight { interpolator }, ArmMotion::HoldHand { .. }) => {
+                let current_joints = interpolator.value();
+                let interpolator = TimedSpline::try_new_transition_timed(
+                    current_joints,
+                    pull_back_joints,
+                    interpolator.current_duration(),
+                )?
+                .into();
+                State::ReleasingTight { interpolator }
+            }
+            (State::Back, ArmMotion::Swing | ArmMotion::HoldHand { .. }) => State::ReleasingTight {
                 interpolator: TimedSpline::try_new_transition_timed(
                     pull_tight_joints,
                     pull_back_joints,
@@ -170,6 +211,28 @@ impl SwingingArm {
                     interpolator: interpolator.into(),
                 }
             }
+            (
+                State::ReleasingTight {
+                    ref mut interpolator,
+                },
+                ArmMotion::HoldHand { .. },
+            ) => {
+                interpolator.advance_by(cycle_duration);
+                if interpolator.is_finished() {
+                    State::MovingToHold {
+                        interpolator: TimedSpline::try_new_transition_timed(
+                            pull_back_joints,
+                            hold_joints,
+                            config.moving_to_hold_duration,
+                        )?
+                        .into(),
+                    }
+                } else {
+                    State::ReleasingTight {
+                        interpolator: interpolator.clone(),
+                    }
+                }
+            }
             (
                 State::ReleasingTight {
                     ref mut interpolator,
@@ -203,14 +266,68 @@ impl SwingingArm {
                     interpolator: interpolator.into(),
                 }
             }
+            (
+                State::MovingToHold {
+                    ref mut interpolator,
+                },
+                ArmMotion::HoldHand { .. },
+            ) => {
+                interpolator.advance_by(cycle_duration);
+                if interpolator.is_finished() {
+                    State::Hold {
+                        joints: hold_joints,
+                    }
+                } else {
+                    State::MovingToHold {
+                        interpolator: interpolator.clone(),
+                    }
+                }
+            }
+            (State::MovingToHold { interpolator }, ArmMotion::Swing) => State::ReleasingBack {
+                interpolator: TimedSpline::try_new_transition_timed(
+                    interpolator.value(),
+                    center_arm_joints,
+                    config.moving_to_hold_duration,
+                )?
+                .into(),
+            },
+            (State::MovingToHold { interpolator }, ArmMotion::PullTight) => State::PullingBack {
+                interpolator: TimedSpline::try_new_transition_timed(
+                    interpolator.value(),
+                    pull_back_joints,
+                    config.pulling_back_duration,
+                )?
+                .into(),
+            },
+            (State::Hold { .. }, ArmMotion::HoldHand { .. }) => State::Hold {
+                joints: hold_joints,
+            },
+            (State::Hold { joints }, ArmMotion::Swing) => State::ReleasingBack {
+                interpolator: TimedSpline::try_new_transition_timed(
+                    *joints,
+                    center_arm_joints,
+                    config.moving_to_hold_duration,
+                )?
+                .into(),
+            },
+            (State::Hold { joints }, ArmMotion::PullTight) => State::PullingBack {
+                interpolator: TimedSpline::try_new_transition_timed(
+                    *joints,
+                    pull_back_joints,
+                    config.pulling_back_duration,
+                )?
+                .into(),
+            },
         };
         Ok(match &self.state {
             State::Swing => swinging_arm_joints,
             State::PullingBack { interpolator }
             | State::ReleasingBack { interpolator }
             | State::ReleasingTight { interpolator }
-            | State::PullingTight { interpolator } => interpolator.value(),
+            | State::PullingTight { interpolator }
+            | State::MovingToHold { interpolator } => interpolator.value(),
             State::Back => pull_tight_joints,
+            State::Hold { joints } => *joints,
         })
     }
 
@@ -220,8 +337,10 @@ impl SwingingArm {
             State::PullingBack { interpolator }
             | State::ReleasingBack { interpolator }
             | State::ReleasingTight { interpolator }
-            | State::PullingTight { interpolator } => interpolator.value().shoulder_pitch,
+            | State::PullingTight { interpolator }
+            | State::MovingToHold { interpolator } => interpolator.value().shoulder_pitch,
             State::Back => config.pull_tight_joints.shoulder_pitch,
+            State::Hold { joints } => joints.shoulder_pitch,
         };
         Ok((shoulder_pitch - FRAC_PI_2) * config.torso_tilt_compensation_factor)
     }
@@ -247,6 +366,25 @@ impl SwingingArm {
         }
     }
 
+    /// Clamps to the joint limits if the hand position is not reachable
+    fn hold_arm_joints(
+        &self,
+        hand_in_robot: Point3<f32>,
+        config: &SwingingArmsParameters,
+    ) -> ArmJoints<f32> {
+        let (_is_reachable, joints) = match self.side {
+            Side::Left => left_arm_angles(hand_in_robot, config.hold_hand_elbow_yaw),
+            Side::Right => right_arm_angles(hand_in_robot, -config.hold_hand_elbow_yaw),
+        };
+        ArmJoints {
+            wrist_yaw: match self.side {
+                Side::Left => -FRAC_PI_2,
+                Side::Right => FRAC_PI_2,
+            },
+            ..joints
+        }
+    }
+
     fn swinging_arm_joints(
         &self,
         foot: FootOffsets,
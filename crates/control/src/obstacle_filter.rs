@@ -4,14 +4,17 @@ use color_eyre::Result;
 use context_attribute::context;
 use filtering::kalman_filter::KalmanFilter;
 use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput};
-use itertools::{chain, iproduct};
-use nalgebra::{distance, point, Isometry2, Matrix2, Point2};
+use itertools::chain;
+use nalgebra::{
+    distance, matrix, vector, Isometry2, Matrix2, Matrix2x4, Matrix4, Matrix4x2, Point2,
+};
 use serde::{Deserialize, Serialize};
 use types::{
     cycle_time::CycleTime,
     detected_feet::DetectedFeet,
     detected_robots::DetectedRobots,
     field_dimensions::FieldDimensions,
+    field_marks::goal_post_positions_from_field_dimensions,
     multivariate_normal_distribution::MultivariateNormalDistribution,
     obstacle_filter::Hypothesis,
     obstacles::{Obstacle, ObstacleKind},
@@ -24,6 +27,7 @@ use types::{
 pub struct ObstacleFilter {
     hypotheses: Vec<Hypothesis>,
     last_primary_state: PrimaryState,
+    last_prediction_time: Option<SystemTime>,
 }
 
 #[context]
@@ -68,12 +72,18 @@ impl ObstacleFilter {
         Ok(Self {
             hypotheses: Vec::new(),
             last_primary_state: PrimaryState::Unstiff,
+            last_prediction_time: None,
         })
     }
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
         let field_dimensions = context.field_dimensions;
         let cycle_start_time = context.cycle_time.start_time;
+        let initial_velocity_covariance = Matrix2::from_diagonal(
+            &context
+                .obstacle_filter_parameters
+                .initial_velocity_covariance,
+        );
         let measurements = context
             .detected_feet_top
             .persistent
@@ -89,8 +99,10 @@ impl ObstacleFilter {
                 .expect("current_odometry_to_last_odometry should not be None");
 
             self.predict_hypotheses_with_odometry(
+                *detection_time,
+                context.obstacle_filter_parameters.velocity_decay_factor,
                 current_odometry_to_last_odometry.inverse(),
-                Matrix2::from_diagonal(&context.obstacle_filter_parameters.process_noise),
+                Matrix4::from_diagonal(&context.obstacle_filter_parameters.process_noise),
             );
 
             let network_robot_obstacles = context.network_robot_obstacles.get(detection_time);
@@ -111,6 +123,7 @@ impl ObstacleFilter {
                             .obstacle_filter_parameters
                             .network_robot_measurement_noise,
                     ),
+                    initial_velocity_covariance,
                 );
             }
 
@@ -134,6 +147,7 @@ impl ObstacleFilter {
                         Matrix2::from_diagonal(
                             &context.obstacle_filter_parameters.feet_measurement_noise,
                         ),
+                        initial_velocity_covariance,
                     );
                 }
             }
@@ -158,6 +172,7 @@ impl ObstacleFilter {
                         Matrix2::from_diagonal(
                             &context.obstacle_filter_parameters.robot_measurement_noise,
                         ),
+                        initial_velocity_covariance,
                     );
                 }
             }
@@ -183,6 +198,7 @@ impl ObstacleFilter {
                         Matrix2::from_diagonal(
                             &context.obstacle_filter_parameters.sonar_measurement_noise,
                         ),
+                        initial_velocity_covariance,
                     );
                 }
             }
@@ -226,11 +242,14 @@ impl ObstacleFilter {
                     ),
                     _ => panic!("Unexpected obstacle radius"),
                 };
+                let velocity = vector![hypothesis.state.mean.z, hypothesis.state.mean.w];
+                let maximum_velocity = context.obstacle_filter_parameters.maximum_velocity;
                 Obstacle {
-                    position: hypothesis.state.mean.into(),
+                    position: hypothesis.state.mean.xy().into(),
                     kind: hypothesis.obstacle_kind,
                     radius_at_hip_height,
                     radius_at_foot_height,
+                    velocity: velocity.cap_magnitude(maximum_velocity),
                 }
             })
             .collect::<Vec<_>>();
@@ -251,17 +270,44 @@ impl ObstacleFilter {
 
     fn predict_hypotheses_with_odometry(
         &mut self,
+        detection_time: SystemTime,
+        velocity_decay_factor: f32,
         last_odometry_to_current_odometry: Isometry2<f32>,
-        process_noise: Matrix2<f32>,
+        process_noise: Matrix4<f32>,
     ) {
+        let time_step = self
+            .last_prediction_time
+            .map_or(Duration::ZERO, |last_prediction_time| {
+                detection_time
+                    .duration_since(last_prediction_time)
+                    .expect("Time has run backwards")
+            })
+            .as_secs_f32();
+        self.last_prediction_time = Some(detection_time);
+        // decay and noise are given per second, detections do not arrive at a fixed rate
+        let velocity_decay = velocity_decay_factor.powf(time_step);
+        let process_noise = process_noise * time_step;
         for hypothesis in self.hypotheses.iter_mut() {
-            let state_prediction = last_odometry_to_current_odometry
+            let constant_velocity_prediction = matrix![
+                1.0, 0.0, time_step, 0.0;
+                0.0, 1.0, 0.0, time_step;
+                0.0, 0.0, velocity_decay, 0.0;
+                0.0, 0.0, 0.0, velocity_decay;
+            ];
+            let rotation = last_odometry_to_current_odometry
                 .rotation
                 .to_rotation_matrix();
-            let control_input_model = Matrix2::identity();
+            let state_rotation = matrix![
+                rotation[(0, 0)], rotation[(0, 1)], 0.0, 0.0;
+                rotation[(1, 0)], rotation[(1, 1)], 0.0, 0.0;
+                0.0, 0.0, rotation[(0, 0)], rotation[(0, 1)];
+                0.0, 0.0, rotation[(1, 0)], rotation[(1, 1)];
+            ];
+            let state_prediction = constant_velocity_prediction * state_rotation;
+            let control_input_model = Matrix4x2::identity();
             let odometry_translation = last_odometry_to_current_odometry.translation.vector;
             hypothesis.state.predict(
-                *state_prediction.matrix(),
+                state_prediction,
                 control_input_model,
                 odometry_translation,
                 process_noise,
@@ -276,12 +322,13 @@ impl ObstacleFilter {
         detection_time: SystemTime,
         matching_distance: f32,
         measurement_noise: Matrix2<f32>,
+        initial_velocity_covariance: Matrix2<f32>,
     ) {
         let mut matching_hypotheses = self
             .hypotheses
             .iter_mut()
             .filter(|hypothesis| {
-                (hypothesis.state.mean - detected_position.coords).norm() < matching_distance
+                (hypothesis.state.mean.xy() - detected_position.coords).norm() < matching_distance
             })
             .peekable();
         if matching_hypotheses.peek().is_none() {
@@ -290,12 +337,13 @@ impl ObstacleFilter {
                 detected_obstacle_kind,
                 detection_time,
                 measurement_noise,
+                initial_velocity_covariance,
             );
             return;
         }
         matching_hypotheses.for_each(|hypothesis| {
             hypothesis.state.update(
-                Matrix2::identity(),
+                Matrix2x4::identity(),
                 detected_position.coords,
                 measurement_noise * detected_position.coords.norm_squared(),
             );
@@ -314,9 +362,17 @@ impl ObstacleFilter {
         detected_position: Point2<f32>,
         obstacle_kind: ObstacleKind,
         detection_time: SystemTime,
-        initial_covariance: Matrix2<f32>,
+        initial_position_covariance: Matrix2<f32>,
+        initial_velocity_covariance: Matrix2<f32>,
     ) {
-        let initial_state = detected_position.coords;
+        let initial_state = vector![detected_position.x, detected_position.y, 0.0, 0.0];
+        let mut initial_covariance = Matrix4::zeros();
+        initial_covariance
+            .fixed_view_mut::<2, 2>(0, 0)
+            .copy_from(&initial_position_covariance);
+        initial_covariance
+            .fixed_view_mut::<2, 2>(2, 2)
+            .copy_from(&initial_velocity_covariance);
         let new_hypothesis = Hypothesis {
             state: MultivariateNormalDistribution {
                 mean: initial_state,
@@ -346,13 +402,13 @@ impl ObstacleFilter {
                 deduplicated_hypotheses
                     .iter_mut()
                     .find(|existing_hypothesis| {
-                        (existing_hypothesis.state.mean - hypothesis.state.mean).norm()
+                        (existing_hypothesis.state.mean.xy() - hypothesis.state.mean.xy()).norm()
                             < merge_distance
                     });
             match hypothesis_in_merge_distance {
                 Some(existing_hypothesis) => {
                     existing_hypothesis.state.update(
-                        Matrix2::identity(),
+                        Matrix4::identity(),
                         hypothesis.state.mean,
                         hypothesis.state.covariance,
                     );
@@ -376,19 +432,88 @@ fn calculate_goal_post_positions(
     current_robot_to_field
         .map(|robot_to_field| {
             let field_to_robot = robot_to_field.inverse();
-            iproduct!([-1.0, 1.0], [-1.0, 1.0]).map(move |(x_sign, y_sign)| {
-                let radius = field_dimensions.goal_post_diameter / 2.0;
-                let position_on_field = point![
-                    x_sign
-                        * (field_dimensions.length / 2.0
-                            + field_dimensions.goal_post_diameter / 2.0
-                            - field_dimensions.line_width / 2.0),
-                    y_sign * (field_dimensions.goal_inner_width / 2.0 + radius)
-                ];
-                field_to_robot * position_on_field
-            })
+            goal_post_positions_from_field_dimensions(field_dimensions)
+                .into_iter()
+                .map(move |position_on_field| field_to_robot * position_on_field)
         })
         .into_iter()
         .flatten()
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn hypotheses_are_predicted_with_the_elapsed_time() {
+        let mut obstacle_filter = ObstacleFilter::new(CreationContext {}).unwrap();
+        obstacle_filter.predict_hypotheses_with_odometry(
+            UNIX_EPOCH,
+            1.0,
+            Isometry2::identity(),
+            Matrix4::zeros(),
+        );
+        obstacle_filter.hypotheses.push(Hypothesis {
+            state: MultivariateNormalDistribution {
+                mean: vector![1.0, 0.0, 1.0, 0.0],
+                covariance: Matrix4::identity(),
+            },
+            measurement_count: 1,
+            last_update: UNIX_EPOCH,
+            obstacle_kind: ObstacleKind::Robot,
+        });
+
+        for milliseconds in [10, 40, 45, 100] {
+            obstacle_filter.predict_hypotheses_with_odometry(
+                UNIX_EPOCH + Duration::from_millis(milliseconds),
+                1.0,
+                Isometry2::identity(),
+                Matrix4::zeros(),
+            );
+        }
+
+        assert_relative_eq!(
+            obstacle_filter.hypotheses[0].state.mean,
+            vector![1.1, 0.0, 1.0, 0.0],
+            epsilon = 0.0001
+        );
+    }
+
+    #[test]
+    fn decay_and_noise_depend_on_the_elapsed_time_only() {
+        let mut obstacle_filter = ObstacleFilter::new(CreationContext {}).unwrap();
+        obstacle_filter.predict_hypotheses_with_odometry(
+            UNIX_EPOCH,
+            0.5,
+            Isometry2::identity(),
+            Matrix4::zeros(),
+        );
+        obstacle_filter.hypotheses.push(Hypothesis {
+            state: MultivariateNormalDistribution {
+                mean: vector![0.0, 0.0, 1.0, 0.0],
+                covariance: Matrix4::zeros(),
+            },
+            measurement_count: 1,
+            last_update: UNIX_EPOCH,
+            obstacle_kind: ObstacleKind::Robot,
+        });
+
+        for milliseconds in [100, 400, 450, 1000] {
+            obstacle_filter.predict_hypotheses_with_odometry(
+                UNIX_EPOCH + Duration::from_millis(milliseconds),
+                0.5,
+                Isometry2::identity(),
+                Matrix4::from_diagonal(&vector![0.2, 0.2, 0.0, 0.0]),
+            );
+        }
+
+        let state = &obstacle_filter.hypotheses[0].state;
+        assert_relative_eq!(state.mean.z, 0.5, epsilon = 0.0001);
+        assert_relative_eq!(state.covariance[(0, 0)], 0.2, epsilon = 0.0001);
+    }
+}
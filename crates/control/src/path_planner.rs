@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use color_eyre::{eyre::eyre, Result};
 use geometry::{arc::Arc, circle::Circle, line_segment::LineSegment, orientation::Orientation};
-use nalgebra::{distance, point, vector, Isometry2, Point2, UnitComplex};
+use nalgebra::{distance, point, vector, Isometry2, Point2, UnitComplex, Vector2};
 use ordered_float::NotNan;
 use smallvec::SmallVec;
 
@@ -11,6 +13,7 @@ use types::{
     path_obstacles::{PathObstacle, PathObstacleShape},
     planned_path::PathSegment,
     rule_obstacles::RuleObstacle,
+    step_plan::Step,
 };
 
 use crate::a_star::{a_star_search, DynamicMap};
@@ -34,6 +37,74 @@ impl From<Point2<f32>> for PathNode {
     }
 }
 
+/// Maximum speeds of the walking robot
+#[derive(Clone, Copy, Debug)]
+pub struct WalkingSpeed {
+    /// Forward speed in m/s
+    pub translation: f32,
+    /// Turning speed in rad/s
+    pub rotation: f32,
+}
+
+impl WalkingSpeed {
+    pub fn from_step_size(max_step_size: &Step, step_duration: Duration) -> Self {
+        let step_duration = step_duration.as_secs_f32();
+        Self {
+            translation: max_step_size.forward / step_duration,
+            rotation: max_step_size.turn / step_duration,
+        }
+    }
+
+    /// Estimated duration to walk along a path, facing forward along its segments
+    ///
+    /// Includes turning towards the first segment and turning while walking along arcs. A path
+    /// which cannot be walked because a speed is zero takes [`Duration::MAX`].
+    pub fn walk_duration(&self, path: &[PathSegment]) -> Duration {
+        let initial_turn = path
+            .first()
+            .map(|segment| {
+                let direction = match segment {
+                    PathSegment::LineSegment(line_segment) => line_segment.1 - line_segment.0,
+                    PathSegment::Arc(arc, orientation) => {
+                        orientation.rotate_vector_90_degrees(arc.start - arc.circle.center)
+                    }
+                };
+                time_to_cover(direction.y.atan2(direction.x).abs(), self.rotation)
+            })
+            .unwrap_or_default();
+        let walk_time: f32 = path
+            .iter()
+            .map(|segment| {
+                let length = segment.length();
+                match segment {
+                    PathSegment::LineSegment(_) => time_to_cover(length, self.translation),
+                    PathSegment::Arc(arc, _) => time_to_cover(length, self.translation)
+                        .max(time_to_cover(length / arc.circle.radius, self.rotation)),
+                }
+            })
+            .sum();
+        Duration::try_from_secs_f32(initial_turn + walk_time).unwrap_or(Duration::MAX)
+    }
+}
+
+/// Time to cover `distance` with `speed`, infinite if there is a distance but (almost) no speed
+fn time_to_cover(distance: f32, speed: f32) -> f32 {
+    if distance.is_nan() || distance <= 0.0 {
+        0.0
+    } else if speed <= f32::EPSILON {
+        f32::INFINITY
+    } else {
+        distance / speed
+    }
+}
+
+/// A planned path together with the estimated time to walk along it
+#[derive(Clone, Debug)]
+pub struct TimedPath {
+    pub path: Vec<PathSegment>,
+    pub arrival_time: Duration,
+}
+
 #[derive(Debug, Default)]
 pub struct PathPlanner {
     /// The first node is always the start, the second the destination
@@ -74,11 +145,32 @@ impl PathPlanner {
     }
 
     pub fn with_obstacles(&mut self, obstacles: &[Obstacle], own_robot_radius: f32) {
+        self.with_moving_obstacles(obstacles, own_robot_radius, Point2::origin(), 0.0, 0.0);
+    }
+
+    /// Adds obstacles covering the area each obstacle sweeps until the robot could reach it
+    ///
+    /// The time until the robot meets an obstacle is estimated as if the robot walked straight
+    /// towards it from `start` with `walking_speed` and is limited to `maximum_horizon`. The swept
+    /// line segment is enclosed by a single circle around its center.
+    pub fn with_moving_obstacles(
+        &mut self,
+        obstacles: &[Obstacle],
+        own_robot_radius: f32,
+        start: Point2<f32>,
+        walking_speed: f32,
+        maximum_horizon: f32,
+    ) {
         let new_obstacles = obstacles.iter().map(|obstacle| {
-            let position = obstacle.position;
-            let radius = obstacle.radius_at_hip_height + own_robot_radius;
+            let meeting_time =
+                time_to_meet(obstacle.position - start, obstacle.velocity, walking_speed)
+                    .unwrap_or(maximum_horizon)
+                    .clamp(0.0, maximum_horizon.max(0.0));
+            let half_displacement = obstacle.velocity * meeting_time / 2.0;
+            let radius =
+                obstacle.radius_at_hip_height + own_robot_radius + half_displacement.norm();
             PathObstacle::from(PathObstacleShape::Circle(Circle {
-                center: position,
+                center: obstacle.position + half_displacement,
                 radius,
             }))
         });
@@ -354,6 +446,18 @@ impl PathPlanner {
         path_segments
     }
 
+    pub fn plan_with_arrival_time(
+        &mut self,
+        start: Point2<f32>,
+        destination: Point2<f32>,
+        walking_speed: &WalkingSpeed,
+    ) -> Result<Option<TimedPath>> {
+        Ok(self.plan(start, destination)?.map(|path| TimedPath {
+            arrival_time: walking_speed.walk_duration(&path),
+            path,
+        }))
+    }
+
     fn add_tangent_between_point_and_obstacle(
         &mut self,
         tangent: LineSegment,
@@ -538,9 +642,36 @@ impl DynamicMap for PathPlanner {
     }
 }
 
+/// Earliest time at which a robot walking straight with `walking_speed` can meet an obstacle
+/// at `relative_position` moving with `velocity`
+fn time_to_meet(
+    relative_position: Vector2<f32>,
+    velocity: Vector2<f32>,
+    walking_speed: f32,
+) -> Option<f32> {
+    let a = velocity.norm_squared() - walking_speed.powi(2);
+    let b = 2.0 * relative_position.dot(&velocity);
+    let c = relative_position.norm_squared();
+    if a.abs() < f32::EPSILON {
+        return (b < 0.0).then(|| -c / b);
+    }
+    let discriminant = b.powi(2) - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let square_root = discriminant.sqrt();
+    [
+        (-b - square_root) / (2.0 * a),
+        (-b + square_root) / (2.0 * a),
+    ]
+    .into_iter()
+    .filter(|time| *time >= 0.0)
+    .min_by(f32::total_cmp)
+}
+
 #[cfg(test)]
 mod tests {
-    use std::f32::consts::PI;
+    use std::f32::consts::{FRAC_PI_2, PI};
 
     use approx::assert_relative_eq;
     use nalgebra::point;
@@ -850,4 +981,108 @@ mod tests {
             .expect("Path error")
             .is_none());
     }
+
+    #[test]
+    fn moving_obstacle_blocks_direct_path() {
+        let obstacle = Obstacle {
+            velocity: vector![0.0, -1.0],
+            ..Obstacle::robot(point![1.0, 0.5], 0.2, 0.2)
+        };
+
+        let mut static_map = PathPlanner::default();
+        static_map.with_obstacles(&[obstacle], 0.0);
+        let static_path = static_map
+            .plan(Point2::origin(), point![2.0, 0.0])
+            .expect("Path error")
+            .expect("Path was none");
+        assert_eq!(static_path.len(), 1);
+
+        let mut moving_map = PathPlanner::default();
+        moving_map.with_moving_obstacles(&[obstacle], 0.0, Point2::origin(), 1.0, 1.0);
+        let moving_path = moving_map
+            .plan(Point2::origin(), point![2.0, 0.0])
+            .expect("Path error")
+            .expect("Path was none");
+        assert!(moving_path.len() > 1);
+    }
+
+    #[test]
+    fn time_to_meet_approaching_and_fleeing_obstacles() {
+        assert_relative_eq!(
+            time_to_meet(vector![2.0, 0.0], vector![-1.0, 0.0], 1.0).unwrap(),
+            1.0
+        );
+        assert_relative_eq!(
+            time_to_meet(vector![1.0, 0.0], vector![0.5, 0.0], 1.0).unwrap(),
+            2.0
+        );
+        assert!(time_to_meet(vector![1.0, 0.0], vector![2.0, 0.0], 1.0).is_none());
+    }
+
+    #[test]
+    fn walk_duration_includes_initial_turn() {
+        let walking_speed = WalkingSpeed {
+            translation: 0.5,
+            rotation: 1.0,
+        };
+        let straight = walking_speed.walk_duration(&[PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![1.0, 0.0],
+        ))]);
+        assert_relative_eq!(straight.as_secs_f32(), 2.0);
+
+        let sideways = walking_speed.walk_duration(&[PathSegment::LineSegment(LineSegment(
+            Point2::origin(),
+            point![0.0, 1.0],
+        ))]);
+        assert_relative_eq!(sideways.as_secs_f32(), 2.0 + FRAC_PI_2);
+    }
+
+    #[test]
+    fn walk_duration_without_speed_is_maximal() {
+        let standing = WalkingSpeed {
+            translation: 0.0,
+            rotation: 0.0,
+        };
+        assert_eq!(
+            standing.walk_duration(&[PathSegment::LineSegment(LineSegment(
+                Point2::origin(),
+                point![1.0, 0.0],
+            ))]),
+            Duration::MAX
+        );
+        assert_eq!(
+            standing.walk_duration(&[PathSegment::LineSegment(LineSegment(
+                Point2::origin(),
+                Point2::origin(),
+            ))]),
+            Duration::ZERO
+        );
+
+        let not_turning = WalkingSpeed {
+            translation: 0.5,
+            rotation: 1e-9,
+        };
+        assert_eq!(
+            not_turning.walk_duration(&[PathSegment::LineSegment(LineSegment(
+                Point2::origin(),
+                point![0.0, 1.0],
+            ))]),
+            Duration::MAX
+        );
+    }
+
+    #[test]
+    fn predicted_obstacle_is_swept_until_meeting_time() {
+        let obstacle = Obstacle {
+            velocity: vector![-1.0, 0.0],
+            ..Obstacle::robot(point![2.0, 0.0], 0.2, 0.2)
+        };
+        let mut map = PathPlanner::default();
+        map.with_moving_obstacles(&[obstacle], 0.1, Point2::origin(), 1.0, 5.0);
+
+        let circle = map.obstacles[0].shape.as_circle().unwrap();
+        assert_relative_eq!(circle.center, point![1.5, 0.0]);
+        assert_relative_eq!(circle.radius, 0.8);
+    }
 }
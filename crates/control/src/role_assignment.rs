@@ -1,13 +1,16 @@
 use color_eyre::{eyre::WrapErr, Result};
 use context_attribute::context;
-use framework::{MainOutput, PerceptionInput};
+use framework::{AdditionalOutput, MainOutput, PerceptionInput};
 use hardware::NetworkInterface;
-use nalgebra::{Isometry2, Point2, Vector2};
+use nalgebra::{Isometry2, Point2};
 use serde::{Deserialize, Serialize};
 use spl_network_messages::{
-    GameControllerReturnMessage, GamePhase, HulkMessage, Penalty, PlayerNumber, Team,
+    GameControllerReturnMessage, GamePhase, HulkMessage, Intention, Penalty, PlayerNumber, Team,
+};
+use std::{
+    cmp::Ordering,
+    time::{Duration, SystemTime},
 };
-use std::time::{Duration, SystemTime};
 use types::{
     ball_position::BallPosition,
     cycle_time::CycleTime,
@@ -15,14 +18,22 @@ use types::{
     field_dimensions::FieldDimensions,
     filtered_game_controller_state::FilteredGameControllerState,
     initial_pose::InitialPose,
+    message_budget::MessageBudgetDecision,
     messages::{IncomingMessage, OutgoingMessage},
     parameters::SplNetworkParameters,
     players::Players,
     primary_state::PrimaryState,
     roles::Role,
+    team_ball::TeamBall,
+    teammate_intention::TeammateIntention,
 };
 
-use crate::localization::generate_initial_pose;
+use crate::{
+    localization::generate_initial_pose,
+    message_budget::{
+        classify_trigger, messages_for_periodic_updates, periodic_interval, remaining_game_time,
+    },
+};
 
 #[derive(Deserialize, Serialize)]
 pub struct RoleAssignment {
@@ -31,8 +42,11 @@ pub struct RoleAssignment {
     last_transmitted_spl_striker_message: Option<SystemTime>,
     role: Role,
     role_initialized: bool,
-    team_ball: Option<BallPosition>,
+    team_ball: Option<TeamBall>,
     last_time_keeper_penalized: Option<SystemTime>,
+    was_fallen: bool,
+    teammate_intentions: Players<Option<TeammateIntention>>,
+    last_teammate_message_times: Players<Option<SystemTime>>,
 }
 
 #[context]
@@ -40,15 +54,19 @@ pub struct CreationContext {}
 
 #[context]
 pub struct CycleContext {
+    message_budget: AdditionalOutput<Option<MessageBudgetDecision>, "message_budget">,
+
     ball_position: Input<Option<BallPosition>, "ball_position?">,
     fall_state: Input<FallState, "fall_state">,
     filtered_game_controller_state:
         Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+    fused_team_ball: Input<Option<TeamBall>, "fused_team_ball?">,
     primary_state: Input<PrimaryState, "primary_state">,
     robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
     cycle_time: Input<CycleTime, "cycle_time">,
     network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
     time_to_reach_kick_position: CyclerState<Duration, "time_to_reach_kick_position">,
+    intention: CyclerState<Intention, "intention">,
 
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     forced_role: Parameter<Option<Role>, "role_assignment.forced_role?">,
@@ -65,9 +83,9 @@ pub struct CycleContext {
 #[context]
 #[derive(Default)]
 pub struct MainOutputs {
-    pub team_ball: MainOutput<Option<BallPosition>>,
     pub network_robot_obstacles: MainOutput<Vec<Point2<f32>>>,
     pub role: MainOutput<Role>,
+    pub teammate_intentions: MainOutput<Players<Option<TeammateIntention>>>,
 }
 
 impl RoleAssignment {
@@ -80,10 +98,16 @@ impl RoleAssignment {
             role_initialized: false,
             team_ball: None,
             last_time_keeper_penalized: None,
+            was_fallen: false,
+            teammate_intentions: Default::default(),
+            last_teammate_message_times: Default::default(),
         })
     }
 
-    pub fn cycle(&mut self, context: CycleContext<impl NetworkInterface>) -> Result<MainOutputs> {
+    pub fn cycle(
+        &mut self,
+        mut context: CycleContext<impl NetworkInterface>,
+    ) -> Result<MainOutputs> {
         let cycle_start_time = context.cycle_time.start_time;
         let primary_state = *context.primary_state;
         let mut role = self.role;
@@ -143,16 +167,55 @@ impl RoleAssignment {
                     .unwrap(),
             )? > context.spl_network.game_controller_return_message_interval;
 
+        let budget = context
+            .filtered_game_controller_state
+            .map(|game_controller_state| {
+                let remaining_game_time = remaining_game_time(
+                    game_controller_state.half,
+                    game_controller_state.remaining_time_in_half,
+                    context.spl_network.half_duration,
+                );
+                let messages_for_periodic_updates = messages_for_periodic_updates(
+                    game_controller_state.remaining_number_of_messages,
+                    context.spl_network,
+                );
+                MessageBudgetDecision {
+                    remaining_messages: game_controller_state.remaining_number_of_messages,
+                    remaining_game_time,
+                    messages_for_periodic_updates,
+                    periodic_interval: periodic_interval(
+                        remaining_game_time,
+                        messages_for_periodic_updates,
+                        context.spl_network.spl_striker_message_send_interval,
+                    ),
+                    trigger: None,
+                    send: false,
+                }
+            });
+        let spl_striker_message_send_interval = budget.map_or(
+            context.spl_network.spl_striker_message_send_interval,
+            |budget| budget.periodic_interval,
+        );
+
         let mut send_spl_striker_message = self.last_transmitted_spl_striker_message.is_none()
             || cycle_start_time
                 .duration_since(self.last_transmitted_spl_striker_message.unwrap())?
-                > context.spl_network.spl_striker_message_send_interval;
-
+                > spl_striker_message_send_interval;
+        let periodic_message_is_due = send_spl_striker_message;
+
+        // the striker sends less often when the budget is low, so everyone waits longer for it
+        let spl_striker_message_receive_timeout = context
+            .spl_network
+            .spl_striker_message_receive_timeout
+            .saturating_add(
+                spl_striker_message_send_interval
+                    .saturating_sub(context.spl_network.spl_striker_message_send_interval),
+            );
         let spl_striker_message_timeout = match self.last_received_spl_striker_message {
             None => false,
             Some(last_received_spl_striker_message) => {
                 cycle_start_time.duration_since(last_received_spl_striker_message)?
-                    > context.spl_network.spl_striker_message_receive_timeout
+                    > spl_striker_message_receive_timeout
             }
         };
 
@@ -220,8 +283,8 @@ impl RoleAssignment {
         if spl_messages.peek().is_none() {
             (role, send_spl_striker_message, team_ball) = process_role_state_machine(
                 role,
-                robot_to_field,
                 context.ball_position,
+                context.fused_team_ball.copied(),
                 primary_state,
                 None,
                 Some(*context.time_to_reach_kick_position),
@@ -240,11 +303,19 @@ impl RoleAssignment {
                     (robot_to_field.inverse() * spl_message.robot_to_field) * Point2::origin();
                 if spl_message.player_number != *context.player_number {
                     network_robot_obstacles.push(sender_position);
+                    self.last_teammate_message_times[spl_message.player_number] =
+                        Some(cycle_start_time);
+                    self.teammate_intentions[spl_message.player_number] = Some(TeammateIntention {
+                        player_number: spl_message.player_number,
+                        role: spl_message.role,
+                        robot_to_field: spl_message.robot_to_field,
+                        intention: spl_message.intention,
+                    });
                 }
                 (role, send_spl_striker_message, team_ball) = process_role_state_machine(
                     role,
-                    robot_to_field,
                     context.ball_position,
+                    context.fused_team_ball.copied(),
                     primary_state,
                     Some(spl_message),
                     Some(*context.time_to_reach_kick_position),
@@ -272,18 +343,33 @@ impl RoleAssignment {
             }
         }
 
-        if send_spl_striker_message
-            && primary_state == PrimaryState::Playing
-            && silence_interval_has_passed
+        let is_fallen = matches!(context.fall_state, FallState::Fallen { .. });
+        let striker_fell = role == Role::Striker && is_fallen && !self.was_fallen;
+        self.was_fallen = is_fallen;
+        let trigger = classify_trigger(
+            self.role,
+            role,
+            striker_fell,
+            periodic_message_is_due,
+            send_spl_striker_message,
+        );
+
+        let mut budget = budget.map(|budget| MessageBudgetDecision { trigger, ..budget });
+        if let Some(trigger) = trigger
+            .filter(|_| primary_state == PrimaryState::Playing && silence_interval_has_passed)
         {
-            self.last_transmitted_spl_striker_message = Some(cycle_start_time);
             self.last_received_spl_striker_message = Some(cycle_start_time);
-            if let Some(game_controller_state) = context.filtered_game_controller_state {
-                if game_controller_state.remaining_number_of_messages
-                    > context
-                        .spl_network
-                        .remaining_amount_of_messages_to_stop_sending
-                {
+            if let Some(budget) = budget.as_mut() {
+                budget.send = if trigger.is_event() {
+                    budget.remaining_messages
+                        > context
+                            .spl_network
+                            .remaining_amount_of_messages_to_stop_sending
+                } else {
+                    budget.messages_for_periodic_updates > 0
+                };
+                if budget.send {
+                    self.last_transmitted_spl_striker_message = Some(cycle_start_time);
                     let ball_position = if context.ball_position.is_none() && team_ball.is_some() {
                         team_ball_to_network_ball_position(
                             team_ball,
@@ -297,14 +383,17 @@ impl RoleAssignment {
                         .hardware
                         .write_to_network(OutgoingMessage::Spl(HulkMessage {
                             player_number: *context.player_number,
-                            fallen: matches!(context.fall_state, FallState::Fallen { .. }),
+                            fallen: is_fallen,
                             robot_to_field,
                             ball_position,
                             time_to_reach_kick_position: Some(*context.time_to_reach_kick_position),
+                            role: context.forced_role.copied().unwrap_or(role),
+                            intention: *context.intention,
                         }))?;
                 }
             }
         }
+        context.message_budget.fill_if_subscribed(|| budget);
 
         if let Some(forced_role) = context.forced_role {
             self.role = *forced_role;
@@ -317,12 +406,23 @@ impl RoleAssignment {
             if game_controller_state.penalties.one.is_some() {
                 self.last_time_keeper_penalized = Some(cycle_start_time);
             }
+            for (player_number, penalty) in game_controller_state.penalties.iter() {
+                if penalty.is_some() {
+                    self.teammate_intentions[player_number] = None;
+                }
+            }
         }
+        remove_outdated_teammate_intentions(
+            &mut self.teammate_intentions,
+            &self.last_teammate_message_times,
+            cycle_start_time,
+            context.spl_network.spl_striker_message_receive_timeout,
+        );
 
         Ok(MainOutputs {
             role: self.role.into(),
-            team_ball: self.team_ball.into(),
             network_robot_obstacles: network_robot_obstacles.into(),
+            teammate_intentions: self.teammate_intentions.into(),
         })
     }
 }
@@ -330,19 +430,19 @@ impl RoleAssignment {
 #[allow(clippy::too_many_arguments)]
 fn process_role_state_machine(
     current_role: Role,
-    current_pose: Isometry2<f32>,
     detected_own_ball: Option<&BallPosition>,
+    fused_team_ball: Option<TeamBall>,
     primary_state: PrimaryState,
     incoming_message: Option<&HulkMessage>,
     time_to_reach_kick_position: Option<Duration>,
     send_spl_striker_message: bool,
-    team_ball: Option<BallPosition>,
+    team_ball: Option<TeamBall>,
     cycle_start_time: SystemTime,
     filtered_game_controller_state: Option<&FilteredGameControllerState>,
     player_number: PlayerNumber,
     striker_trusts_team_ball: Duration,
     optional_roles: &[Role],
-) -> (Role, bool, Option<BallPosition>) {
+) -> (Role, bool, Option<TeamBall>) {
     if let Some(game_controller_state) = filtered_game_controller_state {
         match game_controller_state.game_phase {
             GamePhase::PenaltyShootout {
@@ -358,13 +458,7 @@ fn process_role_state_machine(
     if primary_state != PrimaryState::Playing {
         match detected_own_ball {
             None => return (current_role, false, team_ball),
-            Some(..) => {
-                return (
-                    current_role,
-                    false,
-                    team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-                )
-            }
+            Some(..) => return (current_role, false, fused_team_ball),
         }
     }
 
@@ -411,10 +505,11 @@ fn process_role_state_machine(
                 }
             }
             _ => decide_if_claiming_striker_or_other_role(
+                current_role,
                 spl_message,
+                fused_team_ball,
                 time_to_reach_kick_position,
                 player_number,
-                cycle_start_time,
                 filtered_game_controller_state,
                 optional_roles,
             ),
@@ -427,17 +522,14 @@ fn process_role_state_machine(
         (Role::Striker, Some(..), Some(spl_message)) => match &spl_message.ball_position {
             None => {
                 // another Striker became Loser, so we claim striker since we see a ball
-                (
-                    Role::Striker,
-                    true,
-                    team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-                )
+                (Role::Striker, true, fused_team_ball)
             }
             _ => decide_if_claiming_striker_or_other_role(
+                current_role,
                 spl_message,
+                fused_team_ball,
                 time_to_reach_kick_position,
                 player_number,
-                cycle_start_time,
                 filtered_game_controller_state,
                 optional_roles,
             ),
@@ -449,37 +541,31 @@ fn process_role_state_machine(
         (Role::Loser, None, Some(spl_message)) => match &spl_message.ball_position {
             None => (Role::Loser, false, None), //edge-case, a striker (which should not exist) lost the ball
             _ => decide_if_claiming_striker_or_other_role(
+                current_role,
                 spl_message,
+                fused_team_ball,
                 time_to_reach_kick_position,
                 player_number,
-                cycle_start_time,
                 filtered_game_controller_state,
                 optional_roles,
             ),
         },
 
         //Loser found ball and becomes Striker
-        (Role::Loser, Some(..), None) => (
-            Role::Striker,
-            true,
-            team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-        ),
+        (Role::Loser, Some(..), None) => (Role::Striker, true, fused_team_ball),
 
         // Edge-case, Loser found Ball at the same time as receiving a message
         (Role::Loser, Some(..), Some(spl_message)) => match &spl_message.ball_position {
             None => {
                 // another Striker became Loser, so we claim striker since we see a ball
-                (
-                    Role::Striker,
-                    true,
-                    team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-                )
+                (Role::Striker, true, fused_team_ball)
             }
             _ => decide_if_claiming_striker_or_other_role(
+                current_role,
                 spl_message,
+                fused_team_ball,
                 time_to_reach_kick_position,
                 player_number,
-                cycle_start_time,
                 filtered_game_controller_state,
                 optional_roles,
             ),
@@ -491,34 +577,28 @@ fn process_role_state_machine(
         (Role::Searcher, None, Some(spl_message)) => match &spl_message.ball_position {
             None => (Role::Searcher, false, team_ball), //edge-case, a striker (which should not exist) lost the ball
             _ => decide_if_claiming_striker_or_other_role(
+                current_role,
                 spl_message,
+                fused_team_ball,
                 time_to_reach_kick_position,
                 player_number,
-                cycle_start_time,
                 filtered_game_controller_state,
                 optional_roles,
             ),
         },
 
         //Searcher found ball and becomes Striker
-        (Role::Searcher, Some(..), None) => (
-            Role::Striker,
-            true,
-            team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-        ),
+        (Role::Searcher, Some(..), None) => (Role::Striker, true, fused_team_ball),
 
         // TODO: Searcher found Ball at the same time as receiving a message
         (Role::Searcher, Some(..), Some(spl_message)) => match &spl_message.ball_position {
-            None => (
-                Role::Striker,
-                true,
-                team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-            ),
+            None => (Role::Striker, true, fused_team_ball),
             _ => decide_if_claiming_striker_or_other_role(
+                current_role,
                 spl_message,
+                fused_team_ball,
                 time_to_reach_kick_position,
                 player_number,
-                cycle_start_time,
                 filtered_game_controller_state,
                 optional_roles,
             ),
@@ -537,10 +617,11 @@ fn process_role_state_machine(
                 }
             }
             _ => decide_if_claiming_striker_or_other_role(
+                current_role,
                 spl_message,
+                fused_team_ball,
                 time_to_reach_kick_position,
                 player_number,
-                cycle_start_time,
                 filtered_game_controller_state,
                 optional_roles,
             ),
@@ -548,30 +629,19 @@ fn process_role_state_machine(
 
         // Claim Striker if team-ball position is None
         (other_role, Some(..), None) => match team_ball {
-            None => (
-                Role::Striker,
-                true,
-                team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-            ),
-            Some(..) => (
-                other_role,
-                false,
-                team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-            ),
+            None => (Role::Striker, true, fused_team_ball),
+            Some(..) => (other_role, false, fused_team_ball),
         },
 
         // if message is Ball-Lost => Striker, claim Striker ? design-decision: which ball to trust ?
         (_other_role, Some(..), Some(spl_message)) => match &spl_message.ball_position {
-            None => (
-                Role::Striker,
-                true,
-                team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
-            ),
+            None => (Role::Striker, true, fused_team_ball),
             _ => decide_if_claiming_striker_or_other_role(
+                current_role,
                 spl_message,
+                fused_team_ball,
                 time_to_reach_kick_position,
                 player_number,
-                cycle_start_time,
                 filtered_game_controller_state,
                 optional_roles,
             ),
@@ -580,19 +650,21 @@ fn process_role_state_machine(
 }
 
 fn decide_if_claiming_striker_or_other_role(
+    current_role: Role,
     spl_message: &HulkMessage,
+    team_ball: Option<TeamBall>,
     time_to_reach_kick_position: Option<Duration>,
     player_number: PlayerNumber,
-    cycle_start_time: SystemTime,
     filtered_game_controller_state: Option<&FilteredGameControllerState>,
     optional_roles: &[Role],
-) -> (Role, bool, Option<BallPosition>) {
-    if time_to_reach_kick_position < spl_message.time_to_reach_kick_position {
-        (
-            Role::Striker,
-            true,
-            team_ball_from_spl_message(cycle_start_time, spl_message),
-        )
+) -> (Role, bool, Option<TeamBall>) {
+    let claims_striker = if current_role == Role::Striker && spl_message.role == Role::Striker {
+        wins_striker_conflict(time_to_reach_kick_position, player_number, spl_message)
+    } else {
+        time_to_reach_kick_position < spl_message.time_to_reach_kick_position
+    };
+    if claims_striker {
+        (Role::Striker, true, team_ball)
     } else {
         (
             generate_role(
@@ -602,11 +674,48 @@ fn decide_if_claiming_striker_or_other_role(
                 optional_roles,
             ),
             false,
-            team_ball_from_spl_message(cycle_start_time, spl_message),
+            team_ball,
         )
     }
 }
 
+/// Equal times to reach the kick position are resolved by the higher player number, so exactly
+/// one of both robots claims the striker role
+fn wins_striker_conflict(
+    time_to_reach_kick_position: Option<Duration>,
+    player_number: PlayerNumber,
+    spl_message: &HulkMessage,
+) -> bool {
+    match time_to_reach_kick_position.cmp(&spl_message.time_to_reach_kick_position) {
+        Ordering::Less => true,
+        Ordering::Equal => player_number > spl_message.player_number,
+        Ordering::Greater => false,
+    }
+}
+
+/// Intentions of teammates which stopped sending messages are not announced any longer
+fn remove_outdated_teammate_intentions(
+    teammate_intentions: &mut Players<Option<TeammateIntention>>,
+    last_teammate_message_times: &Players<Option<SystemTime>>,
+    cycle_start_time: SystemTime,
+    timeout: Duration,
+) {
+    for (player_number, last_message_time) in last_teammate_message_times.iter() {
+        let is_outdated = match last_message_time {
+            Some(last_message_time) => {
+                cycle_start_time
+                    .duration_since(*last_message_time)
+                    .unwrap_or_default()
+                    > timeout
+            }
+            None => true,
+        };
+        if is_outdated {
+            teammate_intentions[player_number] = None;
+        }
+    }
+}
+
 fn seen_ball_to_network_ball_position(
     ball: Option<&BallPosition>,
     cycle_start_time: SystemTime,
@@ -618,7 +727,7 @@ fn seen_ball_to_network_ball_position(
 }
 
 fn team_ball_to_network_ball_position(
-    team_ball: Option<BallPosition>,
+    team_ball: Option<TeamBall>,
     robot_to_field: Isometry2<f32>,
     cycle_start_time: SystemTime,
 ) -> Option<spl_network_messages::BallPosition> {
@@ -630,32 +739,6 @@ fn team_ball_to_network_ball_position(
     })
 }
 
-fn team_ball_from_spl_message(
-    cycle_start_time: SystemTime,
-    spl_message: &HulkMessage,
-) -> Option<BallPosition> {
-    spl_message
-        .ball_position
-        .as_ref()
-        .map(|ball_position| BallPosition {
-            position: spl_message.robot_to_field * ball_position.relative_position,
-            velocity: Vector2::zeros(),
-            last_seen: cycle_start_time - ball_position.age,
-        })
-}
-
-fn team_ball_from_seen_ball(
-    ball: Option<&BallPosition>,
-    current_pose: Isometry2<f32>,
-    cycle_start_time: SystemTime,
-) -> Option<BallPosition> {
-    ball.as_ref().map(|ball| BallPosition {
-        position: (current_pose * ball.position),
-        velocity: Vector2::zeros(),
-        last_seen: cycle_start_time,
-    })
-}
-
 fn generate_role(
     own_player_number: PlayerNumber,
     game_controller_state: Option<&FilteredGameControllerState>,
@@ -762,3 +845,134 @@ fn assign_keeper_or_replacement_keeper(
 
     unassigned_robots
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn striker_message(
+        player_number: PlayerNumber,
+        time_to_reach_kick_position: Option<Duration>,
+    ) -> HulkMessage {
+        HulkMessage {
+            player_number,
+            time_to_reach_kick_position,
+            role: Role::Striker,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn faster_robot_wins_striker_conflict() {
+        let message = striker_message(PlayerNumber::Five, Some(Duration::from_secs(3)));
+        assert!(wins_striker_conflict(
+            Some(Duration::from_secs(2)),
+            PlayerNumber::Two,
+            &message
+        ));
+        assert!(!wins_striker_conflict(
+            Some(Duration::from_secs(4)),
+            PlayerNumber::Seven,
+            &message
+        ));
+    }
+
+    #[test]
+    fn equal_times_are_resolved_by_player_number() {
+        let message = striker_message(PlayerNumber::Four, Some(Duration::from_secs(3)));
+        assert!(wins_striker_conflict(
+            Some(Duration::from_secs(3)),
+            PlayerNumber::Five,
+            &message
+        ));
+        assert!(!wins_striker_conflict(
+            Some(Duration::from_secs(3)),
+            PlayerNumber::Three,
+            &message
+        ));
+    }
+
+    #[test]
+    fn teammates_with_a_ball_are_compared_regardless_of_their_role() {
+        let message = HulkMessage {
+            player_number: PlayerNumber::Four,
+            time_to_reach_kick_position: Some(Duration::from_secs(3)),
+            role: Role::Loser,
+            ball_position: Some(spl_network_messages::BallPosition {
+                relative_position: Point2::new(1.0, 0.0),
+                age: Duration::ZERO,
+            }),
+            ..Default::default()
+        };
+        let game_controller_state = FilteredGameControllerState::default();
+        let decide = |time_to_reach_kick_position| {
+            decide_if_claiming_striker_or_other_role(
+                Role::Striker,
+                &message,
+                None,
+                Some(time_to_reach_kick_position),
+                PlayerNumber::Two,
+                Some(&game_controller_state),
+                &[],
+            )
+        };
+
+        let (role, send_spl_striker_message, _) = decide(Duration::from_secs(2));
+        assert_eq!(role, Role::Striker);
+        assert!(send_spl_striker_message);
+
+        let (_, send_spl_striker_message, _) = decide(Duration::from_secs(5));
+        assert!(!send_spl_striker_message);
+    }
+
+    #[test]
+    fn only_striker_conflicts_are_resolved_by_player_number() {
+        let striker = striker_message(PlayerNumber::Four, Some(Duration::from_secs(3)));
+        let loser = HulkMessage {
+            role: Role::Loser,
+            ..striker
+        };
+        let game_controller_state = FilteredGameControllerState::default();
+        let claims_striker = |message| {
+            decide_if_claiming_striker_or_other_role(
+                Role::Striker,
+                message,
+                None,
+                Some(Duration::from_secs(3)),
+                PlayerNumber::Five,
+                Some(&game_controller_state),
+                &[],
+            )
+            .1
+        };
+
+        assert!(claims_striker(&striker));
+        assert!(!claims_striker(&loser));
+    }
+
+    #[test]
+    fn intentions_of_silent_teammates_expire() {
+        let start_time = SystemTime::UNIX_EPOCH;
+        let timeout = Duration::from_secs(3);
+        let mut teammate_intentions = Players::<Option<TeammateIntention>>::default();
+        let mut last_teammate_message_times = Players::<Option<SystemTime>>::default();
+        teammate_intentions[PlayerNumber::Three] = Some(TeammateIntention::default());
+        last_teammate_message_times[PlayerNumber::Three] = Some(start_time);
+
+        remove_outdated_teammate_intentions(
+            &mut teammate_intentions,
+            &last_teammate_message_times,
+            start_time + Duration::from_secs(2),
+            timeout,
+        );
+        assert!(teammate_intentions[PlayerNumber::Three].is_some());
+
+        remove_outdated_teammate_intentions(
+            &mut teammate_intentions,
+            &last_teammate_message_times,
+            start_time + Duration::from_secs(4),
+            timeout,
+        );
+        assert!(teammate_intentions[PlayerNumber::Three].is_none());
+    }
+}
@@ -0,0 +1,282 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{MainOutput, PerceptionInput};
+use nalgebra::{Isometry2, Matrix2, Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::PlayerNumber;
+use types::{
+    ball_event::BallEvent, ball_position::BallPosition, cycle_time::CycleTime,
+    filtered_game_controller_state::FilteredGameControllerState, messages::IncomingMessage,
+    parameters::TeamBallFilterParameters, players::Players, team_ball::TeamBall,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct TeamBallFilter {
+    observations: Players<Option<BallObservation>>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct BallObservation {
+    position: Point2<f32>,
+    /// Only known for the own ball, teammates do not send velocities
+    velocity: Option<Vector2<f32>>,
+    distance_to_observer: f32,
+    last_seen: SystemTime,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    ball_event: Input<Option<BallEvent>, "ball_event?">,
+    ball_position: Input<Option<BallPosition>, "ball_position?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    filtered_game_controller_state:
+        Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+    robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
+    network_message: PerceptionInput<IncomingMessage, "SplNetwork", "message">,
+
+    player_number: Parameter<PlayerNumber, "player_number">,
+    team_ball_filter: Parameter<TeamBallFilterParameters, "team_ball_filter">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub fused_team_ball: MainOutput<Option<TeamBall>>,
+}
+
+impl TeamBallFilter {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            observations: Default::default(),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        for (&receive_time, messages) in context.network_message.persistent.iter() {
+            for message in messages {
+                let IncomingMessage::Spl(message) = message else {
+                    continue;
+                };
+                if message.player_number == *context.player_number {
+                    continue;
+                }
+                // a fallen robot does not know where it is looking at
+                self.observations[message.player_number] = message
+                    .ball_position
+                    .filter(|_| !message.fallen)
+                    .map(|ball_position| BallObservation {
+                        position: message.robot_to_field * ball_position.relative_position,
+                        velocity: None,
+                        distance_to_observer: ball_position.relative_position.coords.norm(),
+                        last_seen: receive_time
+                            .checked_sub(ball_position.age)
+                            .unwrap_or(receive_time),
+                    });
+            }
+        }
+        self.observations[*context.player_number] =
+            match (context.ball_position, context.robot_to_field) {
+                (Some(ball_position), Some(robot_to_field)) => Some(BallObservation {
+                    position: robot_to_field * ball_position.position,
+                    velocity: Some(robot_to_field * ball_position.velocity),
+                    distance_to_observer: ball_position.position.coords.norm(),
+                    last_seen: ball_position.last_seen,
+                }),
+                _ => None,
+            };
+        if let Some(game_controller_state) = context.filtered_game_controller_state {
+            for (player_number, penalty) in game_controller_state.penalties.iter() {
+                if penalty.is_some() {
+                    self.observations[player_number] = None;
+                }
+            }
+        }
+
+        let now = context.cycle_time.start_time;
+        // observations from before the ball left the field or was moved by a referee are stale
+        let stale_before = context.ball_event.map(BallEvent::time);
+        let parameters = context.team_ball_filter;
+        let weighted_observations: Vec<_> = self
+            .observations
+            .iter()
+            .filter_map(|(_player_number, observation)| {
+                let observation = observation.as_ref()?;
+                if stale_before.is_some_and(|stale_before| observation.last_seen < stale_before) {
+                    return None;
+                }
+                let age = now
+                    .duration_since(observation.last_seen)
+                    .unwrap_or_default();
+                if age > parameters.maximum_age {
+                    return None;
+                }
+                let variance = parameters.measurement_noise.powi(2)
+                    + (parameters.distance_noise_factor * observation.distance_to_observer).powi(2)
+                    + parameters.pose_translation_noise.powi(2)
+                    + (parameters.pose_rotation_noise * observation.distance_to_observer).powi(2)
+                    + (parameters.age_noise * age.as_secs_f32()).powi(2);
+                Some(WeightedObservation {
+                    position: observation.position,
+                    velocity: observation.velocity,
+                    covariance: Matrix2::from_diagonal_element(variance),
+                    last_seen: observation.last_seen,
+                })
+            })
+            .collect();
+        let fused_team_ball =
+            fuse_largest_consistent_group(&weighted_observations, parameters.gating_distance);
+
+        Ok(MainOutputs {
+            fused_team_ball: fused_team_ball.into(),
+        })
+    }
+}
+
+struct WeightedObservation {
+    position: Point2<f32>,
+    velocity: Option<Vector2<f32>>,
+    covariance: Matrix2<f32>,
+    last_seen: SystemTime,
+}
+
+/// Fuses the largest group of observations that agree with each other within `gating_distance`
+/// (Mahalanobis), so that a single false positive of one robot does not drag the team ball away
+fn fuse_largest_consistent_group(
+    observations: &[WeightedObservation],
+    gating_distance: f32,
+) -> Option<TeamBall> {
+    let is_consistent = |first: &WeightedObservation, second: &WeightedObservation| {
+        let difference = first.position - second.position;
+        (first.covariance + second.covariance)
+            .try_inverse()
+            .map(|inverse| difference.dot(&(inverse * difference)).sqrt() <= gating_distance)
+            .unwrap_or(false)
+    };
+    let seed = observations.iter().max_by(|first, second| {
+        let number_of_consistent = |seed: &WeightedObservation| {
+            observations
+                .iter()
+                .filter(|observation| is_consistent(seed, observation))
+                .count()
+        };
+        number_of_consistent(first)
+            .cmp(&number_of_consistent(second))
+            .then(
+                second
+                    .covariance
+                    .trace()
+                    .total_cmp(&first.covariance.trace()),
+            )
+    })?;
+    let group: Vec<_> = observations
+        .iter()
+        .filter(|observation| is_consistent(seed, observation))
+        .collect();
+
+    let mut information = Matrix2::zeros();
+    let mut information_vector = Vector2::zeros();
+    for observation in group.iter() {
+        let inverse_covariance = observation.covariance.try_inverse()?;
+        information += inverse_covariance;
+        information_vector += inverse_covariance * observation.position.coords;
+    }
+    let covariance = information.try_inverse()?;
+    let mut velocity_information = Matrix2::zeros();
+    let mut velocity_information_vector = Vector2::zeros();
+    for observation in group.iter() {
+        if let Some(velocity) = observation.velocity {
+            let inverse_covariance = observation.covariance.try_inverse()?;
+            velocity_information += inverse_covariance;
+            velocity_information_vector += inverse_covariance * velocity;
+        }
+    }
+    let velocity = velocity_information
+        .try_inverse()
+        .map_or_else(Vector2::zeros, |velocity_covariance| {
+            velocity_covariance * velocity_information_vector
+        });
+    Some(TeamBall {
+        position: Point2::from(covariance * information_vector),
+        velocity,
+        covariance,
+        last_seen: group
+            .iter()
+            .map(|observation| observation.last_seen)
+            .max()?,
+        number_of_observations: group.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector};
+
+    use super::*;
+
+    fn observation(position: Point2<f32>, variance: f32) -> WeightedObservation {
+        WeightedObservation {
+            position,
+            velocity: None,
+            covariance: Matrix2::from_diagonal_element(variance),
+            last_seen: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn certain_observations_dominate_the_fused_position() {
+        let observations = [
+            observation(point![1.0, 0.0], 0.1),
+            observation(point![1.3, 0.0], 0.2),
+        ];
+
+        let team_ball = fuse_largest_consistent_group(&observations, 3.0).unwrap();
+
+        assert_relative_eq!(team_ball.position, point![1.1, 0.0], epsilon = 0.0001);
+        assert_relative_eq!(
+            team_ball.covariance[(0, 0)],
+            0.1 * 0.2 / 0.3,
+            epsilon = 0.0001
+        );
+        assert_eq!(team_ball.number_of_observations, 2);
+    }
+
+    #[test]
+    fn outlier_is_not_fused() {
+        let observations = [
+            observation(point![1.0, 0.0], 0.1),
+            observation(point![-3.0, 2.0], 0.01),
+            observation(point![1.2, 0.1], 0.1),
+        ];
+
+        let team_ball = fuse_largest_consistent_group(&observations, 3.0).unwrap();
+
+        assert_relative_eq!(team_ball.position, point![1.1, 0.05], epsilon = 0.0001);
+        assert_eq!(team_ball.number_of_observations, 2);
+    }
+
+    #[test]
+    fn velocity_is_only_fused_from_observations_which_know_it() {
+        let observations = [
+            WeightedObservation {
+                velocity: Some(vector![0.5, -0.2]),
+                ..observation(point![1.0, 0.0], 0.1)
+            },
+            observation(point![1.2, 0.1], 0.1),
+        ];
+
+        let team_ball = fuse_largest_consistent_group(&observations, 3.0).unwrap();
+
+        assert_relative_eq!(team_ball.velocity, vector![0.5, -0.2], epsilon = 0.0001);
+    }
+
+    #[test]
+    fn no_observations_result_in_no_team_ball() {
+        assert!(fuse_largest_consistent_group(&[], 3.0).is_none());
+    }
+}
@@ -3,7 +3,6 @@ use std::time::Duration;
 use color_eyre::Result;
 use framework::AdditionalOutput;
 use serde::{Deserialize, Serialize};
-use types::{parameters::BehaviorParameters, planned_path::PathSegment};
 
 #[derive(Deserialize, Serialize)]
 pub struct TimeToReachKickPosition {}
@@ -11,15 +10,13 @@ pub struct TimeToReachKickPosition {}
 use context_attribute::context;
 #[context]
 pub struct CycleContext {
-    dribble_path: Input<Option<Vec<PathSegment>>, "dribble_path?">,
+    dribble_arrival_time: Input<Option<Duration>, "dribble_arrival_time?">,
 
     time_to_reach_kick_position_output:
         AdditionalOutput<Option<Duration>, "time_to_reach_kick_position_output">,
 
     time_to_reach_kick_position: CyclerState<Duration, "time_to_reach_kick_position">,
 
-    configuration: Parameter<BehaviorParameters, "behavior">,
-
     stand_up_back_estimated_remaining_duration:
         Input<Option<Duration>, "stand_up_back_estimated_remaining_duration?">,
     stand_up_front_estimated_remaining_duration:
@@ -38,26 +35,7 @@ impl TimeToReachKickPosition {
     }
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
-        let walk_time = context
-            .dribble_path
-            .as_ref()
-            .map(|path| {
-                path.iter()
-                    .map(|segment: &PathSegment| {
-                        let length = segment.length();
-                        match segment {
-                            PathSegment::LineSegment(_) => {
-                                length / context.configuration.path_planning.line_walking_speed
-                            }
-                            PathSegment::Arc(_, _) => {
-                                length / context.configuration.path_planning.arc_walking_speed
-                            }
-                        }
-                    })
-                    .sum()
-            })
-            .map(Duration::from_secs_f32);
-        let time_to_reach_kick_position = walk_time.map(|walk_time| {
+        let time_to_reach_kick_position = context.dribble_arrival_time.map(|&walk_time| {
             [
                 walk_time,
                 *context
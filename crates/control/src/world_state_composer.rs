@@ -5,6 +5,7 @@ use nalgebra::{Isometry2, Point2};
 use serde::{Deserialize, Serialize};
 use spl_network_messages::PlayerNumber;
 use types::{
+    ball_event::BallEvent,
     fall_state::FallState,
     filtered_game_controller_state::FilteredGameControllerState,
     kick_decision::KickDecision,
@@ -12,6 +13,7 @@ use types::{
     primary_state::PrimaryState,
     roles::Role,
     rule_obstacles::RuleObstacle,
+    team_ball::TeamBall,
     world_state::{BallState, RobotState, WorldState},
 };
 
@@ -24,7 +26,9 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     ball: Input<Option<BallState>, "ball_state?">,
+    ball_event: Input<Option<BallEvent>, "ball_event?">,
     rule_ball: Input<Option<BallState>, "rule_ball_state?">,
+    team_ball: Input<Option<TeamBall>, "fused_team_ball?">,
     filtered_game_controller_state:
         Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
     robot_to_field: Input<Option<Isometry2<f32>>, "robot_to_field?">,
@@ -65,7 +69,9 @@ impl WorldStateComposer {
 
         let world_state = WorldState {
             ball: context.ball.copied(),
+            ball_event: context.ball_event.copied(),
             rule_ball: context.rule_ball.copied(),
+            team_ball: context.team_ball.copied(),
             obstacles: context.obstacles.clone(),
             rule_obstacles: context.rule_obstacles.clone(),
             position_of_interest: *context.position_of_interest,
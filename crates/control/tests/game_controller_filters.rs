@@ -0,0 +1,213 @@
+use std::{
+    collections::BTreeMap,
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, SystemTime},
+};
+
+use control::{
+    game_controller_filter::{self, GameControllerFilter},
+    game_controller_state_filter::{self, GameControllerStateFilter},
+};
+use framework::PerceptionInput;
+use local_game_controller::{Addresses, Command, Game, LocalGameController};
+use nalgebra::Isometry2;
+use spl_network::endpoint::{Endpoint, Ports};
+use spl_network_messages::{GameControllerStateMessage, PlayerNumber, Team};
+use tokio::time::timeout;
+use types::{
+    cycle_time::CycleTime, field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState, filtered_whistle::FilteredWhistle,
+    messages::IncomingMessage, parameters::GameStateFilterParameters,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn free_port() -> u16 {
+    UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+async fn next_state(endpoint: &Endpoint) -> GameControllerStateMessage {
+    loop {
+        match timeout(TIMEOUT, endpoint.read()).await.unwrap().unwrap() {
+            IncomingMessage::GameController(message) => break message,
+            IncomingMessage::Spl(_) => continue,
+        }
+    }
+}
+
+async fn start() -> (Endpoint, LocalGameController) {
+    let ports = Ports {
+        game_controller_state: free_port(),
+        game_controller_return: free_port(),
+        spl: free_port(),
+    };
+    let endpoint = Endpoint::new(ports.clone()).await.unwrap();
+    let game_controller = LocalGameController::start(
+        Game::default(),
+        Addresses {
+            game_controller_state: SocketAddr::from((
+                Ipv4Addr::LOCALHOST,
+                ports.game_controller_state,
+            )),
+            game_controller_return: SocketAddr::from((
+                Ipv4Addr::LOCALHOST,
+                ports.game_controller_return,
+            )),
+            spl: None,
+        },
+    )
+    .await
+    .unwrap();
+    (endpoint, game_controller)
+}
+
+/// Game controller nodes of the control cycler, fed with the messages received by the endpoint
+struct Filters {
+    game_controller_filter: GameControllerFilter,
+    game_controller_state_filter: GameControllerStateFilter,
+    parameters: GameStateFilterParameters,
+    field_dimensions: FieldDimensions,
+    robot_to_field: Isometry2<f32>,
+    now: SystemTime,
+}
+
+impl Filters {
+    fn new() -> Self {
+        Self {
+            game_controller_filter: GameControllerFilter::new(
+                game_controller_filter::CreationContext::new(),
+            )
+            .unwrap(),
+            game_controller_state_filter: GameControllerStateFilter::new(
+                game_controller_state_filter::CreationContext::new(),
+            )
+            .unwrap(),
+            parameters: GameStateFilterParameters {
+                tentative_finish_duration: Duration::from_secs(10),
+                ..Default::default()
+            },
+            field_dimensions: FieldDimensions::default(),
+            robot_to_field: Isometry2::identity(),
+            now: SystemTime::UNIX_EPOCH,
+        }
+    }
+
+    async fn cycle(&mut self, endpoint: &Endpoint) -> FilteredGameControllerState {
+        self.now += Duration::from_millis(12);
+        let message = IncomingMessage::GameController(next_state(endpoint).await);
+        let cycle_time = CycleTime {
+            start_time: self.now,
+            last_cycle_duration: Duration::from_millis(12),
+        };
+        let game_controller_state = self
+            .game_controller_filter
+            .cycle(game_controller_filter::CycleContext::new(
+                &cycle_time,
+                PerceptionInput {
+                    persistent: BTreeMap::from([(self.now, vec![&message])]),
+                    temporary: Default::default(),
+                },
+            ))
+            .unwrap()
+            .game_controller_state
+            .value
+            .unwrap();
+        self.game_controller_state_filter
+            .cycle(game_controller_state_filter::CycleContext::new(
+                None,
+                &cycle_time,
+                &FilteredWhistle::default(),
+                &game_controller_state,
+                &self.parameters,
+                &self.field_dimensions,
+                &mut self.robot_to_field,
+            ))
+            .unwrap()
+            .filtered_game_controller_state
+            .value
+            .unwrap()
+    }
+
+    async fn cycle_until(
+        &mut self,
+        endpoint: &Endpoint,
+        is_expected: impl Fn(FilteredGameState) -> bool,
+    ) -> FilteredGameControllerState {
+        timeout(TIMEOUT, async {
+            loop {
+                let state = self.cycle(endpoint).await;
+                if is_expected(state.game_state) {
+                    break state;
+                }
+            }
+        })
+        .await
+        .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn game_controller_filters_follow_referee_commands() {
+    let (endpoint, game_controller) = start().await;
+    let mut filters = Filters::new();
+
+    let state = filters.cycle(&endpoint).await;
+    assert!(matches!(state.game_state, FilteredGameState::Initial));
+
+    game_controller.apply("ready hulks".parse::<Command>().unwrap());
+    game_controller.apply(
+        "penalize hulks 2 illegal-ball-contact"
+            .parse::<Command>()
+            .unwrap(),
+    );
+    let state = filters
+        .cycle_until(&endpoint, |game_state| {
+            matches!(game_state, FilteredGameState::Ready { .. })
+        })
+        .await;
+    assert!(matches!(
+        state.game_state,
+        FilteredGameState::Ready {
+            kicking_team: Team::Hulks
+        }
+    ));
+    assert!(state.penalties[PlayerNumber::Two].is_some());
+    assert!(state.penalties[PlayerNumber::Three].is_none());
+
+    game_controller.apply("set".parse::<Command>().unwrap());
+    filters
+        .cycle_until(&endpoint, |game_state| {
+            matches!(game_state, FilteredGameState::Set)
+        })
+        .await;
+
+    game_controller.apply("playing".parse::<Command>().unwrap());
+    let state = filters
+        .cycle_until(&endpoint, |game_state| {
+            matches!(game_state, FilteredGameState::Playing { .. })
+        })
+        .await;
+    assert!(matches!(
+        state.game_state,
+        FilteredGameState::Playing {
+            ball_is_free: true,
+            kick_off: false
+        }
+    ));
+
+    // a finished game is only accepted after the tentative finish duration, until then the
+    // robots stand as in set
+    game_controller.apply("finished".parse::<Command>().unwrap());
+    filters
+        .cycle_until(&endpoint, |game_state| {
+            matches!(game_state, FilteredGameState::Set)
+        })
+        .await;
+    let state = filters.cycle(&endpoint).await;
+    assert!(matches!(state.game_state, FilteredGameState::Set));
+}
@@ -3,6 +3,7 @@ pub mod kalman_filter;
 pub mod low_pass_filter;
 pub mod mean_clustering;
 pub mod orientation_filtering;
+pub mod particle_filter;
 pub mod pose_filter;
 pub mod statistics;
 pub mod tap_detector;
@@ -0,0 +1,351 @@
+use nalgebra::{vector, Complex, ComplexField, Isometry2, Matrix3, Vector2, Vector3};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use serde::{Deserialize, Serialize};
+use types::{
+    localization::Particle, multivariate_normal_distribution::MultivariateNormalDistribution,
+};
+
+/// Monte-Carlo pose filter with sensor resetting
+///
+/// The likelihood of the measurements is tracked with a slow and a fast exponential average
+/// (augmented MCL). If the fast average drops below the slow one, the particles no longer explain
+/// what the robot observes (e.g. after it has been kidnapped) and a part of them is replaced by
+/// poses generated from the current observations during resampling.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ParticleFilter {
+    particles: Vec<Particle>,
+    slow_likelihood_average: f32,
+    fast_likelihood_average: f32,
+}
+
+impl ParticleFilter {
+    pub fn from_poses(poses: impl IntoIterator<Item = Isometry2<f32>>) -> Self {
+        let mut particles: Vec<_> = poses
+            .into_iter()
+            .map(|robot_to_field| Particle {
+                robot_to_field,
+                weight: 1.0,
+            })
+            .collect();
+        normalize_weights(&mut particles);
+        Self {
+            particles,
+            slow_likelihood_average: 0.0,
+            fast_likelihood_average: 0.0,
+        }
+    }
+
+    /// Distributes `number_of_particles` evenly among `poses` and scatters them with gaussian noise
+    pub fn from_poses_with_noise(
+        poses: &[Isometry2<f32>],
+        number_of_particles: usize,
+        standard_deviation: Vector3<f32>,
+        random_number_generator: &mut impl Rng,
+    ) -> Self {
+        Self::from_poses(
+            poses
+                .iter()
+                .cycle()
+                .take(number_of_particles)
+                .map(|pose| pose * sample_noise(standard_deviation, random_number_generator)),
+        )
+    }
+
+    pub fn particles(&self) -> &[Particle] {
+        &self.particles
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    pub fn predict(
+        &mut self,
+        current_odometry_to_last_odometry: Isometry2<f32>,
+        odometry_noise: Vector3<f32>,
+        random_number_generator: &mut impl Rng,
+    ) {
+        for particle in self.particles.iter_mut() {
+            particle.robot_to_field = particle.robot_to_field
+                * current_odometry_to_last_odometry
+                * sample_noise(odometry_noise, random_number_generator);
+        }
+    }
+
+    /// Weighs every particle with the likelihood (in `[0, 1]`) of the current measurements at its pose
+    pub fn update(
+        &mut self,
+        likelihood: impl Fn(Isometry2<f32>) -> f32,
+        slow_likelihood_average_factor: f32,
+        fast_likelihood_average_factor: f32,
+    ) {
+        if self.particles.is_empty() {
+            return;
+        }
+        let mut average_likelihood = 0.0;
+        for particle in self.particles.iter_mut() {
+            let particle_likelihood = likelihood(particle.robot_to_field);
+            average_likelihood += particle.weight * particle_likelihood;
+            particle.weight *= particle_likelihood;
+        }
+        if self.slow_likelihood_average <= 0.0 {
+            self.slow_likelihood_average = average_likelihood;
+            self.fast_likelihood_average = average_likelihood;
+        } else {
+            self.slow_likelihood_average += slow_likelihood_average_factor
+                * (average_likelihood - self.slow_likelihood_average);
+            self.fast_likelihood_average += fast_likelihood_average_factor
+                * (average_likelihood - self.fast_likelihood_average);
+        }
+        normalize_weights(&mut self.particles);
+    }
+
+    /// Fraction of particles that should be replaced by poses generated from observations
+    pub fn sensor_resetting_ratio(&self) -> f32 {
+        if self.slow_likelihood_average <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - self.fast_likelihood_average / self.slow_likelihood_average).max(0.0)
+    }
+
+    pub fn effective_number_of_particles(&self) -> f32 {
+        let sum_of_squared_weights: f32 = self
+            .particles
+            .iter()
+            .map(|particle| particle.weight.powi(2))
+            .sum();
+        if sum_of_squared_weights > 0.0 {
+            1.0 / sum_of_squared_weights
+        } else {
+            0.0
+        }
+    }
+
+    /// Draws `number_of_particles` particles with low variance resampling
+    ///
+    /// Up to `maximum_sensor_resetting_ratio` of them are instead generated by `generate_pose`
+    /// according to the current sensor resetting ratio.
+    pub fn resample<RandomNumberGenerator>(
+        &mut self,
+        number_of_particles: usize,
+        maximum_sensor_resetting_ratio: f32,
+        mut generate_pose: impl FnMut(&mut RandomNumberGenerator) -> Option<Isometry2<f32>>,
+        random_number_generator: &mut RandomNumberGenerator,
+    ) where
+        RandomNumberGenerator: Rng,
+    {
+        let sensor_resetting_ratio = self
+            .sensor_resetting_ratio()
+            .min(maximum_sensor_resetting_ratio);
+        let number_of_injected_particles = ((sensor_resetting_ratio * number_of_particles as f32)
+            .round() as usize)
+            .min(number_of_particles);
+        let injected_particles: Vec<_> = (0..number_of_injected_particles)
+            .filter_map(|_| generate_pose(random_number_generator))
+            .map(|robot_to_field| Particle {
+                robot_to_field,
+                weight: 1.0,
+            })
+            .collect();
+
+        let mut particles = low_variance_resample(
+            &self.particles,
+            number_of_particles - injected_particles.len(),
+            random_number_generator,
+        );
+        particles.extend(injected_particles);
+        for particle in particles.iter_mut() {
+            particle.weight = 1.0;
+        }
+        normalize_weights(&mut particles);
+        self.particles = particles;
+    }
+
+    /// Weighted mean and covariance of all particles
+    pub fn estimate(&self) -> Option<MultivariateNormalDistribution<3>> {
+        if self.particles.is_empty() {
+            return None;
+        }
+        let mut mean_translation = Vector2::zeros();
+        let mut mean_angle = Complex::new(0.0, 0.0);
+        for particle in self.particles.iter() {
+            mean_translation += particle.weight * particle.robot_to_field.translation.vector;
+            mean_angle += Complex::new(
+                particle.weight * particle.robot_to_field.rotation.cos_angle(),
+                particle.weight * particle.robot_to_field.rotation.sin_angle(),
+            );
+        }
+        let mean = vector![
+            mean_translation.x,
+            mean_translation.y,
+            mean_angle.argument()
+        ];
+        let covariance = self
+            .particles
+            .iter()
+            .map(|particle| {
+                let difference = vector![
+                    particle.robot_to_field.translation.x - mean.x,
+                    particle.robot_to_field.translation.y - mean.y,
+                    normalized_angle(particle.robot_to_field.rotation.angle() - mean.z)
+                ];
+                particle.weight * difference * difference.transpose()
+            })
+            .sum::<Matrix3<f32>>();
+        Some(MultivariateNormalDistribution { mean, covariance })
+    }
+}
+
+fn sample_noise(
+    standard_deviation: Vector3<f32>,
+    random_number_generator: &mut impl Rng,
+) -> Isometry2<f32> {
+    let noise = standard_deviation.map(|standard_deviation| {
+        standard_deviation * random_number_generator.sample::<f32, _>(StandardNormal)
+    });
+    Isometry2::new(noise.xy(), noise.z)
+}
+
+fn low_variance_resample(
+    particles: &[Particle],
+    number_of_particles: usize,
+    random_number_generator: &mut impl Rng,
+) -> Vec<Particle> {
+    if particles.is_empty() || number_of_particles == 0 {
+        return vec![];
+    }
+    let step = 1.0 / number_of_particles as f32;
+    let offset = random_number_generator.gen_range(0.0..step);
+    let mut index = 0;
+    let mut cumulative_weight = particles[0].weight;
+    (0..number_of_particles)
+        .map(|sample| {
+            let threshold = offset + sample as f32 * step;
+            while threshold > cumulative_weight && index + 1 < particles.len() {
+                index += 1;
+                cumulative_weight += particles[index].weight;
+            }
+            particles[index]
+        })
+        .collect()
+}
+
+fn normalize_weights(particles: &mut [Particle]) {
+    let sum_of_weights: f32 = particles.iter().map(|particle| particle.weight).sum();
+    let uniform_weight = 1.0 / particles.len() as f32;
+    for particle in particles.iter_mut() {
+        particle.weight = if sum_of_weights > 0.0 && sum_of_weights.is_finite() {
+            particle.weight / sum_of_weights
+        } else {
+            uniform_weight
+        };
+    }
+}
+
+fn normalized_angle(angle: f32) -> f32 {
+    angle.sin().atan2(angle.cos())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use approx::assert_relative_eq;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    const SEED: u64 = 42;
+
+    #[test]
+    fn estimate_wraps_angles_around_pi() {
+        let filter = ParticleFilter::from_poses([
+            Isometry2::new(vector![1.0, 2.0], PI - 0.1),
+            Isometry2::new(vector![3.0, 2.0], -PI + 0.1),
+        ]);
+
+        let estimate = filter.estimate().unwrap();
+
+        assert_relative_eq!(estimate.mean.x, 2.0, epsilon = 0.0001);
+        assert_relative_eq!(estimate.mean.y, 2.0, epsilon = 0.0001);
+        assert_relative_eq!(estimate.mean.z.abs(), PI, epsilon = 0.0001);
+        assert_relative_eq!(estimate.covariance[(2, 2)], 0.01, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn resampling_concentrates_on_likely_particles() {
+        let mut random_number_generator = StdRng::seed_from_u64(SEED);
+        let mut filter = ParticleFilter::from_poses([
+            Isometry2::new(vector![0.0, 0.0], 0.0),
+            Isometry2::new(vector![1.0, 0.0], 0.0),
+        ]);
+
+        filter.update(
+            |robot_to_field| {
+                if robot_to_field.translation.x > 0.5 {
+                    0.9
+                } else {
+                    0.0
+                }
+            },
+            0.1,
+            0.5,
+        );
+        filter.resample(10, 0.0, |_| None, &mut random_number_generator);
+
+        assert_eq!(filter.particles().len(), 10);
+        assert!(filter
+            .particles()
+            .iter()
+            .all(|particle| particle.robot_to_field.translation.x > 0.5));
+        assert_relative_eq!(
+            filter.effective_number_of_particles(),
+            10.0,
+            epsilon = 0.001
+        );
+    }
+
+    #[test]
+    fn dropping_likelihood_injects_generated_poses() {
+        let mut random_number_generator = StdRng::seed_from_u64(SEED);
+        let mut filter = ParticleFilter::from_poses(vec![Isometry2::identity(); 10]);
+        filter.update(|_| 0.8, 0.1, 0.5);
+        filter.resample(10, 0.5, |_| None, &mut random_number_generator);
+        assert_eq!(filter.sensor_resetting_ratio(), 0.0);
+
+        filter.update(|_| 0.1, 0.1, 0.5);
+        assert!(filter.sensor_resetting_ratio() > 0.0);
+        let generated_pose = Isometry2::new(vector![4.5, 3.0], 0.0);
+        filter.resample(
+            10,
+            0.5,
+            |_| Some(generated_pose),
+            &mut random_number_generator,
+        );
+
+        let number_of_generated_particles = filter
+            .particles()
+            .iter()
+            .filter(|particle| particle.robot_to_field == generated_pose)
+            .count();
+        assert!(number_of_generated_particles > 0);
+        assert!(number_of_generated_particles <= 5);
+    }
+
+    #[test]
+    fn prediction_applies_odometry_in_robot_frame() {
+        let mut random_number_generator = StdRng::seed_from_u64(SEED);
+        let mut filter = ParticleFilter::from_poses([Isometry2::new(vector![1.0, 0.0], PI / 2.0)]);
+
+        filter.predict(
+            Isometry2::new(vector![1.0, 0.0], 0.0),
+            Vector3::zeros(),
+            &mut random_number_generator,
+        );
+
+        let robot_to_field = filter.particles()[0].robot_to_field;
+        assert_relative_eq!(robot_to_field.translation.x, 1.0, epsilon = 0.0001);
+        assert_relative_eq!(robot_to_field.translation.y, 1.0, epsilon = 0.0001);
+    }
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CycleTimingParameters {
+    /// Maximum duration of the cycle nodes, setup nodes are excluded since they wait for data
+    pub deadline: Duration,
+    pub enable_histograms: bool,
+    pub histogram_bucket_width: Duration,
+    pub number_of_histogram_buckets: usize,
+}
+
+/// Execution durations of the nodes of one cycler instance
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CycleTimings {
+    /// Duration from the end of the setup nodes until all cycle nodes are executed
+    pub cycle_duration: Duration,
+    pub is_deadline_exceeded: bool,
+    pub number_of_cycles: usize,
+    pub number_of_exceeded_deadlines: usize,
+    #[serialize_hierarchy(leaf)]
+    pub nodes: Vec<NodeTiming>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct NodeTiming {
+    pub name: String,
+    /// `None` if the node has not been executed in the last cycle
+    pub duration: Option<Duration>,
+    pub maximum_duration: Duration,
+    pub histogram: Option<Histogram>,
+}
+
+/// Counts of durations in buckets of equal width, the last bucket also counts all longer durations
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Histogram {
+    pub bucket_width: Duration,
+    pub counts: Vec<usize>,
+}
+
+impl CycleTimings {
+    pub fn new(node_names: &[&str]) -> Self {
+        Self {
+            nodes: node_names
+                .iter()
+                .map(|name| NodeTiming {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn begin_cycle(&mut self) {
+        for node in self.nodes.iter_mut() {
+            node.duration = None;
+        }
+    }
+
+    pub fn record_node(
+        &mut self,
+        node_index: usize,
+        duration: Duration,
+        parameters: &CycleTimingParameters,
+    ) {
+        let node = &mut self.nodes[node_index];
+        node.duration = Some(duration);
+        node.maximum_duration = node.maximum_duration.max(duration);
+        if !parameters.enable_histograms || parameters.number_of_histogram_buckets == 0 {
+            node.histogram = None;
+            return;
+        }
+        let histogram = node.histogram.get_or_insert_with(|| {
+            Histogram::new(
+                parameters.histogram_bucket_width,
+                parameters.number_of_histogram_buckets,
+            )
+        });
+        if histogram.bucket_width != parameters.histogram_bucket_width
+            || histogram.counts.len() != parameters.number_of_histogram_buckets
+        {
+            *histogram = Histogram::new(
+                parameters.histogram_bucket_width,
+                parameters.number_of_histogram_buckets,
+            );
+        }
+        histogram.add(duration);
+    }
+
+    pub fn finish_cycle(&mut self, cycle_duration: Duration, parameters: &CycleTimingParameters) {
+        self.cycle_duration = cycle_duration;
+        self.is_deadline_exceeded = cycle_duration > parameters.deadline;
+        self.number_of_cycles += 1;
+        if self.is_deadline_exceeded {
+            self.number_of_exceeded_deadlines += 1;
+        }
+    }
+}
+
+impl Histogram {
+    pub fn new(bucket_width: Duration, number_of_buckets: usize) -> Self {
+        Self {
+            bucket_width,
+            counts: vec![0; number_of_buckets],
+        }
+    }
+
+    pub fn add(&mut self, duration: Duration) {
+        let Some(last_index) = self.counts.len().checked_sub(1) else {
+            return;
+        };
+        let index = if self.bucket_width.is_zero() {
+            last_index
+        } else {
+            ((duration.as_nanos() / self.bucket_width.as_nanos()) as usize).min(last_index)
+        };
+        self.counts[index] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters(enable_histograms: bool) -> CycleTimingParameters {
+        CycleTimingParameters {
+            deadline: Duration::from_millis(12),
+            enable_histograms,
+            histogram_bucket_width: Duration::from_millis(1),
+            number_of_histogram_buckets: 4,
+        }
+    }
+
+    #[test]
+    fn durations_are_collected_into_histogram_buckets() {
+        let mut histogram = Histogram::new(Duration::from_millis(1), 4);
+
+        for milliseconds in [0, 1, 1, 3, 10] {
+            histogram.add(Duration::from_millis(milliseconds));
+        }
+
+        assert_eq!(histogram.counts, vec![1, 2, 0, 2]);
+    }
+
+    #[test]
+    fn exceeded_deadlines_are_flagged_and_counted() {
+        let parameters = parameters(false);
+        let mut timings = CycleTimings::new(&["a", "b"]);
+
+        timings.begin_cycle();
+        timings.record_node(0, Duration::from_millis(3), &parameters);
+        timings.finish_cycle(Duration::from_millis(3), &parameters);
+        assert!(!timings.is_deadline_exceeded);
+
+        timings.begin_cycle();
+        timings.record_node(1, Duration::from_millis(13), &parameters);
+        timings.finish_cycle(Duration::from_millis(13), &parameters);
+
+        assert!(timings.is_deadline_exceeded);
+        assert_eq!(timings.number_of_cycles, 2);
+        assert_eq!(timings.number_of_exceeded_deadlines, 1);
+        assert_eq!(timings.nodes[0].duration, None);
+        assert_eq!(timings.nodes[0].maximum_duration, Duration::from_millis(3));
+        assert_eq!(timings.nodes[1].duration, Some(Duration::from_millis(13)));
+        assert!(timings.nodes[1].histogram.is_none());
+    }
+
+    #[test]
+    fn histograms_are_only_aggregated_if_enabled() {
+        let mut timings = CycleTimings::new(&["a"]);
+
+        timings.record_node(0, Duration::from_millis(2), &parameters(true));
+        timings.record_node(0, Duration::from_millis(2), &parameters(true));
+
+        assert_eq!(
+            timings.nodes[0].histogram.as_ref().unwrap().counts,
+            vec![0, 0, 2, 0]
+        );
+
+        timings.record_node(0, Duration::from_millis(2), &parameters(false));
+
+        assert!(timings.nodes[0].histogram.is_none());
+    }
+}
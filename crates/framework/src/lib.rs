@@ -1,4 +1,5 @@
 mod additional_output;
+mod cycle_timings;
 mod future_queue;
 mod historic_databases;
 mod historic_input;
@@ -8,8 +9,10 @@ mod panic;
 mod parameters;
 mod perception_databases;
 mod perception_input;
+mod recording_index;
 
 pub use additional_output::{should_be_filled, AdditionalOutput};
+pub use cycle_timings::{CycleTimingParameters, CycleTimings, Histogram, NodeTiming};
 pub use future_queue::{future_queue, Consumer, Item, Producer, Update, Updates};
 pub use historic_databases::HistoricDatabases;
 pub use historic_input::HistoricInput;
@@ -19,3 +22,4 @@ pub use panic::deserialize_not_implemented;
 pub use parameters::Parameters;
 pub use perception_databases::PerceptionDatabases;
 pub use perception_input::PerceptionInput;
+pub use recording_index::{FrameIndex, RecordedFrame, RecordingFrameMetadata, RecordingIndex};
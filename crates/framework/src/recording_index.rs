@@ -0,0 +1,193 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+    time::SystemTime,
+};
+
+use bincode::{deserialize_from, serialized_size, ErrorKind};
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+
+/// Precedes every recorded frame in a recording file, the frame data follows directly
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct RecordingFrameMetadata {
+    pub timestamp: SystemTime,
+    pub length: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FrameIndex {
+    pub timestamp: SystemTime,
+    offset: u64,
+    length: usize,
+}
+
+pub struct RecordedFrame {
+    pub timestamp: SystemTime,
+    pub data: Vec<u8>,
+}
+
+pub struct RecordingIndex<Reader = BufReader<File>> {
+    reader: Reader,
+    frames: Vec<FrameIndex>,
+}
+
+impl RecordingIndex {
+    pub fn read_from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let file = File::open(path).wrap_err("failed to open recording file")?;
+        Self::read_from(BufReader::new(file))
+    }
+}
+
+impl<Reader> RecordingIndex<Reader>
+where
+    Reader: Read + Seek,
+{
+    pub fn read_from(mut reader: Reader) -> Result<Self> {
+        let end = reader
+            .seek(SeekFrom::End(0))
+            .wrap_err("failed to determine recording length")?;
+        reader.rewind().wrap_err("failed to rewind recording")?;
+        let mut frames = Vec::new();
+        let mut offset = 0;
+        loop {
+            let metadata: RecordingFrameMetadata = match deserialize_from(&mut reader) {
+                Ok(metadata) => metadata,
+                Err(error) => match *error {
+                    ErrorKind::Io(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => {
+                        break;
+                    }
+                    error => return Err(error).wrap_err("failed to read frame metadata"),
+                },
+            };
+            offset += serialized_size(&metadata).wrap_err("failed to compute metadata size")?;
+            let is_truncated = offset + metadata.length as u64 > end;
+            if is_truncated {
+                break;
+            }
+            frames.push(FrameIndex {
+                timestamp: metadata.timestamp,
+                offset,
+                length: metadata.length,
+            });
+            offset += metadata.length as u64;
+            reader
+                .seek(SeekFrom::Start(offset))
+                .wrap_err("failed to skip frame data")?;
+        }
+        Ok(Self { reader, frames })
+    }
+
+    pub fn frames(&self) -> &[FrameIndex] {
+        &self.frames
+    }
+
+    pub fn find_latest_frame_up_to(&self, timestamp: SystemTime) -> Option<usize> {
+        self.frames
+            .partition_point(|frame| frame.timestamp <= timestamp)
+            .checked_sub(1)
+    }
+
+    pub fn read_frame(&mut self, index: usize) -> Result<RecordedFrame> {
+        let Some(frame) = self.frames.get(index) else {
+            bail!(
+                "frame {index} out of range, recording contains {} frames",
+                self.frames.len()
+            );
+        };
+        self.reader
+            .seek(SeekFrom::Start(frame.offset))
+            .wrap_err("failed to seek to frame")?;
+        let mut data = vec![0; frame.length];
+        self.reader
+            .read_exact(&mut data)
+            .wrap_err("failed to read frame data")?;
+        Ok(RecordedFrame {
+            timestamp: frame.timestamp,
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, time::Duration};
+
+    use bincode::serialize_into;
+
+    use super::*;
+
+    fn write_frame(recording: &mut Vec<u8>, timestamp: SystemTime, data: &[u8]) {
+        serialize_into(
+            &mut *recording,
+            &RecordingFrameMetadata {
+                timestamp,
+                length: data.len(),
+            },
+        )
+        .unwrap();
+        recording.extend_from_slice(data);
+    }
+
+    #[test]
+    fn frames_are_indexed_and_read_back() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(42);
+        let mut recording = Vec::new();
+        write_frame(&mut recording, start, &[1, 2, 3]);
+        write_frame(&mut recording, start + Duration::from_millis(12), &[]);
+        write_frame(&mut recording, start + Duration::from_millis(24), &[4, 5]);
+
+        let mut index = RecordingIndex::read_from(Cursor::new(recording)).unwrap();
+
+        assert_eq!(index.frames().len(), 3);
+        assert_eq!(index.read_frame(2).unwrap().data, vec![4, 5]);
+        assert_eq!(index.read_frame(1).unwrap().data, Vec::<u8>::new());
+        assert_eq!(index.read_frame(0).unwrap().data, vec![1, 2, 3]);
+        assert!(index.read_frame(3).is_err());
+    }
+
+    #[test]
+    fn truncated_frame_is_dropped() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(42);
+        let mut recording = Vec::new();
+        write_frame(&mut recording, start, &[1, 2, 3]);
+        write_frame(
+            &mut recording,
+            start + Duration::from_millis(12),
+            &[4, 5, 6],
+        );
+        recording.pop();
+
+        let index = RecordingIndex::read_from(Cursor::new(recording)).unwrap();
+
+        assert_eq!(index.frames().len(), 1);
+    }
+
+    #[test]
+    fn latest_frame_up_to_timestamp_is_found() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(42);
+        let mut recording = Vec::new();
+        write_frame(&mut recording, start, &[1]);
+        write_frame(&mut recording, start + Duration::from_millis(12), &[2]);
+
+        let index = RecordingIndex::read_from(Cursor::new(recording)).unwrap();
+
+        assert_eq!(
+            index.find_latest_frame_up_to(start - Duration::from_millis(1)),
+            None
+        );
+        assert_eq!(index.find_latest_frame_up_to(start), Some(0));
+        assert_eq!(
+            index.find_latest_frame_up_to(start + Duration::from_millis(11)),
+            Some(0)
+        );
+        assert_eq!(
+            index.find_latest_frame_up_to(start + Duration::from_secs(1)),
+            Some(1)
+        );
+    }
+}
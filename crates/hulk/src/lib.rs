@@ -20,3 +20,191 @@ pub trait HardwareInterface:
 }
 
 include!(concat!(env!("OUT_DIR"), "/generated_code.rs"));
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        f32::consts::TAU,
+        sync::{
+            mpsc::{sync_channel, SyncSender},
+            Arc,
+        },
+        time::SystemTime,
+    };
+
+    use color_eyre::Result;
+    use framework::{future_queue, multiple_buffer_with_slots, Reader};
+    use tokio::sync::Notify;
+    use types::{
+        audio::SpeakerRequest,
+        camera_position::CameraPosition,
+        hardware::{Ids, Paths},
+        joints::Joints,
+        led::Leds,
+        messages::{IncomingMessage, OutgoingMessage},
+        parameters::WhistleDetectionParameters,
+        samples::Samples,
+        sensor_data::SensorData,
+        ycbcr422_image::YCbCr422Image,
+    };
+
+    use super::*;
+    use crate::{
+        cyclers::{
+            audio::{Cycler, CyclerInstance, Database},
+            RecordingFrame,
+        },
+        structs::Parameters,
+    };
+
+    struct Microphones;
+
+    impl ActuatorInterface for Microphones {
+        fn write_to_actuators(
+            &self,
+            _positions: Joints<f32>,
+            _stiffnesses: Joints<f32>,
+            _leds: Leds,
+        ) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    impl CameraInterface for Microphones {
+        fn read_from_camera(&self, _camera_position: CameraPosition) -> Result<YCbCr422Image> {
+            unimplemented!()
+        }
+    }
+
+    impl IdInterface for Microphones {
+        fn get_ids(&self) -> Ids {
+            unimplemented!()
+        }
+    }
+
+    impl MicrophoneInterface for Microphones {
+        fn read_from_microphones(&self) -> Result<Samples> {
+            let whistle = (0..2048)
+                .map(|index| (TAU * 3000.0 * index as f32 / 44100.0).sin())
+                .collect();
+            Ok(Samples {
+                rate: 44100,
+                channels_of_samples: Arc::new(vec![whistle; 4]),
+            })
+        }
+    }
+
+    impl NetworkInterface for Microphones {
+        fn read_from_network(&self) -> Result<IncomingMessage> {
+            unimplemented!()
+        }
+
+        fn write_to_network(&self, _message: OutgoingMessage) -> Result<()> {
+            unimplemented!()
+        }
+    }
+
+    impl PathsInterface for Microphones {
+        fn get_paths(&self) -> Paths {
+            unimplemented!()
+        }
+    }
+
+    impl RecordingInterface for Microphones {
+        fn should_record(&self) -> bool {
+            true
+        }
+
+        fn set_whether_to_record(&self, _enable: bool) {}
+    }
+
+    impl SensorInterface for Microphones {
+        fn read_from_sensors(&self) -> Result<SensorData> {
+            unimplemented!()
+        }
+    }
+
+    impl SpeakerInterface for Microphones {
+        fn write_to_speakers(&self, _request: SpeakerRequest) {}
+    }
+
+    impl TimeInterface for Microphones {
+        fn get_now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH
+        }
+    }
+
+    impl HardwareInterface for Microphones {}
+
+    fn audio_cycler(
+        recording_sender: SyncSender<RecordingFrame>,
+        enable_recording: bool,
+    ) -> (Cycler<Microphones>, Reader<Database>) {
+        let (own_writer, own_reader) = multiple_buffer_with_slots([
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        ]);
+        let (_subscribed_outputs_writer, subscribed_outputs_reader) =
+            multiple_buffer_with_slots([Default::default(), Default::default()]);
+        let parameters = Parameters {
+            whistle_detection: WhistleDetectionParameters {
+                detection_band: 2000.0..4000.0,
+                background_noise_scaling: 1.6,
+                whistle_scaling: 3.8,
+                number_of_chunks: 16,
+            },
+            ..Default::default()
+        };
+        let (_parameters_writer, parameters_reader) =
+            multiple_buffer_with_slots([parameters.clone(), parameters]);
+        let (own_producer, _own_consumer) = future_queue();
+        let (_control_writer, control_reader) =
+            multiple_buffer_with_slots([Default::default(), Default::default()]);
+        let cycler = Cycler::new(
+            CyclerInstance::Audio,
+            Arc::new(Microphones),
+            own_writer,
+            Arc::new(Notify::new()),
+            subscribed_outputs_reader,
+            parameters_reader,
+            own_producer,
+            control_reader,
+            recording_sender,
+            enable_recording,
+        )
+        .expect("failed to create audio cycler");
+        (cycler, own_reader)
+    }
+
+    #[test]
+    fn recorded_frame_replays_node_without_replayable_state() {
+        let (recording_sender, recording_receiver) = sync_channel(1);
+        let (mut recording_cycler, recorded_database) = audio_cycler(recording_sender, true);
+        recording_cycler.cycle().expect("failed to record cycle");
+        let RecordingFrame::Audio { data, .. } = recording_receiver
+            .try_recv()
+            .expect("recording frame is missing")
+        else {
+            panic!("recording frame does not belong to the audio cycler");
+        };
+
+        let (replay_sender, _replay_receiver) = sync_channel(0);
+        let (mut replaying_cycler, replayed_database) = audio_cycler(replay_sender, false);
+        replaying_cycler
+            .replay(&data)
+            .expect("failed to replay recorded frame");
+
+        let recorded = &recorded_database.next().main_outputs;
+        let replayed = &replayed_database.next().main_outputs;
+        assert_eq!(
+            replayed.samples.channels_of_samples,
+            recorded.samples.channels_of_samples
+        );
+        assert_eq!(
+            replayed.detected_whistle.is_detected,
+            recorded.detected_whistle.is_detected
+        );
+        assert_eq!(replayed.detected_whistle.is_detected, vec![true; 4]);
+    }
+}
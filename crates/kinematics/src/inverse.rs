@@ -1,11 +1,34 @@
 use std::f32::consts::PI;
 
-use nalgebra::{geometry::Isometry3, Rotation3, Translation3, Vector3};
+use nalgebra::{distance, geometry::Isometry3, Point3, Rotation3, Translation3, Vector3};
 use types::{
-    joints::{body::LowerBodyJoints, leg::LegJoints},
+    joints::{arm::ArmJoints, body::LowerBodyJoints, leg::LegJoints, mirror::Mirror},
     robot_dimensions::RobotDimensions,
 };
 
+use crate::forward::{
+    left_elbow_to_left_upper_arm, left_forearm_to_left_elbow, left_shoulder_to_robot,
+    left_upper_arm_to_left_shoulder, left_wrist_to_left_forearm,
+};
+
+const LEFT_ARM_MINIMUM_ANGLES: ArmJoints<f32> = ArmJoints {
+    shoulder_pitch: -2.0857,
+    shoulder_roll: -0.3142,
+    elbow_yaw: -2.0857,
+    elbow_roll: -1.5446,
+    wrist_yaw: -1.8238,
+    hand: 0.0,
+};
+const LEFT_ARM_MAXIMUM_ANGLES: ArmJoints<f32> = ArmJoints {
+    shoulder_pitch: 2.0857,
+    shoulder_roll: 1.3265,
+    elbow_yaw: 2.0857,
+    elbow_roll: -0.0349,
+    wrist_yaw: 1.8238,
+    hand: 1.0,
+};
+const MAXIMUM_HAND_POSITION_ERROR: f32 = 0.001;
+
 pub fn leg_angles(
     left_foot_to_robot: Isometry3<f32>,
     right_foot_to_robot: Isometry3<f32>,
@@ -132,3 +155,179 @@ pub fn leg_angles(
         },
     )
 }
+
+/// Calculates the left arm joint angles placing the hand (the wrist joint) at the given position
+///
+/// The arm has one redundant degree of freedom for positioning the hand, which is resolved by the
+/// given elbow yaw. All angles are clamped to the joint limits. The returned flag tells whether
+/// the clamped angles still reach the requested position.
+pub fn left_arm_angles(hand_in_robot: Point3<f32>, elbow_yaw: f32) -> (bool, ArmJoints<f32>) {
+    let upper_arm = RobotDimensions::LEFT_SHOULDER_TO_LEFT_ELBOW;
+    let forearm_length = RobotDimensions::ELBOW_TO_WRIST.x;
+    let hand_in_shoulder = hand_in_robot - Point3::from(RobotDimensions::ROBOT_TO_LEFT_SHOULDER);
+    let elbow_yaw = elbow_yaw.clamp(
+        LEFT_ARM_MINIMUM_ANGLES.elbow_yaw,
+        LEFT_ARM_MAXIMUM_ANGLES.elbow_yaw,
+    );
+
+    // the distance between shoulder and hand only depends on elbow yaw and roll
+    let cosine_factor = 2.0 * upper_arm.x * forearm_length;
+    let sine_factor = 2.0 * upper_arm.y * forearm_length * elbow_yaw.cos();
+    let squared_distance_offset =
+        hand_in_shoulder.norm_squared() - upper_arm.norm_squared() - forearm_length.powi(2);
+    let elbow_roll = sine_factor.atan2(cosine_factor)
+        - (squared_distance_offset / cosine_factor.hypot(sine_factor))
+            .clamp(-1.0, 1.0)
+            .acos();
+    let elbow_roll = elbow_roll.clamp(
+        LEFT_ARM_MINIMUM_ANGLES.elbow_roll,
+        LEFT_ARM_MAXIMUM_ANGLES.elbow_roll,
+    );
+
+    let hand_in_upper_arm = upper_arm
+        + Rotation3::new(Vector3::x() * elbow_yaw)
+            * Rotation3::new(Vector3::z() * elbow_roll)
+            * Vector3::x()
+            * forearm_length;
+
+    // shoulder pitch rotates around the y axis, so shoulder roll alone has to match the y coordinate
+    let hand_distance_in_xy = hand_in_upper_arm.xy().norm();
+    let shoulder_roll = (hand_in_shoulder.y / hand_distance_in_xy)
+        .clamp(-1.0, 1.0)
+        .asin()
+        - hand_in_upper_arm.y.atan2(hand_in_upper_arm.x);
+    let shoulder_roll = shoulder_roll.clamp(
+        LEFT_ARM_MINIMUM_ANGLES.shoulder_roll,
+        LEFT_ARM_MAXIMUM_ANGLES.shoulder_roll,
+    );
+
+    let hand_in_shoulder_without_pitch =
+        Rotation3::new(Vector3::z() * shoulder_roll) * hand_in_upper_arm;
+    let shoulder_pitch = normalize_angle(
+        hand_in_shoulder.x.atan2(hand_in_shoulder.z)
+            - hand_in_shoulder_without_pitch
+                .x
+                .atan2(hand_in_shoulder_without_pitch.z),
+    )
+    .clamp(
+        LEFT_ARM_MINIMUM_ANGLES.shoulder_pitch,
+        LEFT_ARM_MAXIMUM_ANGLES.shoulder_pitch,
+    );
+
+    let left_arm = ArmJoints {
+        shoulder_pitch,
+        shoulder_roll,
+        elbow_yaw,
+        elbow_roll,
+        wrist_yaw: 0.0,
+        hand: 0.0,
+    };
+    let reached_hand_in_robot = left_shoulder_to_robot(&left_arm)
+        * left_upper_arm_to_left_shoulder(&left_arm)
+        * left_elbow_to_left_upper_arm(&left_arm)
+        * left_forearm_to_left_elbow(&left_arm)
+        * left_wrist_to_left_forearm(&left_arm)
+        * Point3::origin();
+    let is_reachable =
+        distance(&reached_hand_in_robot, &hand_in_robot) <= MAXIMUM_HAND_POSITION_ERROR;
+
+    (is_reachable, left_arm)
+}
+
+/// Calculates the right arm joint angles by mirroring the solution of [`left_arm_angles`]
+pub fn right_arm_angles(hand_in_robot: Point3<f32>, elbow_yaw: f32) -> (bool, ArmJoints<f32>) {
+    let mirrored_hand_in_robot = Point3::new(hand_in_robot.x, -hand_in_robot.y, hand_in_robot.z);
+    let (is_reachable, left_arm) = left_arm_angles(mirrored_hand_in_robot, -elbow_yaw);
+    (is_reachable, left_arm.mirrored())
+}
+
+fn normalize_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use approx::assert_relative_eq;
+
+    use crate::forward::{
+        right_elbow_to_right_upper_arm, right_forearm_to_right_elbow, right_shoulder_to_robot,
+        right_upper_arm_to_right_shoulder, right_wrist_to_right_forearm,
+    };
+
+    use super::*;
+
+    fn left_hand_in_robot(left_arm: &ArmJoints<f32>) -> Point3<f32> {
+        left_shoulder_to_robot(left_arm)
+            * left_upper_arm_to_left_shoulder(left_arm)
+            * left_elbow_to_left_upper_arm(left_arm)
+            * left_forearm_to_left_elbow(left_arm)
+            * left_wrist_to_left_forearm(left_arm)
+            * Point3::origin()
+    }
+
+    fn right_hand_in_robot(right_arm: &ArmJoints<f32>) -> Point3<f32> {
+        right_shoulder_to_robot(right_arm)
+            * right_upper_arm_to_right_shoulder(right_arm)
+            * right_elbow_to_right_upper_arm(right_arm)
+            * right_forearm_to_right_elbow(right_arm)
+            * right_wrist_to_right_forearm(right_arm)
+            * Point3::origin()
+    }
+
+    #[test]
+    fn reachable_hand_positions_are_reached() {
+        let left_arm = ArmJoints {
+            shoulder_pitch: 1.2,
+            shoulder_roll: 0.4,
+            elbow_yaw: -FRAC_PI_2,
+            elbow_roll: -0.8,
+            wrist_yaw: 0.0,
+            hand: 0.0,
+        };
+        let hand_in_robot = left_hand_in_robot(&left_arm);
+
+        let (is_reachable, solution) = left_arm_angles(hand_in_robot, -FRAC_PI_2);
+
+        assert!(is_reachable);
+        assert_relative_eq!(solution.shoulder_pitch, 1.2, epsilon = 0.001);
+        assert_relative_eq!(solution.shoulder_roll, 0.4, epsilon = 0.001);
+        assert_relative_eq!(solution.elbow_roll, -0.8, epsilon = 0.001);
+        assert_relative_eq!(
+            left_hand_in_robot(&solution),
+            hand_in_robot,
+            epsilon = 0.001
+        );
+    }
+
+    #[test]
+    fn right_arm_solution_is_mirrored() {
+        let hand_in_robot = Point3::new(0.05, -0.15, 0.05);
+
+        let (is_reachable, right_arm) = right_arm_angles(hand_in_robot, FRAC_PI_2);
+
+        assert!(is_reachable);
+        assert_relative_eq!(
+            right_hand_in_robot(&right_arm),
+            hand_in_robot,
+            epsilon = 0.001
+        );
+        assert!(right_arm.shoulder_roll <= 0.3142);
+        assert!(right_arm.elbow_roll >= 0.0349);
+    }
+
+    #[test]
+    fn unreachable_hand_positions_respect_joint_limits() {
+        let (is_reachable, left_arm) = left_arm_angles(Point3::new(0.0, 0.5, 0.0), -FRAC_PI_2);
+
+        assert!(!is_reachable);
+        for ((angle, minimum), maximum) in left_arm
+            .into_iter()
+            .zip(LEFT_ARM_MINIMUM_ANGLES)
+            .zip(LEFT_ARM_MAXIMUM_ANGLES)
+        {
+            assert!((minimum..=maximum).contains(&angle));
+        }
+    }
+}
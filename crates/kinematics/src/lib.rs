@@ -11,4 +11,4 @@ pub use forward::{
     right_shoulder_to_robot, right_thigh_to_right_hip, right_tibia_to_right_thigh,
     right_upper_arm_to_right_shoulder, right_wrist_to_right_forearm,
 };
-pub use inverse::leg_angles;
+pub use inverse::{left_arm_angles, leg_angles, right_arm_angles};
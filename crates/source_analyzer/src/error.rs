@@ -5,6 +5,8 @@ use quote::ToTokens;
 use thiserror::Error;
 use threadbound::ThreadBound;
 
+use crate::manifest::ManifestError;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("failed to perform IO on `{path}`")]
@@ -17,6 +19,11 @@ pub enum Error {
         node: String,
         path: PathBuf,
     },
+    #[error("invalid framework manifest {path}")]
+    Manifest {
+        source: ManifestError,
+        path: PathBuf,
+    },
     #[error("invalid module path")]
     InvalidModulePath,
     #[error("`{node}` requires output `{output}`, but it is never produced")]
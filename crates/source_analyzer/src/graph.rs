@@ -0,0 +1,301 @@
+use std::fmt::{self, Write};
+
+use quote::ToTokens;
+use serde::Serialize;
+
+use crate::{
+    contexts::Field,
+    cyclers::{Cycler, CyclerName, Cyclers},
+    node::{Node, NodeName},
+    path::Path,
+};
+
+/// Nodes of all cyclers connected by the main outputs they exchange
+#[derive(Debug, Serialize)]
+pub struct DependencyGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GraphNode {
+    pub cycler: CyclerName,
+    pub name: NodeName,
+    pub module: String,
+    pub is_setup_node: bool,
+    pub outputs: Vec<String>,
+}
+
+/// Connects the producer of a main output with one of its consumers, both index into `nodes`
+#[derive(Debug, Serialize)]
+pub struct Edge {
+    pub producer: usize,
+    pub consumer: usize,
+    pub output: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub enum EdgeKind {
+    Input,
+    RequiredInput,
+    HistoricInput,
+    PerceptionInput,
+}
+
+/// Input of a node referring to a main output of a (possibly different) cycler
+pub struct Consumption<'cyclers> {
+    pub producing_cycler: &'cyclers Cycler,
+    pub path: &'cyclers Path,
+    pub data_type: &'cyclers syn::Type,
+    pub kind: EdgeKind,
+}
+
+impl<'cyclers> Consumption<'cyclers> {
+    pub fn try_from_field(
+        field: &'cyclers Field,
+        cycler: &'cyclers Cycler,
+        cyclers: &'cyclers Cyclers,
+    ) -> Option<Self> {
+        let (cycler_instance, data_type, path, kind) = match field {
+            Field::HistoricInput {
+                data_type, path, ..
+            } => (None, data_type, path, EdgeKind::HistoricInput),
+            Field::Input {
+                cycler_instance,
+                data_type,
+                path,
+                ..
+            } => (cycler_instance.as_ref(), data_type, path, EdgeKind::Input),
+            Field::PerceptionInput {
+                cycler_instance,
+                data_type,
+                path,
+                ..
+            } => (
+                Some(cycler_instance),
+                data_type,
+                path,
+                EdgeKind::PerceptionInput,
+            ),
+            Field::RequiredInput {
+                cycler_instance,
+                data_type,
+                path,
+                ..
+            } => (
+                cycler_instance.as_ref(),
+                data_type,
+                path,
+                EdgeKind::RequiredInput,
+            ),
+            _ => return None,
+        };
+        let producing_cycler = match cycler_instance {
+            Some(cycler_instance) => {
+                cyclers
+                    .instances()
+                    .find(|(_cycler, instance)| *instance == cycler_instance)?
+                    .0
+            }
+            None => cycler,
+        };
+        Some(Self {
+            producing_cycler,
+            path,
+            data_type,
+            kind,
+        })
+    }
+
+    pub fn output_name(&self) -> &str {
+        &self.path.segments[0].name
+    }
+}
+
+impl DependencyGraph {
+    pub fn from_cyclers(cyclers: &Cyclers) -> Self {
+        let nodes: Vec<_> = cyclers
+            .cyclers
+            .iter()
+            .flat_map(|cycler| {
+                cycler
+                    .setup_nodes
+                    .iter()
+                    .map(move |node| graph_node(cycler, node, true))
+                    .chain(
+                        cycler
+                            .cycle_nodes
+                            .iter()
+                            .map(move |node| graph_node(cycler, node, false)),
+                    )
+            })
+            .collect();
+        let index_of = |cycler: &Cycler, node: &Node| {
+            nodes.iter().position(|graph_node| {
+                graph_node.cycler == cycler.name && graph_node.name == node.name
+            })
+        };
+        let producer_of = |cycler: &Cycler, output: &str| {
+            nodes.iter().position(|graph_node| {
+                graph_node.cycler == cycler.name
+                    && graph_node.outputs.iter().any(|name| name == output)
+            })
+        };
+
+        let mut edges = Vec::new();
+        for cycler in &cyclers.cyclers {
+            for node in cycler.iter_nodes() {
+                let Some(consumer) = index_of(cycler, node) else {
+                    continue;
+                };
+                for field in &node.contexts.cycle_context {
+                    let Some(consumption) = Consumption::try_from_field(field, cycler, cyclers)
+                    else {
+                        continue;
+                    };
+                    let output = consumption.output_name();
+                    let Some(producer) = producer_of(consumption.producing_cycler, output) else {
+                        continue;
+                    };
+                    edges.push(Edge {
+                        producer,
+                        consumer,
+                        output: output.to_string(),
+                        kind: consumption.kind,
+                    });
+                }
+            }
+        }
+
+        Self { nodes, edges }
+    }
+
+    pub fn to_dot(&self) -> Result<String, fmt::Error> {
+        let mut dot = String::new();
+        writeln!(dot, "digraph dependencies {{")?;
+        writeln!(dot, "  rankdir=LR;")?;
+        let mut cyclers: Vec<_> = self.nodes.iter().map(|node| &node.cycler).collect();
+        cyclers.dedup();
+        for cycler in cyclers {
+            writeln!(dot, "  subgraph \"cluster_{cycler}\" {{")?;
+            writeln!(dot, "    label=\"{cycler}\";")?;
+            for node in self.nodes.iter().filter(|node| &node.cycler == cycler) {
+                let shape = if node.is_setup_node { "box" } else { "ellipse" };
+                writeln!(
+                    dot,
+                    "    \"{}\" [label=\"{}\", shape={shape}];",
+                    node.identifier(),
+                    node.name
+                )?;
+            }
+            writeln!(dot, "  }}")?;
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                EdgeKind::Input | EdgeKind::RequiredInput => "solid",
+                EdgeKind::HistoricInput => "dotted",
+                EdgeKind::PerceptionInput => "dashed",
+            };
+            writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{}\", style={style}];",
+                self.nodes[edge.producer].identifier(),
+                self.nodes[edge.consumer].identifier(),
+                edge.output
+            )?;
+        }
+        writeln!(dot, "}}")?;
+        Ok(dot)
+    }
+}
+
+impl GraphNode {
+    fn identifier(&self) -> String {
+        format!("{}::{}", self.cycler, self.name)
+    }
+}
+
+fn graph_node(cycler: &Cycler, node: &Node, is_setup_node: bool) -> GraphNode {
+    GraphNode {
+        cycler: cycler.name.clone(),
+        name: node.name.clone(),
+        module: node.module.to_token_stream().to_string().replace(' ', ""),
+        is_setup_node,
+        outputs: node
+            .contexts
+            .main_outputs
+            .iter()
+            .filter_map(|field| match field {
+                Field::MainOutput { name, .. } => Some(name.to_string()),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cyclers::CyclerKind,
+        test_utilities::{cycler, cyclers, node},
+    };
+
+    use super::*;
+
+    #[test]
+    fn edges_connect_producers_with_consumers_across_cyclers() {
+        let cyclers = cyclers(vec![
+            cycler(
+                "Control",
+                CyclerKind::RealTime,
+                &[""],
+                vec![
+                    node(
+                        "Producer",
+                        r#"
+                        #[context] pub struct CreationContext {}
+                        #[context] pub struct CycleContext {}
+                        #[context] pub struct MainOutputs { pub value: MainOutput<Option<f32>> }
+                        "#,
+                    ),
+                    node(
+                        "Consumer",
+                        r#"
+                        #[context] pub struct CreationContext {}
+                        #[context] pub struct CycleContext {
+                            value: RequiredInput<Option<f32>, "value?">,
+                            detected: PerceptionInput<bool, "VisionTop", "detected">,
+                        }
+                        #[context] pub struct MainOutputs {}
+                        "#,
+                    ),
+                ],
+            ),
+            cycler(
+                "Vision",
+                CyclerKind::Perception,
+                &["Top", "Bottom"],
+                vec![node(
+                    "Detector",
+                    r#"
+                    #[context] pub struct CreationContext {}
+                    #[context] pub struct CycleContext {}
+                    #[context] pub struct MainOutputs { pub detected: MainOutput<bool> }
+                    "#,
+                )],
+            ),
+        ]);
+
+        let graph = DependencyGraph::from_cyclers(&cyclers);
+
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.nodes[graph.edges[1].producer].name, "Detector");
+        assert_eq!(graph.nodes[graph.edges[1].consumer].name, "Consumer");
+        let dot = graph.to_dot().unwrap();
+        assert!(dot.contains(
+            r#""Control::Producer" -> "Control::Consumer" [label="value", style=solid];"#
+        ));
+    }
+}
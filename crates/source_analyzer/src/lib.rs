@@ -1,11 +1,15 @@
 pub mod contexts;
 pub mod cyclers;
 pub mod error;
+pub mod graph;
+pub mod lint;
 pub mod manifest;
 pub mod node;
 pub mod path;
 pub mod pretty;
 pub mod struct_hierarchy;
 pub mod structs;
+#[cfg(test)]
+mod test_utilities;
 mod to_absolute;
 mod uses;
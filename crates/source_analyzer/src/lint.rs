@@ -0,0 +1,389 @@
+//! Static checks of the dependencies between nodes and of their declared parameters
+
+use std::{collections::BTreeMap, fmt};
+
+use quote::ToTokens;
+use serde_json::Value;
+
+use crate::{
+    contexts::Field,
+    cyclers::{Cycler, CyclerName, Cyclers},
+    graph::Consumption,
+    node::NodeName,
+    path::Path,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Lint {
+    UnusedOutput {
+        cycler: CyclerName,
+        node: NodeName,
+        output: String,
+    },
+    MissingParameter {
+        cycler: CyclerName,
+        node: NodeName,
+        path: String,
+    },
+    CollidingAdditionalOutputs {
+        cycler: CyclerName,
+        first: (NodeName, String),
+        second: (NodeName, String),
+    },
+    MismatchingInputTypes {
+        cycler: CyclerName,
+        path: String,
+        /// Nodes with the data type they request, the producing node comes first if known
+        data_types: Vec<(NodeName, String)>,
+    },
+}
+
+impl Lint {
+    /// Unused outputs may still be subscribed from the outside and are therefore only warnings
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Lint::UnusedOutput { .. })
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lint::UnusedOutput {
+                cycler,
+                node,
+                output,
+            } => write!(
+                formatter,
+                "main output `{output}` of {cycler}::{node} is not used by any node"
+            ),
+            Lint::MissingParameter { cycler, node, path } => write!(
+                formatter,
+                "parameter `{path}` of {cycler}::{node} is missing in the default parameters"
+            ),
+            Lint::CollidingAdditionalOutputs {
+                cycler,
+                first,
+                second,
+            } => write!(
+                formatter,
+                "additional output `{}` of {cycler}::{} collides with `{}` of {cycler}::{}",
+                first.1, first.0, second.1, second.0
+            ),
+            Lint::MismatchingInputTypes {
+                cycler,
+                path,
+                data_types,
+            } => {
+                write!(formatter, "`{path}` of {cycler} is requested as")?;
+                for (index, (node, data_type)) in data_types.iter().enumerate() {
+                    let separator = if index == 0 { "" } else { "," };
+                    write!(formatter, "{separator} `{data_type}` by {node}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub fn lint(cyclers: &Cyclers, parameters: &Value) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    lints.extend(unused_outputs(cyclers));
+    lints.extend(missing_parameters(cyclers, parameters));
+    lints.extend(colliding_additional_outputs(cyclers));
+    lints.extend(mismatching_input_types(cyclers));
+    lints
+}
+
+fn consumptions(cyclers: &Cyclers) -> impl Iterator<Item = (&NodeName, Consumption<'_>)> {
+    cyclers.cyclers.iter().flat_map(move |cycler| {
+        cycler.iter_nodes().flat_map(move |node| {
+            node.contexts
+                .cycle_context
+                .iter()
+                .filter_map(move |field| Consumption::try_from_field(field, cycler, cyclers))
+                .map(move |consumption| (&node.name, consumption))
+        })
+    })
+}
+
+fn unused_outputs(cyclers: &Cyclers) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    for cycler in &cyclers.cyclers {
+        for node in cycler.iter_nodes() {
+            for field in &node.contexts.main_outputs {
+                let Field::MainOutput { name, .. } = field else {
+                    continue;
+                };
+                let output = name.to_string();
+                let is_used = consumptions(cyclers).any(|(_consumer, consumption)| {
+                    consumption.producing_cycler.name == cycler.name
+                        && consumption.output_name() == output
+                });
+                if !is_used {
+                    lints.push(Lint::UnusedOutput {
+                        cycler: cycler.name.clone(),
+                        node: node.name.clone(),
+                        output,
+                    });
+                }
+            }
+        }
+    }
+    lints
+}
+
+fn missing_parameters(cyclers: &Cyclers, parameters: &Value) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    for cycler in &cyclers.cyclers {
+        for node in cycler.iter_nodes() {
+            for field in node
+                .contexts
+                .creation_context
+                .iter()
+                .chain(node.contexts.cycle_context.iter())
+            {
+                let Field::Parameter { path, .. } = field else {
+                    continue;
+                };
+                for path in path.expand_variables(&cycler.instances) {
+                    if !is_parameter_present(parameters, &path) {
+                        lints.push(Lint::MissingParameter {
+                            cycler: cycler.name.clone(),
+                            node: node.name.clone(),
+                            path: path_to_string(&path),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    lints
+}
+
+/// Optional segments are allowed to be absent or `null`
+fn is_parameter_present(parameters: &Value, path: &Path) -> bool {
+    let mut value = parameters;
+    for segment in &path.segments {
+        match value.get(&segment.name) {
+            Some(Value::Null) | None if segment.is_optional => return true,
+            Some(child) => value = child,
+            None => return false,
+        }
+    }
+    true
+}
+
+fn colliding_additional_outputs(cyclers: &Cyclers) -> Vec<Lint> {
+    let mut lints = Vec::new();
+    for cycler in &cyclers.cyclers {
+        let additional_outputs = additional_outputs(cycler);
+        for (index, (first_node, first_path)) in additional_outputs.iter().enumerate() {
+            for (second_node, second_path) in &additional_outputs[index + 1..] {
+                if is_prefix(first_path, second_path) || is_prefix(second_path, first_path) {
+                    lints.push(Lint::CollidingAdditionalOutputs {
+                        cycler: cycler.name.clone(),
+                        first: ((*first_node).clone(), path_to_string(first_path)),
+                        second: ((*second_node).clone(), path_to_string(second_path)),
+                    });
+                }
+            }
+        }
+    }
+    lints
+}
+
+fn additional_outputs(cycler: &Cycler) -> Vec<(&NodeName, Path)> {
+    cycler
+        .iter_nodes()
+        .flat_map(|node| {
+            node.contexts
+                .creation_context
+                .iter()
+                .chain(node.contexts.cycle_context.iter())
+                .filter_map(|field| match field {
+                    Field::AdditionalOutput { path, .. } => Some(path),
+                    _ => None,
+                })
+                .flat_map(|path| path.expand_variables(&cycler.instances))
+                .map(move |path| (&node.name, path))
+        })
+        .collect()
+}
+
+fn is_prefix(prefix: &Path, path: &Path) -> bool {
+    prefix.segments.len() <= path.segments.len()
+        && prefix
+            .segments
+            .iter()
+            .zip(path.segments.iter())
+            .all(|(prefix, segment)| prefix.name == segment.name)
+}
+
+fn mismatching_input_types(cyclers: &Cyclers) -> Vec<Lint> {
+    let mut requested_types: BTreeMap<(&CyclerName, String), Vec<(NodeName, String)>> =
+        BTreeMap::new();
+    for (consumer, consumption) in consumptions(cyclers) {
+        let data_types = requested_types
+            .entry((
+                &consumption.producing_cycler.name,
+                path_to_string(consumption.path),
+            ))
+            .or_insert_with(|| {
+                if consumption.path.segments.len() > 1 {
+                    return Vec::new();
+                }
+                main_output_type(consumption.producing_cycler, consumption.output_name())
+                    .into_iter()
+                    .collect()
+            });
+        data_types.push((
+            consumer.clone(),
+            consumption.data_type.to_token_stream().to_string(),
+        ));
+    }
+
+    requested_types
+        .into_iter()
+        .filter(|(_key, data_types)| {
+            data_types
+                .iter()
+                .any(|(_node, data_type)| *data_type != data_types[0].1)
+        })
+        .map(|((cycler, path), data_types)| Lint::MismatchingInputTypes {
+            cycler: cycler.clone(),
+            path,
+            data_types,
+        })
+        .collect()
+}
+
+fn main_output_type(cycler: &Cycler, output: &str) -> Option<(NodeName, String)> {
+    cycler.iter_nodes().find_map(|node| {
+        node.contexts
+            .main_outputs
+            .iter()
+            .find_map(|field| match field {
+                Field::MainOutput { data_type, name } if name == output => {
+                    Some((node.name.clone(), data_type.to_token_stream().to_string()))
+                }
+                _ => None,
+            })
+    })
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| {
+            let variable = if segment.is_variable { "$" } else { "" };
+            let optional = if segment.is_optional { "?" } else { "" };
+            format!("{variable}{}{optional}", segment.name)
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        cyclers::CyclerKind,
+        test_utilities::{cycler, cyclers, node},
+    };
+
+    use super::*;
+
+    fn lints_of(nodes: Vec<crate::node::Node>, parameters: Value) -> Vec<Lint> {
+        let cyclers = cyclers(vec![cycler(
+            "Vision",
+            CyclerKind::Perception,
+            &["Top", "Bottom"],
+            nodes,
+        )]);
+        lint(&cyclers, &parameters)
+    }
+
+    #[test]
+    fn unused_outputs_and_missing_parameters_are_reported() {
+        let lints = lints_of(
+            vec![node(
+                "Detector",
+                r#"
+                #[context] pub struct CreationContext {
+                    threshold: Parameter<f32, "detector.$cycler_instance.threshold">,
+                    offset: Parameter<Option<f32>, "detector.offset?">,
+                }
+                #[context] pub struct CycleContext {}
+                #[context] pub struct MainOutputs { pub detected: MainOutput<bool> }
+                "#,
+            )],
+            json!({ "detector": { "vision_top": { "threshold": 0.5 } } }),
+        );
+
+        assert_eq!(
+            lints,
+            vec![
+                Lint::UnusedOutput {
+                    cycler: "Vision".to_string(),
+                    node: "Detector".to_string(),
+                    output: "detected".to_string(),
+                },
+                Lint::MissingParameter {
+                    cycler: "Vision".to_string(),
+                    node: "Detector".to_string(),
+                    path: "detector.vision_bottom.threshold".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn colliding_additional_outputs_and_mismatching_types_are_reported() {
+        let lints = lints_of(
+            vec![
+                node(
+                    "Producer",
+                    r#"
+                    #[context] pub struct CreationContext {}
+                    #[context] pub struct CycleContext {
+                        lines: AdditionalOutput<Vec<f32>, "lines">,
+                    }
+                    #[context] pub struct MainOutputs { pub value: MainOutput<Option<f32>> }
+                    "#,
+                ),
+                node(
+                    "Consumer",
+                    r#"
+                    #[context] pub struct CreationContext {}
+                    #[context] pub struct CycleContext {
+                        value: Input<Option<f64>, "value">,
+                        points: AdditionalOutput<Vec<f32>, "lines.points">,
+                    }
+                    #[context] pub struct MainOutputs {}
+                    "#,
+                ),
+            ],
+            json!({}),
+        );
+
+        assert_eq!(
+            lints,
+            vec![
+                Lint::CollidingAdditionalOutputs {
+                    cycler: "Vision".to_string(),
+                    first: ("Producer".to_string(), "lines".to_string()),
+                    second: ("Consumer".to_string(), "lines.points".to_string()),
+                },
+                Lint::MismatchingInputTypes {
+                    cycler: "Vision".to_string(),
+                    path: "value".to_string(),
+                    data_types: vec![
+                        ("Producer".to_string(), "Option < f32 >".to_string()),
+                        ("Consumer".to_string(), "Option < f64 >".to_string()),
+                    ],
+                },
+            ]
+        );
+    }
+}
@@ -1,17 +1,359 @@
+//! Loading of the framework manifest describing cyclers and their nodes
+//!
+//! The manifest is a TOML file declaring all targets and cyclers:
+//!
+//! ```toml
+//! targets = ["nao", "webots", "simulator"]
+//!
+//! [[cyclers]]
+//! name = "Control"
+//! kind = "RealTime"
+//! instances = [""]
+//! setup_nodes = [
+//!     { node = "control::sensor_data_receiver", targets = ["nao", "webots"] },
+//!     { node = "control::fake_data", targets = ["simulator"] },
+//! ]
+//! nodes = [
+//!     "control::behavior::node",
+//!     { node = "control::localization", enabled = false },
+//! ]
+//! ```
+//!
+//! Cyclers and nodes without `targets` are part of every target.
+
+use std::{collections::HashSet, fs::read_to_string, ops::Range, path::Path};
+
 use serde::Deserialize;
+use thiserror::Error;
+use toml::Spanned;
 
-use crate::cyclers::CyclerKind;
+use crate::{cyclers::CyclerKind, error::Error};
 
 #[derive(Debug, Default)]
 pub struct FrameworkManifest {
     pub cyclers: Vec<CyclerManifest>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 pub struct CyclerManifest {
-    pub name: &'static str,
+    pub name: String,
     pub kind: CyclerKind,
-    pub instances: Vec<&'static str>,
-    pub setup_nodes: Vec<&'static str>,
-    pub nodes: Vec<&'static str>,
+    pub instances: Vec<String>,
+    pub setup_nodes: Vec<String>,
+    pub nodes: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error("{position}: unknown target `{target}`, expected one of {declared_targets:?}")]
+    UnknownTarget {
+        target: String,
+        declared_targets: Vec<String>,
+        position: Position,
+    },
+    #[error("target `{target}` is not declared, expected one of {declared_targets:?}")]
+    UndeclaredTarget {
+        target: String,
+        declared_targets: Vec<String>,
+    },
+    #[error("{position}: target `{target}` is declared more than once")]
+    DuplicateTarget { target: String, position: Position },
+    #[error("{position}: cycler `{cycler}` is declared more than once")]
+    DuplicateCycler { cycler: String, position: Position },
+    #[error("{position}: cycler `{cycler}` has no instances")]
+    NoInstances { cycler: String, position: Position },
+    #[error("{position}: node `{node}` is listed more than once in cycler `{cycler}`")]
+    DuplicateNode {
+        cycler: String,
+        node: String,
+        position: Position,
+    },
+    #[error("{position}: `{node}` is not a node path like `crate::module`")]
+    InvalidNodePath { node: String, position: Position },
+}
+
+/// One-based line and column in the manifest file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}:{}", self.line, self.column)
+    }
+}
+
+impl Position {
+    fn from_span(source: &str, span: Range<usize>) -> Self {
+        let preceding = &source[..span.start.min(source.len())];
+        let line = preceding.matches('\n').count() + 1;
+        let column = preceding.len() - preceding.rfind('\n').map_or(0, |index| index + 1) + 1;
+        Self { line, column }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    targets: Vec<Spanned<String>>,
+    cyclers: Vec<CyclerEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CyclerEntry {
+    name: Spanned<String>,
+    kind: CyclerKind,
+    instances: Vec<String>,
+    targets: Option<Vec<Spanned<String>>>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default)]
+    setup_nodes: Vec<Spanned<NodeEntry>>,
+    #[serde(default)]
+    nodes: Vec<Spanned<NodeEntry>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NodeEntry {
+    Path(String),
+    Detailed {
+        node: String,
+        targets: Option<Vec<String>>,
+        #[serde(default = "default_enabled")]
+        enabled: bool,
+    },
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl FrameworkManifest {
+    pub fn try_from_file(path: impl AsRef<Path>, target: &str) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let source = read_to_string(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_path_buf(),
+        })?;
+        Self::try_from_toml(&source, target).map_err(|source| Error::Manifest {
+            source,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Parses and validates a manifest, keeping only the enabled cyclers and nodes of `target`
+    pub fn try_from_toml(source: &str, target: &str) -> Result<Self, ManifestError> {
+        let file: ManifestFile = toml::from_str(source)?;
+        let position = |span: Range<usize>| Position::from_span(source, span);
+
+        let mut declared_targets = Vec::new();
+        for declared_target in &file.targets {
+            if declared_targets.contains(declared_target.get_ref()) {
+                return Err(ManifestError::DuplicateTarget {
+                    target: declared_target.get_ref().clone(),
+                    position: position(declared_target.span()),
+                });
+            }
+            declared_targets.push(declared_target.get_ref().clone());
+        }
+        if !declared_targets.iter().any(|declared| declared == target) {
+            return Err(ManifestError::UndeclaredTarget {
+                target: target.to_string(),
+                declared_targets,
+            });
+        }
+        let validate_target = |target: &str, span: Range<usize>| {
+            if declared_targets.iter().any(|declared| declared == target) {
+                Ok(())
+            } else {
+                Err(ManifestError::UnknownTarget {
+                    target: target.to_string(),
+                    declared_targets: declared_targets.clone(),
+                    position: position(span),
+                })
+            }
+        };
+
+        let mut cycler_names = HashSet::new();
+        let mut cyclers = Vec::new();
+        for cycler in file.cyclers {
+            let name = cycler.name.get_ref();
+            if !cycler_names.insert(name.clone()) {
+                return Err(ManifestError::DuplicateCycler {
+                    cycler: name.clone(),
+                    position: position(cycler.name.span()),
+                });
+            }
+            if cycler.instances.is_empty() {
+                return Err(ManifestError::NoInstances {
+                    cycler: name.clone(),
+                    position: position(cycler.name.span()),
+                });
+            }
+            for cycler_target in cycler.targets.iter().flatten() {
+                validate_target(cycler_target.get_ref(), cycler_target.span())?;
+            }
+
+            let mut node_names = HashSet::new();
+            let mut select_nodes = |entries: &[Spanned<NodeEntry>]| {
+                let mut selected = Vec::new();
+                for entry in entries {
+                    let (node, targets, enabled) = match entry.get_ref() {
+                        NodeEntry::Path(node) => (node, None, true),
+                        NodeEntry::Detailed {
+                            node,
+                            targets,
+                            enabled,
+                        } => (node, targets.as_ref(), *enabled),
+                    };
+                    if !is_node_path(node) {
+                        return Err(ManifestError::InvalidNodePath {
+                            node: node.clone(),
+                            position: position(entry.span()),
+                        });
+                    }
+                    if !node_names.insert(node.clone()) {
+                        return Err(ManifestError::DuplicateNode {
+                            cycler: name.clone(),
+                            node: node.clone(),
+                            position: position(entry.span()),
+                        });
+                    }
+                    for node_target in targets.into_iter().flatten() {
+                        validate_target(node_target, entry.span())?;
+                    }
+                    let is_in_target = match targets {
+                        Some(targets) => targets.iter().any(|node_target| node_target == target),
+                        None => true,
+                    };
+                    if enabled && is_in_target {
+                        selected.push(node.clone());
+                    }
+                }
+                Ok(selected)
+            };
+            let setup_nodes = select_nodes(&cycler.setup_nodes)?;
+            let nodes = select_nodes(&cycler.nodes)?;
+
+            let is_in_target = match &cycler.targets {
+                Some(targets) => targets
+                    .iter()
+                    .any(|cycler_target| cycler_target.get_ref() == target),
+                None => true,
+            };
+            if cycler.enabled && is_in_target {
+                cyclers.push(CyclerManifest {
+                    name: name.clone(),
+                    kind: cycler.kind,
+                    instances: cycler.instances,
+                    setup_nodes,
+                    nodes,
+                });
+            }
+        }
+
+        Ok(Self { cyclers })
+    }
+}
+
+fn is_node_path(node: &str) -> bool {
+    let segments: Vec<_> = node.split("::").collect();
+    segments.len() >= 2
+        && segments.iter().all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|character| character.is_ascii_alphanumeric() || character == '_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+targets = ["nao", "simulator"]
+
+[[cyclers]]
+name = "Vision"
+kind = "Perception"
+instances = ["Top", "Bottom"]
+targets = ["nao"]
+setup_nodes = ["vision::image_receiver"]
+
+[[cyclers]]
+name = "Control"
+kind = "RealTime"
+instances = [""]
+setup_nodes = [
+    { node = "control::sensor_data_receiver", targets = ["nao"] },
+    { node = "control::fake_data", targets = ["simulator"] },
+]
+nodes = [
+    "control::behavior::node",
+    { node = "control::localization", enabled = false },
+]
+"#;
+
+    #[test]
+    fn cyclers_and_nodes_are_selected_by_target() {
+        let nao = FrameworkManifest::try_from_toml(MANIFEST, "nao").unwrap();
+        let simulator = FrameworkManifest::try_from_toml(MANIFEST, "simulator").unwrap();
+
+        assert_eq!(nao.cyclers.len(), 2);
+        assert_eq!(nao.cyclers[0].instances, vec!["Top", "Bottom"]);
+        assert_eq!(
+            nao.cyclers[1].setup_nodes,
+            vec!["control::sensor_data_receiver"]
+        );
+        assert_eq!(nao.cyclers[1].nodes, vec!["control::behavior::node"]);
+        assert_eq!(simulator.cyclers.len(), 1);
+        assert_eq!(simulator.cyclers[0].name, "Control");
+        assert_eq!(simulator.cyclers[0].setup_nodes, vec!["control::fake_data"]);
+    }
+
+    #[test]
+    fn validation_errors_point_at_the_offending_entry() {
+        let unknown_target = MANIFEST.replace(r#"targets = ["simulator"]"#, r#"targets = ["sim"]"#);
+        let duplicate_node = MANIFEST.replace("control::localization", "control::behavior::node");
+
+        match FrameworkManifest::try_from_toml(&unknown_target, "nao") {
+            Err(ManifestError::UnknownTarget {
+                target, position, ..
+            }) => {
+                assert_eq!(target, "sim");
+                assert_eq!(
+                    position,
+                    Position {
+                        line: 17,
+                        column: 5
+                    }
+                );
+            }
+            result => panic!("unexpected result {result:?}"),
+        }
+        match FrameworkManifest::try_from_toml(&duplicate_node, "nao") {
+            Err(ManifestError::DuplicateNode { node, position, .. }) => {
+                assert_eq!(node, "control::behavior::node");
+                assert_eq!(
+                    position,
+                    Position {
+                        line: 21,
+                        column: 5
+                    }
+                );
+            }
+            result => panic!("unexpected result {result:?}"),
+        }
+        assert!(matches!(
+            FrameworkManifest::try_from_toml(MANIFEST, "webots"),
+            Err(ManifestError::UndeclaredTarget { .. })
+        ));
+    }
 }
@@ -5,7 +5,7 @@ use std::{
 };
 
 use quote::ToTokens;
-use syn::{parse_file, ImplItem, Item, ItemImpl, Type};
+use syn::{parse_file, ImplItem, Item, ItemImpl, ItemStruct, Type};
 
 use crate::{
     contexts::Contexts,
@@ -20,6 +20,8 @@ pub struct Node {
     pub module: syn::Path,
     pub file_path: PathBuf,
     pub contexts: Contexts,
+    /// Whether the node state survives a recording, i.e. no field is deserialized via `deserialize_not_implemented`
+    pub has_replayable_state: bool,
 }
 
 pub fn parse_rust_file(file_path: impl AsRef<Path>) -> Result<syn::File, Error> {
@@ -58,11 +60,13 @@ impl Node {
             .ok_or_else(|| wrap_error(ParseError::new_spanned(&rust_file, "cannot find node declaration, expected a type with new(...) and cycle(...) method")))?
             .to_string();
         let contexts = Contexts::try_from_file(&rust_file).map_err(wrap_error)?;
+        let has_replayable_state = has_replayable_state(&rust_file, &name);
         Ok(Self {
             name,
             module,
             file_path,
             contexts,
+            has_replayable_state,
         })
     }
 }
@@ -80,6 +84,27 @@ fn file_path_from_module_path(root: &Path, module: syn::Path) -> Result<PathBuf,
     Ok(root.join(format!("{crate_name}/src/{path_to_module}.rs")))
 }
 
+pub fn has_replayable_state(rust_file: &syn::File, node_name: &str) -> bool {
+    !rust_file.items.iter().any(|item| match item {
+        Item::Struct(structure) if structure.ident == node_name => {
+            has_field_without_deserialization(structure)
+        }
+        _ => false,
+    })
+}
+
+fn has_field_without_deserialization(structure: &ItemStruct) -> bool {
+    structure.fields.iter().any(|field| {
+        field.attrs.iter().any(|attribute| {
+            attribute.path.is_ident("serde")
+                && attribute
+                    .tokens
+                    .to_string()
+                    .contains("deserialize_not_implemented")
+        })
+    })
+}
+
 fn has_new_and_cycle_method(implementation: &ItemImpl) -> bool {
     implementation
         .items
@@ -1,9 +1,10 @@
 use std::{collections::BTreeMap, iter::once};
 
+use convert_case::{Case, Casing};
 use quote::format_ident;
 use syn::{
-    punctuated::Punctuated, AngleBracketedGenericArguments, GenericArgument, PathArguments, Type,
-    TypePath,
+    parse_quote, punctuated::Punctuated, AngleBracketedGenericArguments, GenericArgument,
+    PathArguments, Type, TypePath,
 };
 use thiserror::Error;
 
@@ -34,6 +35,9 @@ impl Structs {
 
         for cycler in cyclers.cyclers.iter() {
             let cycler_structs = structs.cyclers.entry(cycler.name.clone()).or_default();
+            structs
+                .parameters
+                .insert(cycle_timing_insertion_rules(&cycler.name))?;
 
             for node in cycler.iter_nodes() {
                 for field in node.contexts.main_outputs.iter() {
@@ -164,6 +168,23 @@ fn path_to_insertion_rules<'path>(
         }))
 }
 
+/// Each cycler reads its deadline and histogram configuration from `cycle_timing.<cycler>`
+fn cycle_timing_insertion_rules(cycler_name: &str) -> [InsertionRule; 5] {
+    [
+        InsertionRule::BeginStruct,
+        InsertionRule::InsertField {
+            name: "cycle_timing".to_string(),
+        },
+        InsertionRule::BeginStruct,
+        InsertionRule::InsertField {
+            name: cycler_name.to_case(Case::Snake),
+        },
+        InsertionRule::AppendDataType {
+            data_type: parse_quote! { framework::CycleTimingParameters },
+        },
+    ]
+}
+
 fn unwrap_option_type(data_type: Type) -> Type {
     match data_type {
         Type::Path(TypePath {
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use syn::parse_file;
+
+use crate::{
+    contexts::Contexts,
+    cyclers::{Cycler, CyclerKind, Cyclers},
+    node::{has_replayable_state, Node},
+};
+
+/// Creates a node from the source code of its context structs
+pub fn node(name: &str, contexts: &str) -> Node {
+    let file = parse_file(contexts).expect("failed to parse contexts");
+    Node {
+        name: name.to_string(),
+        module: syn::parse_str(&format!("test::{name}")).unwrap(),
+        file_path: PathBuf::from(format!("{name}.rs")),
+        contexts: Contexts::try_from_file(&file).expect("failed to read contexts"),
+        has_replayable_state: has_replayable_state(&file, name),
+    }
+}
+
+pub fn cycler(name: &str, kind: CyclerKind, instances: &[&str], nodes: Vec<Node>) -> Cycler {
+    Cycler {
+        name: name.to_string(),
+        kind,
+        instances: instances
+            .iter()
+            .map(|instance| format!("{name}{instance}"))
+            .collect(),
+        setup_nodes: Vec::new(),
+        cycle_nodes: nodes,
+    }
+}
+
+pub fn cyclers(cyclers: Vec<Cycler>) -> Cyclers {
+    Cyclers { cyclers }
+}
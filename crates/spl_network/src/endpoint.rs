@@ -68,7 +68,7 @@ impl Endpoint {
                 },
                 result = self.spl_socket.recv_from(&mut spl_buffer) => {
                     let (received_bytes, _address) = result.map_err(Error::ReadError)?;
-                    match bincode::deserialize(&spl_buffer[0..received_bytes]) {
+                    match spl_buffer[0..received_bytes].try_into() {
                         Ok(parsed_message) => {
                             break Ok(IncomingMessage::Spl(parsed_message));
                         }
@@ -89,23 +89,19 @@ impl Endpoint {
                 self.send_game_controller_visual_referee_message(message)
                     .await;
             }
-            OutgoingMessage::Spl(message) => match bincode::serialize(&message) {
-                Ok(message) => {
-                    if let Err(error) = self
-                        .spl_socket
-                        .send_to(
-                            message.as_slice(),
-                            SocketAddr::new(Ipv4Addr::BROADCAST.into(), self.ports.spl),
-                        )
-                        .await
-                    {
-                        warn!("Failed to send UDP datagram via SPL socket: {error:?}")
-                    }
-                }
-                Err(error) => {
-                    warn!("Failed to serialize Hulk Message: {error:?}")
+            OutgoingMessage::Spl(message) => {
+                let message: Vec<u8> = message.into();
+                if let Err(error) = self
+                    .spl_socket
+                    .send_to(
+                        message.as_slice(),
+                        SocketAddr::new(Ipv4Addr::BROADCAST.into(), self.ports.spl),
+                    )
+                    .await
+                {
+                    warn!("Failed to send UDP datagram via SPL socket: {error:?}")
                 }
-            },
+            }
             OutgoingMessage::VisualReferee(message) => {
                 let message: Vec<u8> = message.into();
                 self.send_game_controller_visual_referee_message(message)
@@ -136,7 +132,7 @@ impl Endpoint {
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct Ports {
-    game_controller_state: u16,
-    game_controller_return: u16,
-    spl: u16,
+    pub game_controller_state: u16,
+    pub game_controller_return: u16,
+    pub spl: u16,
 }
@@ -0,0 +1,98 @@
+use std::{
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    time::Duration,
+};
+
+use local_game_controller::{Addresses, Command, Game, LocalGameController};
+use nalgebra::Isometry2;
+use spl_network::endpoint::{Endpoint, Ports};
+use spl_network_messages::{
+    GameControllerReturnMessage, GameControllerStateMessage, GameState, PlayerNumber, Team,
+};
+use tokio::time::timeout;
+use types::messages::{IncomingMessage, OutgoingMessage};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn free_port() -> u16 {
+    UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+async fn next_state(endpoint: &Endpoint) -> GameControllerStateMessage {
+    loop {
+        match timeout(TIMEOUT, endpoint.read()).await.unwrap().unwrap() {
+            IncomingMessage::GameController(message) => break message,
+            IncomingMessage::Spl(_) => continue,
+        }
+    }
+}
+
+async fn start() -> (Endpoint, LocalGameController) {
+    let ports = Ports {
+        game_controller_state: free_port(),
+        game_controller_return: free_port(),
+        spl: free_port(),
+    };
+    let endpoint = Endpoint::new(ports.clone()).await.unwrap();
+    let game_controller = LocalGameController::start(
+        Game::default(),
+        Addresses {
+            game_controller_state: SocketAddr::from((
+                Ipv4Addr::LOCALHOST,
+                ports.game_controller_state,
+            )),
+            game_controller_return: SocketAddr::from((
+                Ipv4Addr::LOCALHOST,
+                ports.game_controller_return,
+            )),
+            spl: None,
+        },
+    )
+    .await
+    .unwrap();
+    (endpoint, game_controller)
+}
+
+#[tokio::test]
+async fn robot_follows_referee_commands_and_answers() {
+    let (endpoint, game_controller) = start().await;
+
+    assert_eq!(next_state(&endpoint).await.game_state, GameState::Initial);
+
+    game_controller.apply("ready opponent".parse::<Command>().unwrap());
+    game_controller.apply(
+        "penalize hulks 3 player-pushing"
+            .parse::<Command>()
+            .unwrap(),
+    );
+    let state = loop {
+        let state = next_state(&endpoint).await;
+        if state.game_state == GameState::Ready {
+            break state;
+        }
+    };
+    assert_eq!(state.kicking_team, Team::Opponent);
+    assert!(state.hulks_team.players[2].penalty.is_some());
+    assert!(state.hulks_team.players[1].penalty.is_none());
+
+    endpoint
+        .write(OutgoingMessage::GameController(
+            GameControllerReturnMessage {
+                player_number: PlayerNumber::Four,
+                fallen: true,
+                robot_to_field: Isometry2::identity(),
+                ball_position: None,
+            },
+        ))
+        .await;
+    let record = game_controller
+        .wait_for_return_message(PlayerNumber::Four, TIMEOUT)
+        .await
+        .unwrap();
+    assert!(record.message.fallen);
+    assert_eq!(record.number_of_received_messages, 1);
+}
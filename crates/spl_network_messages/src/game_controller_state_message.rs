@@ -3,6 +3,7 @@ use std::{
     ffi::c_char,
     mem::size_of,
     ptr::read,
+    slice::from_raw_parts,
     time::Duration,
 };
 
@@ -12,11 +13,11 @@ use serialize_hierarchy::SerializeHierarchy;
 
 use crate::{
     bindings::{
-        RoboCupGameControlData, RobotInfo, COMPETITION_PHASE_PLAYOFF, COMPETITION_PHASE_ROUNDROBIN,
-        COMPETITION_TYPE_DYNAMIC_BALL_HANDLING, COMPETITION_TYPE_NORMAL,
-        GAMECONTROLLER_STRUCT_HEADER, GAMECONTROLLER_STRUCT_VERSION, GAME_PHASE_NORMAL,
-        GAME_PHASE_OVERTIME, GAME_PHASE_PENALTYSHOOT, GAME_PHASE_TIMEOUT, MAX_NUM_PLAYERS,
-        PENALTY_MANUAL, PENALTY_NONE, PENALTY_SPL_ILLEGAL_BALL_CONTACT,
+        RoboCupGameControlData, RobotInfo, TeamInfo, COMPETITION_PHASE_PLAYOFF,
+        COMPETITION_PHASE_ROUNDROBIN, COMPETITION_TYPE_DYNAMIC_BALL_HANDLING,
+        COMPETITION_TYPE_NORMAL, GAMECONTROLLER_STRUCT_HEADER, GAMECONTROLLER_STRUCT_VERSION,
+        GAME_PHASE_NORMAL, GAME_PHASE_OVERTIME, GAME_PHASE_PENALTYSHOOT, GAME_PHASE_TIMEOUT,
+        MAX_NUM_PLAYERS, PENALTY_MANUAL, PENALTY_NONE, PENALTY_SPL_ILLEGAL_BALL_CONTACT,
         PENALTY_SPL_ILLEGAL_MOTION_IN_SET, PENALTY_SPL_ILLEGAL_POSITION,
         PENALTY_SPL_ILLEGAL_POSITION_IN_SET, PENALTY_SPL_INACTIVE_PLAYER,
         PENALTY_SPL_LEAVING_THE_FIELD, PENALTY_SPL_LOCAL_GAME_STUCK, PENALTY_SPL_PLAYER_PUSHING,
@@ -197,6 +198,66 @@ impl TryFrom<RoboCupGameControlData> for GameControllerStateMessage {
     }
 }
 
+impl From<GameControllerStateMessage> for Vec<u8> {
+    fn from(message: GameControllerStateMessage) -> Self {
+        let message: RoboCupGameControlData = message.into();
+        unsafe {
+            from_raw_parts(
+                &message as *const RoboCupGameControlData as *const u8,
+                size_of::<RoboCupGameControlData>(),
+            )
+        }
+        .to_vec()
+    }
+}
+
+impl From<GameControllerStateMessage> for RoboCupGameControlData {
+    fn from(message: GameControllerStateMessage) -> Self {
+        let kicking_team = match message.kicking_team {
+            Team::Hulks => message.hulks_team.team_number,
+            Team::Opponent => message.opponent_team.team_number,
+            Team::Uncertain => 0,
+        };
+        let players_per_team = message
+            .hulks_team
+            .players
+            .len()
+            .max(message.opponent_team.players.len())
+            .min(MAX_NUM_PLAYERS as usize) as u8;
+        let hulks_team = message.hulks_team.into();
+        let opponent_team = message.opponent_team.into();
+        RoboCupGameControlData {
+            header: [
+                GAMECONTROLLER_STRUCT_HEADER[0] as c_char,
+                GAMECONTROLLER_STRUCT_HEADER[1] as c_char,
+                GAMECONTROLLER_STRUCT_HEADER[2] as c_char,
+                GAMECONTROLLER_STRUCT_HEADER[3] as c_char,
+            ],
+            version: GAMECONTROLLER_STRUCT_VERSION,
+            packetNumber: 0,
+            playersPerTeam: players_per_team,
+            competitionPhase: message.competition_phase.into(),
+            competitionType: message.competition_type.into(),
+            gamePhase: message.game_phase.into(),
+            state: message.game_state.into(),
+            setPlay: message.sub_state.map_or(SET_PLAY_NONE, Into::into),
+            firstHalf: message.half.into(),
+            kickingTeam: kicking_team,
+            secsRemaining: seconds_to_i16(message.remaining_time_in_half),
+            secondaryTime: seconds_to_i16(message.secondary_time),
+            teams: if message.hulks_team_is_home_after_coin_toss {
+                [hulks_team, opponent_team]
+            } else {
+                [opponent_team, hulks_team]
+            },
+        }
+    }
+}
+
+fn seconds_to_i16(duration: Duration) -> i16 {
+    duration.as_secs().try_into().unwrap_or(i16::MAX)
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub enum CompetitionPhase {
     RoundRobin,
@@ -213,6 +274,15 @@ impl CompetitionPhase {
     }
 }
 
+impl From<CompetitionPhase> for u8 {
+    fn from(competition_phase: CompetitionPhase) -> Self {
+        match competition_phase {
+            CompetitionPhase::RoundRobin => COMPETITION_PHASE_ROUNDROBIN,
+            CompetitionPhase::PlayOff => COMPETITION_PHASE_PLAYOFF,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub enum CompetitionType {
     Normal,
@@ -229,6 +299,15 @@ impl CompetitionType {
     }
 }
 
+impl From<CompetitionType> for u8 {
+    fn from(competition_type: CompetitionType) -> Self {
+        match competition_type {
+            CompetitionType::Normal => COMPETITION_TYPE_NORMAL,
+            CompetitionType::DynamicBallHandling => COMPETITION_TYPE_DYNAMIC_BALL_HANDLING,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub enum GamePhase {
     #[default]
@@ -257,6 +336,17 @@ impl GamePhase {
     }
 }
 
+impl From<GamePhase> for u8 {
+    fn from(game_phase: GamePhase) -> Self {
+        match game_phase {
+            GamePhase::Normal => GAME_PHASE_NORMAL,
+            GamePhase::PenaltyShootout { .. } => GAME_PHASE_PENALTYSHOOT,
+            GamePhase::Overtime => GAME_PHASE_OVERTIME,
+            GamePhase::Timeout => GAME_PHASE_TIMEOUT,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
 pub enum GameState {
     Initial,
@@ -279,6 +369,18 @@ impl GameState {
     }
 }
 
+impl From<GameState> for u8 {
+    fn from(game_state: GameState) -> Self {
+        match game_state {
+            GameState::Initial => STATE_INITIAL,
+            GameState::Ready => STATE_READY,
+            GameState::Set => STATE_SET,
+            GameState::Playing => STATE_PLAYING,
+            GameState::Finished => STATE_FINISHED,
+        }
+    }
+}
+
 #[derive(
     Default, Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
 )]
@@ -314,7 +416,21 @@ impl SubState {
     }
 }
 
-#[derive(Default, Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+impl From<SubState> for u8 {
+    fn from(sub_state: SubState) -> Self {
+        match sub_state {
+            SubState::GoalKick => SET_PLAY_GOAL_KICK,
+            SubState::PushingFreeKick => SET_PLAY_PUSHING_FREE_KICK,
+            SubState::CornerKick => SET_PLAY_CORNER_KICK,
+            SubState::KickIn => SET_PLAY_KICK_IN,
+            SubState::PenaltyKick => SET_PLAY_PENALTY_KICK,
+        }
+    }
+}
+
+#[derive(
+    Default, Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
 pub enum SubState {
     #[default]
     GoalKick,
@@ -324,8 +440,11 @@ pub enum SubState {
     PenaltyKick,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
 pub enum Half {
+    #[default]
     First,
     Second,
 }
@@ -342,6 +461,15 @@ impl TryFrom<u8> for Half {
     }
 }
 
+impl From<Half> for u8 {
+    fn from(half: Half) -> Self {
+        match half {
+            Half::First => 1,
+            Half::Second => 0,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub struct TeamState {
     pub team_number: u8,
@@ -355,6 +483,44 @@ pub struct TeamState {
     pub players: Vec<Player>,
 }
 
+impl From<TeamState> for TeamInfo {
+    fn from(team: TeamState) -> Self {
+        let mut players = [RobotInfo {
+            penalty: PENALTY_NONE,
+            secsTillUnpenalised: 0,
+        }; MAX_NUM_PLAYERS as usize];
+        for (robot_info, player) in players.iter_mut().zip(team.players) {
+            *robot_info = player.into();
+        }
+        TeamInfo {
+            teamNumber: team.team_number,
+            fieldPlayerColour: team.field_player_color.into(),
+            goalkeeperColour: team.goal_keeper_color.into(),
+            goalkeeper: match team.goal_keeper_player_number {
+                PlayerNumber::One => 1,
+                PlayerNumber::Two => 2,
+                PlayerNumber::Three => 3,
+                PlayerNumber::Four => 4,
+                PlayerNumber::Five => 5,
+                PlayerNumber::Six => 6,
+                PlayerNumber::Seven => 7,
+            },
+            score: team.score,
+            penaltyShot: team.penalty_shoot_index,
+            singleShots: team
+                .penalty_shoots
+                .iter()
+                .enumerate()
+                .filter(|(_, shoot)| matches!(shoot, PenaltyShoot::Successful))
+                .fold(0, |single_shots, (shoot_index, _)| {
+                    single_shots | 1 << shoot_index
+                }),
+            messageBudget: team.remaining_amount_of_messages,
+            players,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub enum TeamColor {
     Blue,
@@ -389,6 +555,23 @@ impl TryFrom<u8> for TeamColor {
     }
 }
 
+impl From<TeamColor> for u8 {
+    fn from(team_color: TeamColor) -> Self {
+        match team_color {
+            TeamColor::Blue => TEAM_BLUE,
+            TeamColor::Red => TEAM_RED,
+            TeamColor::Yellow => TEAM_YELLOW,
+            TeamColor::Black => TEAM_BLACK,
+            TeamColor::White => TEAM_WHITE,
+            TeamColor::Green => TEAM_GREEN,
+            TeamColor::Orange => TEAM_ORANGE,
+            TeamColor::Purple => TEAM_PURPLE,
+            TeamColor::Brown => TEAM_BROWN,
+            TeamColor::Gray => TEAM_GRAY,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum PenaltyShoot {
     Successful,
@@ -411,6 +594,21 @@ impl TryFrom<RobotInfo> for Player {
     }
 }
 
+impl From<Player> for RobotInfo {
+    fn from(player: Player) -> Self {
+        match player.penalty {
+            Some(penalty) => RobotInfo {
+                penalty: penalty.into(),
+                secsTillUnpenalised: penalty.remaining().as_secs().try_into().unwrap_or(u8::MAX),
+            },
+            None => RobotInfo {
+                penalty: PENALTY_NONE,
+                secsTillUnpenalised: 0,
+            },
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub enum Penalty {
     IllegalBallContact { remaining: Duration },
@@ -450,4 +648,84 @@ impl Penalty {
             _ => bail!("unexpected penalty type"),
         }
     }
+
+    pub fn remaining(&self) -> Duration {
+        match self {
+            Penalty::IllegalBallContact { remaining }
+            | Penalty::PlayerPushing { remaining }
+            | Penalty::IllegalMotionInSet { remaining }
+            | Penalty::InactivePlayer { remaining }
+            | Penalty::IllegalPosition { remaining }
+            | Penalty::LeavingTheField { remaining }
+            | Penalty::RequestForPickup { remaining }
+            | Penalty::LocalGameStuck { remaining }
+            | Penalty::IllegalPositionInSet { remaining }
+            | Penalty::PlayerStance { remaining }
+            | Penalty::Substitute { remaining }
+            | Penalty::Manual { remaining } => *remaining,
+        }
+    }
+}
+
+impl From<Penalty> for u8 {
+    fn from(penalty: Penalty) -> Self {
+        match penalty {
+            Penalty::IllegalBallContact { .. } => PENALTY_SPL_ILLEGAL_BALL_CONTACT,
+            Penalty::PlayerPushing { .. } => PENALTY_SPL_PLAYER_PUSHING,
+            Penalty::IllegalMotionInSet { .. } => PENALTY_SPL_ILLEGAL_MOTION_IN_SET,
+            Penalty::InactivePlayer { .. } => PENALTY_SPL_INACTIVE_PLAYER,
+            Penalty::IllegalPosition { .. } => PENALTY_SPL_ILLEGAL_POSITION,
+            Penalty::LeavingTheField { .. } => PENALTY_SPL_LEAVING_THE_FIELD,
+            Penalty::RequestForPickup { .. } => PENALTY_SPL_REQUEST_FOR_PICKUP,
+            Penalty::LocalGameStuck { .. } => PENALTY_SPL_LOCAL_GAME_STUCK,
+            Penalty::IllegalPositionInSet { .. } => PENALTY_SPL_ILLEGAL_POSITION_IN_SET,
+            Penalty::PlayerStance { .. } => PENALTY_SPL_PLAYER_STANCE,
+            Penalty::Substitute { .. } => PENALTY_SUBSTITUTE,
+            Penalty::Manual { .. } => PENALTY_MANUAL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encoded_message_is_parsed_again() {
+        let team = |team_number, penalty| TeamState {
+            team_number,
+            field_player_color: TeamColor::Blue,
+            goal_keeper_color: TeamColor::Yellow,
+            goal_keeper_player_number: PlayerNumber::Two,
+            score: 3,
+            penalty_shoot_index: 2,
+            penalty_shoots: vec![PenaltyShoot::Successful, PenaltyShoot::Unsuccessful],
+            remaining_amount_of_messages: 1000,
+            players: vec![Player { penalty }, Player { penalty: None }],
+        };
+        let input_message = GameControllerStateMessage {
+            competition_phase: CompetitionPhase::PlayOff,
+            competition_type: CompetitionType::Normal,
+            game_phase: GamePhase::Normal,
+            game_state: GameState::Playing,
+            sub_state: Some(SubState::CornerKick),
+            half: Half::Second,
+            remaining_time_in_half: Duration::from_secs(300),
+            secondary_time: Duration::from_secs(20),
+            hulks_team: team(
+                HULKS_TEAM_NUMBER,
+                Some(Penalty::PlayerPushing {
+                    remaining: Duration::from_secs(12),
+                }),
+            ),
+            opponent_team: team(HULKS_TEAM_NUMBER + 1, None),
+            kicking_team: Team::Opponent,
+            hulks_team_is_home_after_coin_toss: false,
+        };
+        let bytes: Vec<u8> = input_message.clone().into();
+
+        let output_message = GameControllerStateMessage::try_from(bytes.as_slice()).unwrap();
+
+        assert_eq!(format!("{output_message:?}"), format!("{input_message:?}"));
+    }
 }
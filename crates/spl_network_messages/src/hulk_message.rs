@@ -0,0 +1,468 @@
+use std::{f32::consts::PI, time::Duration};
+
+use color_eyre::{eyre::bail, Report, Result};
+use nalgebra::{point, Isometry2, Point2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::{PlayerNumber, Role};
+
+/// Identifies team messages of the HULKs among other data on the SPL port
+pub const HULK_MESSAGE_HEADER: [u8; 2] = *b"HK";
+/// Incremented on every incompatible change of the wire format
+pub const HULK_MESSAGE_VERSION: u8 = 2;
+/// Maximum size of a team message allowed by the SPL rules
+pub const MAXIMUM_HULK_MESSAGE_SIZE: usize = 128;
+
+const FALLEN_FLAG: u8 = 1 << 0;
+const BALL_POSITION_FLAG: u8 = 1 << 1;
+const TIME_TO_REACH_KICK_POSITION_FLAG: u8 = 1 << 2;
+const KICK_TARGET_FLAG: u8 = 1 << 3;
+const WALK_TARGET_FLAG: u8 = 1 << 4;
+const KNOWN_FLAGS: u8 = FALLEN_FLAG
+    | BALL_POSITION_FLAG
+    | TIME_TO_REACH_KICK_POSITION_FLAG
+    | KICK_TARGET_FLAG
+    | WALK_TARGET_FLAG;
+
+/// Positions are transmitted in millimeters
+const POSITION_SCALE: f32 = 1000.0;
+/// Angles are transmitted in the full range of an `i16`
+const ANGLE_SCALE: f32 = i16::MAX as f32 / PI;
+/// Ball ages are transmitted in milliseconds
+const BALL_AGE_RESOLUTION: Duration = Duration::from_millis(1);
+/// Times to reach the kick position are transmitted in centiseconds
+const TIME_TO_REACH_KICK_POSITION_RESOLUTION: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct HulkMessage {
+    pub player_number: PlayerNumber,
+    pub fallen: bool,
+    pub robot_to_field: Isometry2<f32>,
+    pub ball_position: Option<BallPosition>,
+    pub time_to_reach_kick_position: Option<Duration>,
+    pub role: Role,
+    pub intention: Intention,
+}
+
+/// What the sender is currently about to do, in field coordinates
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Intention {
+    pub kick_target: Option<Point2<f32>>,
+    pub walk_target: Option<Point2<f32>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallPosition {
+    pub relative_position: Point2<f32>,
+    pub age: Duration,
+}
+
+/// Encodes the message into the versioned wire format
+///
+/// ```text
+/// header (2) | version (1) | flags (1) | player number (1) | role (1)
+///     | pose x, y, angle (3 × i16) [| ball x, y (2 × i16) | ball age (u16)]
+///     [| time to reach kick position (u16)] [| kick target x, y (2 × i16)]
+///     [| walk target x, y (2 × i16)]
+/// ```
+///
+/// All multi-byte fields are little endian. Values exceeding the range of their field saturate.
+impl From<HulkMessage> for Vec<u8> {
+    fn from(message: HulkMessage) -> Self {
+        let mut flags = 0;
+        if message.fallen {
+            flags |= FALLEN_FLAG;
+        }
+        if message.ball_position.is_some() {
+            flags |= BALL_POSITION_FLAG;
+        }
+        if message.time_to_reach_kick_position.is_some() {
+            flags |= TIME_TO_REACH_KICK_POSITION_FLAG;
+        }
+        if message.intention.kick_target.is_some() {
+            flags |= KICK_TARGET_FLAG;
+        }
+        if message.intention.walk_target.is_some() {
+            flags |= WALK_TARGET_FLAG;
+        }
+
+        let mut buffer = Vec::with_capacity(MAXIMUM_HULK_MESSAGE_SIZE);
+        buffer.extend_from_slice(&HULK_MESSAGE_HEADER);
+        buffer.push(HULK_MESSAGE_VERSION);
+        buffer.push(flags);
+        buffer.push(encode_player_number(message.player_number));
+        buffer.push(encode_role(message.role));
+        write_position(
+            &mut buffer,
+            message.robot_to_field.translation.vector.into(),
+        );
+        buffer.extend_from_slice(
+            &quantize(message.robot_to_field.rotation.angle(), ANGLE_SCALE).to_le_bytes(),
+        );
+        if let Some(ball_position) = message.ball_position {
+            write_position(&mut buffer, ball_position.relative_position);
+            buffer.extend_from_slice(
+                &quantize_duration(ball_position.age, BALL_AGE_RESOLUTION).to_le_bytes(),
+            );
+        }
+        if let Some(time_to_reach_kick_position) = message.time_to_reach_kick_position {
+            buffer.extend_from_slice(
+                &quantize_duration(
+                    time_to_reach_kick_position,
+                    TIME_TO_REACH_KICK_POSITION_RESOLUTION,
+                )
+                .to_le_bytes(),
+            );
+        }
+        if let Some(kick_target) = message.intention.kick_target {
+            write_position(&mut buffer, kick_target);
+        }
+        if let Some(walk_target) = message.intention.walk_target {
+            write_position(&mut buffer, walk_target);
+        }
+        buffer
+    }
+}
+
+impl TryFrom<&[u8]> for HulkMessage {
+    type Error = Report;
+
+    fn try_from(buffer: &[u8]) -> Result<Self> {
+        let mut reader = Reader { buffer };
+        if reader.take::<2>()? != HULK_MESSAGE_HEADER {
+            bail!("unexpected header");
+        }
+        let version = reader.take::<1>()?[0];
+        if version != HULK_MESSAGE_VERSION {
+            bail!("unexpected version {version}, expected {HULK_MESSAGE_VERSION}");
+        }
+        let flags = reader.take::<1>()?[0];
+        if flags & !KNOWN_FLAGS != 0 {
+            bail!("unexpected flags {flags:#010b}");
+        }
+        let player_number = decode_player_number(reader.take::<1>()?[0])?;
+        let role = decode_role(reader.take::<1>()?[0])?;
+        let position = reader.read_position()?;
+        let angle = reader.read_i16()?;
+        let robot_to_field = Isometry2::new(position.coords, dequantize(angle, ANGLE_SCALE));
+        let ball_position = if flags & BALL_POSITION_FLAG != 0 {
+            let relative_position = reader.read_position()?;
+            let age = reader.read_u16()?;
+            Some(BallPosition {
+                relative_position,
+                age: BALL_AGE_RESOLUTION * age.into(),
+            })
+        } else {
+            None
+        };
+        let time_to_reach_kick_position = if flags & TIME_TO_REACH_KICK_POSITION_FLAG != 0 {
+            Some(TIME_TO_REACH_KICK_POSITION_RESOLUTION * reader.read_u16()?.into())
+        } else {
+            None
+        };
+        let kick_target = if flags & KICK_TARGET_FLAG != 0 {
+            Some(reader.read_position()?)
+        } else {
+            None
+        };
+        let walk_target = if flags & WALK_TARGET_FLAG != 0 {
+            Some(reader.read_position()?)
+        } else {
+            None
+        };
+        if !reader.buffer.is_empty() {
+            bail!("{} unexpected trailing bytes", reader.buffer.len());
+        }
+        Ok(Self {
+            player_number,
+            fallen: flags & FALLEN_FLAG != 0,
+            robot_to_field,
+            ball_position,
+            time_to_reach_kick_position,
+            role,
+            intention: Intention {
+                kick_target,
+                walk_target,
+            },
+        })
+    }
+}
+
+struct Reader<'buffer> {
+    buffer: &'buffer [u8],
+}
+
+impl Reader<'_> {
+    fn take<const SIZE: usize>(&mut self) -> Result<[u8; SIZE]> {
+        if self.buffer.len() < SIZE {
+            bail!("buffer too small");
+        }
+        let (bytes, rest) = self.buffer.split_at(SIZE);
+        self.buffer = rest;
+        Ok(bytes.try_into()?)
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        Ok(i16::from_le_bytes(self.take()?))
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    fn read_position(&mut self) -> Result<Point2<f32>> {
+        let x = self.read_i16()?;
+        let y = self.read_i16()?;
+        Ok(point![
+            dequantize(x, POSITION_SCALE),
+            dequantize(y, POSITION_SCALE)
+        ])
+    }
+}
+
+fn write_position(buffer: &mut Vec<u8>, position: Point2<f32>) {
+    buffer.extend_from_slice(&quantize(position.x, POSITION_SCALE).to_le_bytes());
+    buffer.extend_from_slice(&quantize(position.y, POSITION_SCALE).to_le_bytes());
+}
+
+fn quantize(value: f32, scale: f32) -> i16 {
+    (value * scale).round() as i16
+}
+
+fn dequantize(value: i16, scale: f32) -> f32 {
+    value as f32 / scale
+}
+
+fn quantize_duration(duration: Duration, resolution: Duration) -> u16 {
+    (duration.as_nanos() / resolution.as_nanos())
+        .try_into()
+        .unwrap_or(u16::MAX)
+}
+
+fn encode_player_number(player_number: PlayerNumber) -> u8 {
+    match player_number {
+        PlayerNumber::One => 1,
+        PlayerNumber::Two => 2,
+        PlayerNumber::Three => 3,
+        PlayerNumber::Four => 4,
+        PlayerNumber::Five => 5,
+        PlayerNumber::Six => 6,
+        PlayerNumber::Seven => 7,
+    }
+}
+
+fn encode_role(role: Role) -> u8 {
+    match role {
+        Role::DefenderLeft => 0,
+        Role::DefenderRight => 1,
+        Role::Keeper => 2,
+        Role::Loser => 3,
+        Role::MidfielderLeft => 4,
+        Role::MidfielderRight => 5,
+        Role::ReplacementKeeper => 6,
+        Role::Searcher => 7,
+        Role::Striker => 8,
+        Role::StrikerSupporter => 9,
+    }
+}
+
+fn decode_role(role: u8) -> Result<Role> {
+    Ok(match role {
+        0 => Role::DefenderLeft,
+        1 => Role::DefenderRight,
+        2 => Role::Keeper,
+        3 => Role::Loser,
+        4 => Role::MidfielderLeft,
+        5 => Role::MidfielderRight,
+        6 => Role::ReplacementKeeper,
+        7 => Role::Searcher,
+        8 => Role::Striker,
+        9 => Role::StrikerSupporter,
+        _ => bail!("unexpected role {role}"),
+    })
+}
+
+fn decode_player_number(player_number: u8) -> Result<PlayerNumber> {
+    Ok(match player_number {
+        1 => PlayerNumber::One,
+        2 => PlayerNumber::Two,
+        3 => PlayerNumber::Three,
+        4 => PlayerNumber::Four,
+        5 => PlayerNumber::Five,
+        6 => PlayerNumber::Six,
+        7 => PlayerNumber::Seven,
+        _ => bail!("unexpected player number {player_number}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::vector;
+    use proptest::{option, prelude::*};
+
+    use super::*;
+
+    fn player_number() -> impl Strategy<Value = PlayerNumber> {
+        prop_oneof![
+            Just(PlayerNumber::One),
+            Just(PlayerNumber::Two),
+            Just(PlayerNumber::Three),
+            Just(PlayerNumber::Four),
+            Just(PlayerNumber::Five),
+            Just(PlayerNumber::Six),
+            Just(PlayerNumber::Seven),
+        ]
+    }
+
+    fn role() -> impl Strategy<Value = Role> {
+        (0u8..10).prop_map(|role| decode_role(role).unwrap())
+    }
+
+    fn field_position() -> impl Strategy<Value = Point2<f32>> {
+        (-6.0f32..6.0, -4.5f32..4.5).prop_map(|(x, y)| point![x, y])
+    }
+
+    fn hulk_message() -> impl Strategy<Value = HulkMessage> {
+        (
+            (player_number(), any::<bool>(), role()),
+            (-6.0f32..6.0, -4.5f32..4.5, -PI..PI),
+            option::of((-9.0f32..9.0, -9.0f32..9.0, 0u64..60_000)),
+            option::of(0u64..600_000),
+            (option::of(field_position()), option::of(field_position())),
+        )
+            .prop_map(
+                |(
+                    (player_number, fallen, role),
+                    (x, y, angle),
+                    ball,
+                    time_to_reach_kick_position,
+                    (kick_target, walk_target),
+                )| HulkMessage {
+                    player_number,
+                    fallen,
+                    robot_to_field: Isometry2::new(vector![x, y], angle),
+                    ball_position: ball.map(|(x, y, age)| BallPosition {
+                        relative_position: point![x, y],
+                        age: Duration::from_millis(age),
+                    }),
+                    time_to_reach_kick_position: time_to_reach_kick_position
+                        .map(Duration::from_millis),
+                    role,
+                    intention: Intention {
+                        kick_target,
+                        walk_target,
+                    },
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn round_trip_is_exact_up_to_quantization(message in hulk_message()) {
+            let buffer: Vec<u8> = message.into();
+            prop_assert!(buffer.len() <= MAXIMUM_HULK_MESSAGE_SIZE);
+            let decoded = HulkMessage::try_from(buffer.as_slice()).unwrap();
+
+            prop_assert_eq!(decoded.player_number, message.player_number);
+            prop_assert_eq!(decoded.fallen, message.fallen);
+            prop_assert_eq!(decoded.role, message.role);
+            assert_relative_eq!(
+                decoded.robot_to_field.translation.vector,
+                message.robot_to_field.translation.vector,
+                epsilon = 0.0005
+            );
+            assert_relative_eq!(
+                decoded.robot_to_field.rotation.angle(),
+                message.robot_to_field.rotation.angle(),
+                epsilon = 0.0001
+            );
+            prop_assert_eq!(decoded.ball_position.is_some(), message.ball_position.is_some());
+            if let (Some(decoded), Some(original)) = (decoded.ball_position, message.ball_position) {
+                assert_relative_eq!(
+                    decoded.relative_position,
+                    original.relative_position,
+                    epsilon = 0.0005
+                );
+                prop_assert_eq!(decoded.age, original.age);
+            }
+            prop_assert_eq!(
+                decoded.time_to_reach_kick_position.map(|time| time.as_millis() / 10),
+                message.time_to_reach_kick_position.map(|time| time.as_millis() / 10)
+            );
+            for (decoded, original) in [
+                (decoded.intention.kick_target, message.intention.kick_target),
+                (decoded.intention.walk_target, message.intention.walk_target),
+            ] {
+                prop_assert_eq!(decoded.is_some(), original.is_some());
+                if let (Some(decoded), Some(original)) = (decoded, original) {
+                    assert_relative_eq!(decoded, original, epsilon = 0.0005);
+                }
+            }
+        }
+
+        #[test]
+        fn truncated_messages_are_rejected(message in hulk_message(), cut in 1usize..8) {
+            let buffer: Vec<u8> = message.into();
+            let truncated = &buffer[..buffer.len().saturating_sub(cut)];
+            prop_assert!(HulkMessage::try_from(truncated).is_err());
+        }
+    }
+
+    #[test]
+    fn maximum_hulk_message_size() {
+        let message = HulkMessage {
+            player_number: PlayerNumber::Seven,
+            fallen: true,
+            robot_to_field: Isometry2::identity(),
+            ball_position: Some(BallPosition {
+                relative_position: Point2::origin(),
+                age: Duration::MAX,
+            }),
+            time_to_reach_kick_position: Some(Duration::MAX),
+            role: Role::StrikerSupporter,
+            intention: Intention {
+                kick_target: Some(Point2::origin()),
+                walk_target: Some(Point2::origin()),
+            },
+        };
+        let buffer: Vec<u8> = message.into();
+        assert!(buffer.len() <= MAXIMUM_HULK_MESSAGE_SIZE);
+
+        let decoded = HulkMessage::try_from(buffer.as_slice()).unwrap();
+        assert_eq!(
+            decoded.ball_position.unwrap().age,
+            Duration::from_millis(65535)
+        );
+        assert_eq!(
+            decoded.time_to_reach_kick_position,
+            Some(Duration::from_millis(655350))
+        );
+    }
+
+    #[test]
+    fn foreign_and_old_messages_are_rejected() {
+        let buffer: Vec<u8> = HulkMessage::default().into();
+
+        let mut foreign = buffer.clone();
+        foreign[0] = b'X';
+        assert!(HulkMessage::try_from(foreign.as_slice()).is_err());
+
+        let mut old = buffer.clone();
+        old[2] = HULK_MESSAGE_VERSION - 1;
+        assert!(HulkMessage::try_from(old.as_slice()).is_err());
+
+        let mut unknown_flags = buffer.clone();
+        unknown_flags[3] |= 1 << 7;
+        assert!(HulkMessage::try_from(unknown_flags.as_slice()).is_err());
+
+        let mut unknown_role = buffer.clone();
+        unknown_role[5] = 10;
+        assert!(HulkMessage::try_from(unknown_role.as_slice()).is_err());
+
+        let mut trailing = buffer;
+        trailing.push(0);
+        assert!(HulkMessage::try_from(trailing.as_slice()).is_err());
+    }
+}
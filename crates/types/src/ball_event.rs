@@ -0,0 +1,37 @@
+use std::time::SystemTime;
+
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::SubState;
+
+/// Latest change of the ball that the ball filter cannot follow by tracking
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub enum BallEvent {
+    /// The ball fully crossed a side or goal line
+    LeftField {
+        position_in_field: Point2<f32>,
+        time: SystemTime,
+    },
+    /// The ball vanished while it should have been visible, e.g. it was picked up by a referee
+    Removed {
+        last_position_in_field: Point2<f32>,
+        time: SystemTime,
+    },
+    /// The ball is expected to be put back in play at the position defined by the set play
+    PlacedBack {
+        position_in_field: Point2<f32>,
+        sub_state: SubState,
+        time: SystemTime,
+    },
+}
+
+impl BallEvent {
+    pub fn time(&self) -> SystemTime {
+        match self {
+            BallEvent::LeftField { time, .. }
+            | BallEvent::Removed { time, .. }
+            | BallEvent::PlacedBack { time, .. } => *time,
+        }
+    }
+}
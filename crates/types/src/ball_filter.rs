@@ -9,6 +9,9 @@ use crate::{
     parameters::BallFilterParameters,
 };
 
+/// Time step of the ball filter prediction, the velocity decays once per step
+pub const PREDICTION_CYCLE_TIME: f32 = 0.012;
+
 #[derive(Clone, Debug, Serialize, Deserialize, SerializeHierarchy)]
 pub struct Hypothesis {
     pub moving_state: MultivariateNormalDistribution<4>,
@@ -0,0 +1,267 @@
+use std::{ops::Mul, time::Duration};
+
+use nalgebra::{distance, Isometry2, Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::{
+    ball_filter::PREDICTION_CYCLE_TIME, ball_position::BallPosition, line::Line,
+    parameters::BallFilterParameters,
+};
+
+/// Straight ball motion on the ground whose velocity decays exponentially as predicted by the
+/// ball filter
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallTrajectory {
+    pub position: Point2<f32>,
+    pub velocity: Vector2<f32>,
+    /// Rate in 1/s at which the velocity decays, i.e. it shrinks by `exp(-velocity_decay_rate * t)`
+    pub velocity_decay_rate: f32,
+    /// Speed in m/s below which the ball is considered to be at rest
+    pub resting_speed: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallInterception {
+    pub position: Point2<f32>,
+    pub time_to_interception: Duration,
+}
+
+impl BallTrajectory {
+    /// Continues the ball filter prediction which multiplies the velocity with the velocity decay
+    /// factor every prediction cycle
+    pub fn from_ball_filter(
+        ball_position: BallPosition,
+        configuration: &BallFilterParameters,
+    ) -> Self {
+        Self {
+            position: ball_position.position,
+            velocity: ball_position.velocity,
+            velocity_decay_rate: -configuration.velocity_decay_factor.ln() / PREDICTION_CYCLE_TIME,
+            resting_speed: configuration.resting_ball_velocity_threshold,
+        }
+    }
+
+    pub fn time_to_rest(&self) -> Duration {
+        duration_from_seconds(self.seconds_to_rest())
+    }
+
+    pub fn rest_position(&self) -> Point2<f32> {
+        self.position_after(self.seconds_to_rest())
+    }
+
+    pub fn position_at(&self, time: Duration) -> Point2<f32> {
+        self.position_after(time.as_secs_f32().min(self.seconds_to_rest()))
+    }
+
+    /// Point of the trajectory closest to the given point and when the ball passes it
+    ///
+    /// If the ball comes to rest before passing the given point, the rest position is returned.
+    pub fn interception(&self, point: Point2<f32>) -> BallInterception {
+        let speed = self.velocity.norm();
+        if speed <= self.resting_speed {
+            return BallInterception {
+                position: self.position,
+                time_to_interception: Duration::ZERO,
+            };
+        }
+        let position = Line(self.position, self.rest_position()).project_onto_segment(point);
+        let distance_to_interception = distance(&self.position, &position);
+        // solves distance = speed / rate * (1 - exp(-rate * t)), the projection onto the rolling
+        // path keeps the logarithm finite
+        let decay_rate = self.decay_rate();
+        let seconds = (-(1.0 - decay_rate * distance_to_interception / speed).ln() / decay_rate)
+            .min(self.seconds_to_rest());
+        BallInterception {
+            position,
+            time_to_interception: duration_from_seconds(seconds),
+        }
+    }
+
+    fn direction(&self) -> Vector2<f32> {
+        self.velocity
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector2::zeros)
+    }
+
+    /// Decay rate which is guaranteed to be positive, otherwise the ball would never come to rest
+    fn decay_rate(&self) -> f32 {
+        if self.velocity_decay_rate > 0.0 {
+            self.velocity_decay_rate
+        } else {
+            f32::EPSILON
+        }
+    }
+
+    fn seconds_to_rest(&self) -> f32 {
+        let speed = self.velocity.norm();
+        if speed <= self.resting_speed {
+            return 0.0;
+        }
+        (speed / self.resting_speed.max(f32::EPSILON)).ln() / self.decay_rate()
+    }
+
+    fn position_after(&self, seconds: f32) -> Point2<f32> {
+        let decay_rate = self.decay_rate();
+        let travelled_distance =
+            self.velocity.norm() * (1.0 - (-decay_rate * seconds).exp()) / decay_rate;
+        self.position + self.direction() * travelled_distance
+    }
+}
+
+/// Like [`Duration::from_secs_f32`] but saturating instead of panicking on invalid seconds
+fn duration_from_seconds(seconds: f32) -> Duration {
+    Duration::try_from_secs_f32(seconds.max(0.0)).unwrap_or(Duration::MAX)
+}
+
+impl Mul<BallTrajectory> for Isometry2<f32> {
+    type Output = BallTrajectory;
+
+    fn mul(self, right: BallTrajectory) -> Self::Output {
+        BallTrajectory {
+            position: self * right.position,
+            velocity: self * right.velocity,
+            ..right
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{f32::consts::LN_2, time::SystemTime};
+
+    use approx::assert_relative_eq;
+    use nalgebra::{point, vector};
+
+    use super::*;
+
+    #[test]
+    fn rolling_ball_comes_to_rest() {
+        let trajectory = BallTrajectory {
+            position: point![1.0, 0.0],
+            velocity: vector![-2.0, 0.0],
+            velocity_decay_rate: LN_2,
+            resting_speed: 0.25,
+        };
+
+        // the speed halves every second
+        assert_relative_eq!(
+            trajectory.time_to_rest().as_secs_f32(),
+            3.0,
+            epsilon = 0.001
+        );
+        assert_relative_eq!(
+            trajectory.rest_position(),
+            point![1.0 - 1.75 / LN_2, 0.0],
+            epsilon = 0.001
+        );
+        assert_relative_eq!(
+            trajectory.position_at(Duration::from_secs(1)),
+            point![1.0 - 1.0 / LN_2, 0.0],
+            epsilon = 0.001
+        );
+        assert_relative_eq!(
+            trajectory.position_at(Duration::from_secs(5)),
+            trajectory.rest_position()
+        );
+    }
+
+    #[test]
+    fn interception_is_closest_point_on_rolling_path() {
+        let trajectory = BallTrajectory {
+            position: point![2.0, 0.0],
+            velocity: vector![-2.0, 0.0],
+            velocity_decay_rate: LN_2,
+            resting_speed: 0.25,
+        };
+
+        let interception = trajectory.interception(point![0.0, 0.3]);
+
+        assert_relative_eq!(interception.position, point![0.0, 0.0]);
+        // 2 m = 2 m/s / ln(2) * (1 - exp(-ln(2) * t))
+        assert_relative_eq!(
+            interception.time_to_interception.as_secs_f32(),
+            -(1.0 - LN_2).ln() / LN_2,
+            epsilon = 0.001
+        );
+
+        let resting_before = BallTrajectory {
+            velocity_decay_rate: 2.0 * LN_2,
+            ..trajectory
+        }
+        .interception(point![0.0, 0.0]);
+
+        assert_relative_eq!(
+            resting_before.position,
+            point![2.0 - 1.75 / (2.0 * LN_2), 0.0],
+            epsilon = 0.001
+        );
+        assert_relative_eq!(
+            resting_before.time_to_interception.as_secs_f32(),
+            1.5,
+            epsilon = 0.001
+        );
+    }
+
+    #[test]
+    fn resting_ball_stays_in_place() {
+        let trajectory = BallTrajectory {
+            position: point![1.0, 2.0],
+            velocity: vector![0.1, 0.0],
+            velocity_decay_rate: 0.5,
+            resting_speed: 0.25,
+        };
+
+        assert_eq!(trajectory.time_to_rest(), Duration::ZERO);
+        assert_relative_eq!(trajectory.rest_position(), point![1.0, 2.0]);
+        assert_relative_eq!(
+            trajectory.interception(point![0.0, 0.0]).position,
+            point![1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn trajectory_follows_the_ball_filter_velocity_decay() {
+        let configuration = BallFilterParameters {
+            velocity_decay_factor: 0.99,
+            resting_ball_velocity_threshold: 0.25,
+            ..Default::default()
+        };
+        let trajectory = BallTrajectory::from_ball_filter(
+            BallPosition {
+                position: point![0.0, 0.0],
+                velocity: vector![2.0, 0.0],
+                last_seen: SystemTime::UNIX_EPOCH,
+            },
+            &configuration,
+        );
+
+        let mut speed = 2.0;
+        let mut number_of_cycles = 0;
+        while speed >= configuration.resting_ball_velocity_threshold {
+            speed *= configuration.velocity_decay_factor;
+            number_of_cycles += 1;
+        }
+
+        assert_relative_eq!(
+            trajectory.time_to_rest().as_secs_f32(),
+            number_of_cycles as f32 * PREDICTION_CYCLE_TIME,
+            epsilon = PREDICTION_CYCLE_TIME
+        );
+    }
+
+    #[test]
+    fn invalid_decay_rate_does_not_panic() {
+        for velocity_decay_rate in [0.0, -1.0, f32::NAN] {
+            let trajectory = BallTrajectory {
+                position: point![0.0, 0.0],
+                velocity: vector![1.0, 0.0],
+                velocity_decay_rate,
+                resting_speed: 0.25,
+            };
+
+            assert!(trajectory.time_to_rest() > Duration::ZERO);
+            assert!(trajectory.interception(point![1.0, 0.0]).position.x > 0.0);
+        }
+    }
+}
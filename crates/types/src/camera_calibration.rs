@@ -0,0 +1,126 @@
+use std::time::SystemTime;
+
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::joints::head::HeadJoints;
+
+/// Head position requested by the camera calibration while collecting measurements
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CalibrationCommand {
+    pub head_position: HeadJoints<f32>,
+}
+
+/// Loss applied to each residual to down-weight measurements with wrong line associations
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub enum RobustLoss {
+    #[default]
+    Squared,
+    Huber {
+        threshold: f32,
+    },
+    Cauchy {
+        scale: f32,
+    },
+}
+
+impl RobustLoss {
+    /// Maps a residual to one whose square is the robust loss of the original residual
+    pub fn apply(self, residual: f32) -> f32 {
+        let squared_residual = residual.powi(2);
+        let loss = match self {
+            RobustLoss::Squared => return residual,
+            RobustLoss::Huber { threshold } if residual.abs() <= threshold => return residual,
+            RobustLoss::Huber { threshold } => 2.0 * threshold * residual.abs() - threshold.powi(2),
+            RobustLoss::Cauchy { scale } => {
+                scale.powi(2) * (squared_residual / scale.powi(2)).ln_1p()
+            }
+        };
+        loss.sqrt().copysign(residual)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize, SerializeHierarchy)]
+pub enum RejectionReason {
+    NotConverged,
+    TooFewMeasurements,
+    ResidualTooLarge,
+    UncertaintyTooLarge,
+    TooManyOutliers,
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize, SerializeHierarchy,
+)]
+pub enum CalibrationVerdict {
+    #[default]
+    Accepted,
+    Rejected {
+        reason: RejectionReason,
+    },
+}
+
+impl CalibrationVerdict {
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, CalibrationVerdict::Accepted)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub enum CalibrationPhase {
+    #[default]
+    Inactive,
+    MovingHead {
+        head_position_index: usize,
+    },
+    Measuring {
+        head_position_index: usize,
+        started_at: SystemTime,
+    },
+    Solving,
+    Finished,
+}
+
+/// Outcome of the camera calibration
+///
+/// The rotations already contain the solved corrections and are meant to be stored as
+/// head-specific `camera_matrix_parameters`. All rotations are euler angles in degrees.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CalibrationResult {
+    pub verdict: CalibrationVerdict,
+    pub number_of_measurements: usize,
+    pub number_of_outliers: usize,
+    pub number_of_evaluations: usize,
+    pub is_converged: bool,
+    pub initial_root_mean_square_residual: Option<f32>,
+    pub root_mean_square_residual: Option<f32>,
+    /// Root mean square of the residuals of each measurement, `None` if its lines do not project
+    pub measurement_residuals: Vec<Option<f32>>,
+    /// Standard deviations of the robot, top and bottom camera correction angles in degrees
+    #[serialize_hierarchy(leaf)]
+    pub standard_deviations: Option<[Vector3<f32>; 3]>,
+    pub robot_rotation_correction: Vector3<f32>,
+    pub top_extrinsic_rotations: Vector3<f32>,
+    pub bottom_extrinsic_rotations: Vector3<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn robust_losses_keep_small_residuals_and_reduce_large_ones() {
+        let huber = RobustLoss::Huber { threshold: 0.1 };
+        let cauchy = RobustLoss::Cauchy { scale: 0.1 };
+
+        assert_relative_eq!(RobustLoss::Squared.apply(-2.0), -2.0);
+        assert_relative_eq!(huber.apply(0.05), 0.05);
+        assert_relative_eq!(huber.apply(-2.0), -(0.4_f32 - 0.01).sqrt());
+        assert_relative_eq!(cauchy.apply(0.001), 0.001, epsilon = 0.0001);
+        assert!(cauchy.apply(2.0) < huber.apply(2.0));
+        assert!(cauchy.apply(-2.0) < 0.0);
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     field_dimensions::FieldDimensions,
     line::{Line, Line2},
+    line_data::{intersections_of_lines, IntersectionKind, LineIntersection},
 };
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize)]
@@ -412,3 +413,84 @@ pub fn field_marks_from_field_dimensions(field_dimensions: &FieldDimensions) ->
         },
     ]
 }
+
+pub fn penalty_mark_positions_from_field_dimensions(
+    field_dimensions: &FieldDimensions,
+) -> Vec<Point2<f32>> {
+    vec![
+        point![
+            -field_dimensions.length / 2.0 + field_dimensions.penalty_marker_distance,
+            0.0
+        ],
+        point![
+            field_dimensions.length / 2.0 - field_dimensions.penalty_marker_distance,
+            0.0
+        ],
+    ]
+}
+
+pub fn goal_post_positions_from_field_dimensions(
+    field_dimensions: &FieldDimensions,
+) -> Vec<Point2<f32>> {
+    let post_x = field_dimensions.length / 2.0 + field_dimensions.goal_post_diameter / 2.0
+        - field_dimensions.line_width / 2.0;
+    let post_y = (field_dimensions.goal_inner_width + field_dimensions.goal_post_diameter) / 2.0;
+    vec![
+        point![-post_x, -post_y],
+        point![-post_x, post_y],
+        point![post_x, -post_y],
+        point![post_x, post_y],
+    ]
+}
+
+/// Corners and junctions of the straight field lines and the crossings of the center circle
+pub fn line_intersections_from_field_dimensions(
+    field_dimensions: &FieldDimensions,
+) -> Vec<LineIntersection> {
+    let lines: Vec<_> = field_marks_from_field_dimensions(field_dimensions)
+        .into_iter()
+        .filter_map(|field_mark| match field_mark {
+            FieldMark::Line { line, direction: _ } => Some(line),
+            FieldMark::Circle { .. } => None,
+        })
+        .collect();
+    let mut intersections = intersections_of_lines(&lines, 0.01, field_dimensions.line_width / 2.0);
+    // the line detection fits a segment tangent to the center circle where it crosses the center
+    // line, which is orthogonal to the center line
+    let center_circle_radius = field_dimensions.center_circle_diameter / 2.0;
+    intersections.extend(
+        [-center_circle_radius, center_circle_radius].map(|y| LineIntersection {
+            position: point![0.0, y],
+            kind: IntersectionKind::X,
+        }),
+    );
+    intersections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn center_circle_crosses_the_center_line() {
+        let field_dimensions = FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            center_circle_diameter: 1.5,
+            goal_box_area_length: 0.6,
+            goal_box_area_width: 2.2,
+            penalty_area_length: 1.65,
+            penalty_area_width: 4.0,
+            line_width: 0.05,
+            ..Default::default()
+        };
+
+        let crossings: Vec<_> = line_intersections_from_field_dimensions(&field_dimensions)
+            .into_iter()
+            .filter(|intersection| intersection.kind == IntersectionKind::X)
+            .map(|intersection| intersection.position)
+            .collect();
+
+        assert_eq!(crossings, vec![point![0.0, -0.75], point![0.0, 0.75]]);
+    }
+}
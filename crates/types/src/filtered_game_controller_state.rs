@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
-use spl_network_messages::{GamePhase, Penalty, SubState, Team};
+use spl_network_messages::{GamePhase, Half, Penalty, SubState, Team};
 
 use crate::{filtered_game_state::FilteredGameState, players::Players};
 
@@ -10,6 +12,8 @@ pub struct FilteredGameControllerState {
     pub opponent_game_state: FilteredGameState,
     pub game_phase: GamePhase,
     pub kicking_team: Team,
+    pub half: Half,
+    pub remaining_time_in_half: Duration,
     pub penalties: Players<Option<Penalty>>,
     pub remaining_number_of_messages: u16,
     pub sub_state: Option<SubState>,
@@ -1,8 +1,8 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
-use spl_network_messages::{GamePhase, GameState, Penalty, SubState, Team};
+use spl_network_messages::{GamePhase, GameState, Half, Penalty, SubState, Team};
 
 use crate::players::Players;
 
@@ -12,6 +12,8 @@ pub struct GameControllerState {
     pub game_phase: GamePhase,
     pub kicking_team: Team,
     pub last_game_state_change: SystemTime,
+    pub half: Half,
+    pub remaining_time_in_half: Duration,
     pub penalties: Players<Option<Penalty>>,
     pub remaining_amount_of_messages: u16,
     pub sub_state: Option<SubState>,
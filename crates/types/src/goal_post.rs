@@ -0,0 +1,10 @@
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct GoalPost {
+    /// Center of the post where it touches the ground
+    pub position: Point2<f32>,
+    pub number_of_scan_lines: usize,
+}
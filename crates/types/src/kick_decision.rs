@@ -1,4 +1,4 @@
-use nalgebra::Isometry2;
+use nalgebra::{Isometry2, Point2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -10,4 +10,5 @@ pub struct KickDecision {
     pub kicking_side: Side,
     pub kick_pose: Isometry2<f32>,
     pub strength: f32,
+    pub target: Point2<f32>,
 }
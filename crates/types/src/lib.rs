@@ -2,9 +2,12 @@
 pub mod action;
 pub mod audio;
 pub mod ball;
+pub mod ball_event;
 pub mod ball_filter;
 pub mod ball_position;
+pub mod ball_trajectory;
 pub mod buttons;
+pub mod camera_calibration;
 pub mod camera_matrix;
 pub mod camera_position;
 pub mod color;
@@ -22,6 +25,7 @@ pub mod filtered_game_state;
 pub mod filtered_segments;
 pub mod filtered_whistle;
 pub mod game_controller_state;
+pub mod goal_post;
 pub mod grayscale_image;
 pub mod hardware;
 pub mod horizon;
@@ -39,6 +43,7 @@ pub mod limb;
 pub mod line;
 pub mod line_data;
 pub mod localization;
+pub mod message_budget;
 pub mod message_event;
 pub mod messages;
 pub mod motion_command;
@@ -50,6 +55,7 @@ pub mod obstacles;
 pub mod orientation_filter;
 pub mod parameters;
 pub mod path_obstacles;
+pub mod penalty_mark;
 pub mod penalty_shot_direction;
 pub mod perspective_grid_candidates;
 pub mod planned_path;
@@ -69,6 +75,8 @@ pub mod sonar_values;
 pub mod step_adjustment;
 pub mod step_plan;
 pub mod support_foot;
+pub mod team_ball;
+pub mod teammate_intention;
 pub mod walk_command;
 pub mod whistle;
 pub mod world_state;
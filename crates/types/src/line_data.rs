@@ -9,6 +9,7 @@ use crate::line::Line2;
 #[derive(Clone, Default, Debug, Serialize, Deserialize, SerializeHierarchy)]
 pub struct LineData {
     pub lines_in_robot: Vec<Line2>,
+    pub intersections_in_robot: Vec<LineIntersection>,
     pub used_vertical_filtered_segments: HashSet<Point2<u16>>,
 }
 
@@ -25,4 +26,168 @@ pub struct ImageLines {
     pub discarded_lines: Vec<(Line2, LineDiscardReason)>,
     pub lines: Vec<Line2>,
     pub points: Vec<Point2<f32>>,
+    pub intersections: Vec<LineIntersection>,
+}
+
+/// Shape of the junction two orthogonal lines form
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, SerializeHierarchy)]
+pub enum IntersectionKind {
+    /// Both lines end at the intersection, e.g. a field corner
+    L,
+    /// One line ends on the other one, e.g. where the center line meets a side line
+    T,
+    /// Both lines cross each other, e.g. where the center circle crosses the center line
+    X,
+}
+
+impl IntersectionKind {
+    pub const ALL: [Self; 3] = [Self::L, Self::T, Self::X];
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct LineIntersection {
+    pub position: Point2<f32>,
+    pub kind: IntersectionKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IntersectionOnLine {
+    End,
+    Inner,
+}
+
+impl LineIntersection {
+    /// Intersects two line segments and classifies the junction
+    ///
+    /// Returns `None` if the lines are not orthogonal within `maximum_angle_deviation` or if the
+    /// intersection is further than `maximum_end_distance` beyond the end of one of the segments.
+    /// Intersections within `maximum_end_distance` of a segment end count as the end of that line.
+    pub fn from_lines(
+        first: Line2,
+        second: Line2,
+        maximum_angle_deviation: f32,
+        maximum_end_distance: f32,
+    ) -> Option<Self> {
+        if first.length() <= 0.0
+            || second.length() <= 0.0
+            || first.signed_acute_angle_to_orthogonal(second).abs() > maximum_angle_deviation
+        {
+            return None;
+        }
+        let position = first.intersection(&second);
+        let kind = match (
+            locate_on_line(first, position, maximum_end_distance)?,
+            locate_on_line(second, position, maximum_end_distance)?,
+        ) {
+            (IntersectionOnLine::End, IntersectionOnLine::End) => IntersectionKind::L,
+            (IntersectionOnLine::Inner, IntersectionOnLine::Inner) => IntersectionKind::X,
+            _ => IntersectionKind::T,
+        };
+        Some(Self { position, kind })
+    }
+}
+
+fn locate_on_line(
+    line: Line2,
+    position: Point2<f32>,
+    maximum_end_distance: f32,
+) -> Option<IntersectionOnLine> {
+    let length = line.length();
+    let distance_along_line = (position - line.0).dot(&(line.1 - line.0)) / length;
+    if distance_along_line < -maximum_end_distance
+        || distance_along_line > length + maximum_end_distance
+    {
+        None
+    } else if distance_along_line <= maximum_end_distance
+        || distance_along_line >= length - maximum_end_distance
+    {
+        Some(IntersectionOnLine::End)
+    } else {
+        Some(IntersectionOnLine::Inner)
+    }
+}
+
+/// Classifies all intersections between pairs of the given lines
+pub fn intersections_of_lines(
+    lines: &[Line2],
+    maximum_angle_deviation: f32,
+    maximum_end_distance: f32,
+) -> Vec<LineIntersection> {
+    lines
+        .iter()
+        .enumerate()
+        .flat_map(|(index, &first)| {
+            lines[index + 1..].iter().filter_map(move |&second| {
+                LineIntersection::from_lines(
+                    first,
+                    second,
+                    maximum_angle_deviation,
+                    maximum_end_distance,
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use nalgebra::point;
+
+    use crate::line::Line;
+
+    use super::*;
+
+    #[test]
+    fn junctions_are_classified_by_segment_ends() {
+        let horizontal = Line(point![0.0, 0.0], point![2.0, 0.0]);
+
+        let corner = LineIntersection::from_lines(
+            horizontal,
+            Line(point![2.05, 0.1], point![2.05, 1.0]),
+            0.1,
+            0.2,
+        )
+        .unwrap();
+        assert_eq!(corner.kind, IntersectionKind::L);
+        assert_relative_eq!(corner.position, point![2.05, 0.0]);
+
+        let junction = LineIntersection::from_lines(
+            horizontal,
+            Line(point![1.0, 0.1], point![1.0, 1.0]),
+            0.1,
+            0.2,
+        )
+        .unwrap();
+        assert_eq!(junction.kind, IntersectionKind::T);
+
+        let cross = LineIntersection::from_lines(
+            horizontal,
+            Line(point![1.0, -1.0], point![1.0, 1.0]),
+            0.1,
+            0.2,
+        )
+        .unwrap();
+        assert_eq!(cross.kind, IntersectionKind::X);
+    }
+
+    #[test]
+    fn distant_or_skewed_lines_do_not_intersect() {
+        let horizontal = Line(point![0.0, 0.0], point![2.0, 0.0]);
+
+        assert!(LineIntersection::from_lines(
+            horizontal,
+            Line(point![1.0, 0.5], point![1.0, 1.0]),
+            0.1,
+            0.2,
+        )
+        .is_none());
+        assert!(LineIntersection::from_lines(
+            horizontal,
+            Line(point![1.0, -1.0], point![1.5, 1.0]),
+            0.1,
+            0.2,
+        )
+        .is_none());
+    }
 }
@@ -4,6 +4,15 @@ use serialize_hierarchy::SerializeHierarchy;
 
 use crate::multivariate_normal_distribution::MultivariateNormalDistribution;
 
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub enum LocalizationMode {
+    #[default]
+    MultiHypothesis,
+    ParticleFilter,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub struct Update {
     pub robot_to_field: Isometry2<f32>,
@@ -35,3 +44,9 @@ impl ScoredPose {
         }
     }
 }
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct Particle {
+    pub robot_to_field: Isometry2<f32>,
+    pub weight: f32,
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum MessageTrigger {
+    /// The robot became striker after another robot was striker
+    StrikerClaim,
+    /// The robot became striker because it saw the ball while the team had lost it
+    BallSeenAfterLoss,
+    /// The striker lost the ball and gives up the striker role
+    BallLost,
+    /// The striker fell and needs time to get up again
+    Fallen,
+    /// Regular update of the striker paced by the message budget
+    Periodic,
+}
+
+impl MessageTrigger {
+    pub fn is_event(&self) -> bool {
+        !matches!(self, MessageTrigger::Periodic)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct MessageBudgetDecision {
+    pub remaining_messages: u16,
+    pub remaining_game_time: Duration,
+    /// Messages available for periodic updates after subtracting the reserve for events
+    pub messages_for_periodic_updates: u16,
+    pub periodic_interval: Duration,
+    pub trigger: Option<MessageTrigger>,
+    pub send: bool,
+}
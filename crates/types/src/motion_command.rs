@@ -1,4 +1,4 @@
-use nalgebra::{Point2, UnitComplex};
+use nalgebra::{Point2, Point3, UnitComplex};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -77,13 +77,21 @@ pub enum HeadMotion {
     LookLeftAndRightOf {
         target: Point2<f32>,
     },
+    Angles {
+        yaw: f32,
+        pitch: f32,
+    },
     Unstiff,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
 pub enum ArmMotion {
     Swing,
     PullTight,
+    /// Holds the hand at a position in robot coordinates, e.g. close to the body
+    HoldHand {
+        hand_in_robot: Point3<f32>,
+    },
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
@@ -8,7 +8,8 @@ use crate::{
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hypothesis {
-    pub state: MultivariateNormalDistribution<2>,
+    /// Position and velocity `[x, y, vx, vy]` in robot coordinates
+    pub state: MultivariateNormalDistribution<4>,
     pub measurement_count: usize,
     pub last_update: SystemTime,
     pub obstacle_kind: ObstacleKind,
@@ -1,4 +1,4 @@
-use nalgebra::Point2;
+use nalgebra::{Point2, Vector2};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -17,6 +17,8 @@ pub struct Obstacle {
     pub position: Point2<f32>,
     pub radius_at_foot_height: f32,
     pub radius_at_hip_height: f32,
+    /// Estimated velocity in the same coordinate frame as the position
+    pub velocity: Vector2<f32>,
 }
 
 impl Obstacle {
@@ -26,6 +28,7 @@ impl Obstacle {
             position,
             radius_at_foot_height: radius,
             radius_at_hip_height: radius,
+            velocity: Vector2::zeros(),
         }
     }
 
@@ -39,6 +42,7 @@ impl Obstacle {
             position,
             radius_at_foot_height,
             radius_at_hip_height,
+            velocity: Vector2::zeros(),
         }
     }
 
@@ -48,6 +52,7 @@ impl Obstacle {
             position,
             radius_at_foot_height: radius,
             radius_at_hip_height: radius,
+            velocity: Vector2::zeros(),
         }
     }
 }
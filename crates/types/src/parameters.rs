@@ -1,11 +1,12 @@
 use std::ops::{Index, Range};
 use std::{path::PathBuf, time::Duration};
 
-use nalgebra::{Point2, Vector2, Vector3, Vector4};
+use nalgebra::{Point2, Point3, Vector2, Vector3, Vector4};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
 use crate::{
+    camera_calibration::RobustLoss,
     joints::{arm::ArmJoints, head::HeadJoints, leg::LegJoints},
     kick_step::KickStep,
     motion_command::{KickVariant, MotionCommand},
@@ -147,14 +148,16 @@ pub struct InterceptBallParameters {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct PathPlanningParameters {
-    pub arc_walking_speed: f32,
     pub ball_obstacle_radius: f32,
     pub field_border_weight: f32,
-    pub line_walking_speed: f32,
     pub rotation_penalty_factor: f32,
     pub minimum_robot_radius_at_foot_height: f32,
+    pub obstacle_prediction_horizon: f32,
     pub robot_radius_at_foot_height: f32,
     pub robot_radius_at_hip_height: f32,
+    pub arm_obstacle_distance: f32,
+    pub duel_distance: f32,
+    pub obstacle_avoiding_hand_position: Point3<f32>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -225,6 +228,8 @@ pub struct SwingingArmsParameters {
     pub pull_tight_joints: ArmJoints<f32>,
     pub pulling_back_duration: Duration,
     pub pulling_tight_duration: Duration,
+    pub hold_hand_elbow_yaw: f32,
+    pub moving_to_hold_duration: Duration,
     pub torso_tilt_compensation_factor: f32,
 }
 
@@ -255,7 +260,9 @@ pub struct LookAroundParameters {
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct SplNetworkParameters {
+    pub event_message_reserve: u16,
     pub game_controller_return_message_interval: Duration,
+    pub half_duration: Duration,
     pub remaining_amount_of_messages_to_stop_sending: u16,
     pub silence_interval_between_messages: Duration,
     pub spl_striker_message_receive_timeout: Duration,
@@ -308,6 +315,32 @@ pub struct BallFilterParameters {
     pub validity_discard_threshold: f32,
     pub velocity_decay_factor: f32,
     pub resting_ball_velocity_threshold: f32,
+    pub reliable_ball_minimum_validity: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeamBallFilterParameters {
+    pub maximum_age: Duration,
+    pub measurement_noise: f32,
+    pub distance_noise_factor: f32,
+    pub pose_translation_noise: f32,
+    pub pose_rotation_noise: f32,
+    pub age_noise: f32,
+    pub gating_distance: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ParticleFilterParameters {
+    pub number_of_particles: usize,
+    pub initial_pose_standard_deviation: Vector3<f32>,
+    pub odometry_noise: Vector3<f32>,
+    pub measurement_noise: f32,
+    pub resampling_threshold: f32,
+    pub slow_likelihood_average_factor: f32,
+    pub fast_likelihood_average_factor: f32,
+    pub maximum_sensor_resetting_ratio: f32,
+    /// Seeds the random number generator of the particle filter to make replays deterministic
+    pub random_seed: u64,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -319,12 +352,17 @@ pub struct ObstacleFilterParameters {
     pub robot_detection_measurement_matching_distance: f32,
     pub goal_post_measurement_matching_distance: f32,
     pub hypothesis_merge_distance: f32,
-    pub process_noise: Vector2<f32>,
+    /// Variances added per second of prediction
+    pub process_noise: Vector4<f32>,
     pub feet_measurement_noise: Vector2<f32>,
     pub robot_measurement_noise: Vector2<f32>,
     pub sonar_measurement_noise: Vector2<f32>,
     pub network_robot_measurement_noise: Vector2<f32>,
     pub initial_covariance: Vector2<f32>,
+    pub initial_velocity_covariance: Vector2<f32>,
+    /// Fraction of the velocity which remains after one second
+    pub velocity_decay_factor: f32,
+    pub maximum_velocity: f32,
     pub measurement_count_threshold: usize,
     pub use_feet_detection_measurements: bool,
     pub use_robot_detection_measurements: bool,
@@ -353,6 +391,28 @@ pub struct CameraMatrixParameters {
     pub cc_optical_center: Point2<f32>,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CameraCalibrationParameters {
+    pub head_positions: Vec<HeadJoints<f32>>,
+    pub head_position_tolerance: f32,
+    pub settle_duration: Duration,
+    pub measurement_duration: Duration,
+    pub maximum_line_angle_deviation: f32,
+    pub maximum_line_distance_deviation: f32,
+    pub robust_loss: RobustLoss,
+    pub acceptance: CalibrationAcceptanceParameters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CalibrationAcceptanceParameters {
+    pub minimum_number_of_measurements: usize,
+    pub maximum_root_mean_square_residual: f32,
+    /// In degrees
+    pub maximum_standard_deviation: f32,
+    pub outlier_residual_threshold: f32,
+    pub maximum_outlier_ratio: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct FallProtectionParameters {
     pub ground_impact_angular_threshold: f32,
@@ -0,0 +1,18 @@
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PenaltyMark {
+    pub position: Point2<f32>,
+    pub confidence: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PenaltyMarkCandidate {
+    pub center_in_image: Point2<f32>,
+    pub radius_in_image: f32,
+    pub number_of_segments: usize,
+    pub confidence: f32,
+    pub is_accepted: bool,
+}
@@ -71,6 +71,13 @@ impl PathSegment {
             PathSegment::Arc(arc, orientation) => arc.length(*orientation),
         }
     }
+
+    pub fn end_point(&self) -> Point2<f32> {
+        match self {
+            PathSegment::LineSegment(line_segment) => line_segment.1,
+            PathSegment::Arc(arc, _orientation) => arc.end,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, SerializeHierarchy, Deserialize)]
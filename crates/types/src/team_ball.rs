@@ -0,0 +1,15 @@
+use std::time::SystemTime;
+
+use nalgebra::{Matrix2, Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeamBall {
+    pub position: Point2<f32>,
+    pub velocity: Vector2<f32>,
+    #[serialize_hierarchy(leaf)]
+    pub covariance: Matrix2<f32>,
+    pub last_seen: SystemTime,
+    pub number_of_observations: usize,
+}
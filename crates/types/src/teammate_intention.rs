@@ -0,0 +1,13 @@
+use nalgebra::Isometry2;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::{Intention, PlayerNumber, Role};
+
+/// Role and intention a teammate announced in its latest message
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeammateIntention {
+    pub player_number: PlayerNumber,
+    pub role: Role,
+    pub robot_to_field: Isometry2<f32>,
+    pub intention: Intention,
+}
@@ -6,15 +6,18 @@ use serialize_hierarchy::SerializeHierarchy;
 use spl_network_messages::PlayerNumber;
 
 use crate::{
-    fall_state::FallState, filtered_game_controller_state::FilteredGameControllerState,
-    kick_decision::KickDecision, obstacles::Obstacle, penalty_shot_direction::PenaltyShotDirection,
-    primary_state::PrimaryState, roles::Role, rule_obstacles::RuleObstacle, support_foot::Side,
+    ball_event::BallEvent, ball_trajectory::BallTrajectory, fall_state::FallState,
+    filtered_game_controller_state::FilteredGameControllerState, kick_decision::KickDecision,
+    obstacles::Obstacle, penalty_shot_direction::PenaltyShotDirection, primary_state::PrimaryState,
+    roles::Role, rule_obstacles::RuleObstacle, support_foot::Side, team_ball::TeamBall,
 };
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
 pub struct WorldState {
     pub ball: Option<BallState>,
+    pub ball_event: Option<BallEvent>,
     pub rule_ball: Option<BallState>,
+    pub team_ball: Option<TeamBall>,
     pub filtered_game_controller_state: Option<FilteredGameControllerState>,
     pub obstacles: Vec<Obstacle>,
     pub rule_obstacles: Vec<RuleObstacle>,
@@ -29,6 +32,8 @@ pub struct BallState {
     pub ball_in_ground: Point2<f32>,
     pub ball_in_field: Point2<f32>,
     pub ball_in_ground_velocity: Vector2<f32>,
+    /// Predicted rolling of the own ball, unavailable for team and rule balls
+    pub trajectory_in_ground: Option<BallTrajectory>,
     pub last_seen_ball: SystemTime,
     pub penalty_shot_direction: Option<PenaltyShotDirection>,
     pub field_side: Side,
@@ -40,6 +45,7 @@ impl BallState {
             ball_in_field: Point2::origin(),
             ball_in_ground: robot_to_field.inverse() * Point2::origin(),
             ball_in_ground_velocity: Vector2::zeros(),
+            trajectory_in_ground: None,
             last_seen_ball: UNIX_EPOCH,
             penalty_shot_direction: Default::default(),
             field_side: Side::Left,
@@ -0,0 +1,257 @@
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{AdditionalOutput, MainOutput};
+use nalgebra::{distance, point, Point2, Vector2};
+use projection::Projection;
+use serde::{Deserialize, Serialize};
+use types::{
+    camera_matrix::CameraMatrix,
+    color::Intensity,
+    field_border::FieldBorder,
+    goal_post::GoalPost,
+    image_segments::{EdgeType, ImageSegments, Segment},
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct GoalPostDetection {}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    goal_post_foot_points_in_image:
+        AdditionalOutput<Vec<Point2<f32>>, "goal_post_foot_points_in_image">,
+
+    enable: Parameter<bool, "goal_post_detection.$cycler_instance.enable">,
+    maximum_distance_to_robot:
+        Parameter<f32, "goal_post_detection.$cycler_instance.maximum_distance_to_robot">,
+    maximum_foot_point_distance:
+        Parameter<f32, "goal_post_detection.$cycler_instance.maximum_foot_point_distance">,
+    maximum_post_width: Parameter<f32, "goal_post_detection.$cycler_instance.maximum_post_width">,
+    minimum_luminance: Parameter<u8, "goal_post_detection.$cycler_instance.minimum_luminance">,
+    minimum_scan_lines_per_post:
+        Parameter<usize, "goal_post_detection.$cycler_instance.minimum_scan_lines_per_post">,
+    minimum_segment_length:
+        Parameter<u16, "goal_post_detection.$cycler_instance.minimum_segment_length">,
+    goal_post_diameter: Parameter<f32, "field_dimensions.goal_post_diameter">,
+
+    camera_matrix: RequiredInput<Option<CameraMatrix>, "camera_matrix?">,
+    field_border: Input<Option<FieldBorder>, "field_border?">,
+    image_segments: Input<ImageSegments, "image_segments">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub goal_posts: MainOutput<Option<Vec<GoalPost>>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct FootPoint {
+    scan_line_index: usize,
+    position_in_image: Point2<f32>,
+    position_in_ground: Point2<f32>,
+}
+
+impl GoalPostDetection {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {})
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        if !context.enable {
+            return Ok(MainOutputs::default());
+        }
+
+        let foot_points: Vec<_> = context
+            .image_segments
+            .scan_grid
+            .vertical_scan_lines
+            .iter()
+            .enumerate()
+            .filter_map(|(scan_line_index, scan_line)| {
+                let segment = scan_line.segments.iter().find(|segment| {
+                    is_post_segment(
+                        segment,
+                        scan_line.position,
+                        context.field_border,
+                        *context.minimum_luminance,
+                        *context.minimum_segment_length,
+                    )
+                })?;
+                let position_in_image = point![scan_line.position as f32, segment.end as f32];
+                let position_in_ground = context
+                    .camera_matrix
+                    .pixel_to_ground(position_in_image)
+                    .ok()?;
+                (position_in_ground.coords.norm() <= *context.maximum_distance_to_robot).then_some(
+                    FootPoint {
+                        scan_line_index,
+                        position_in_image,
+                        position_in_ground,
+                    },
+                )
+            })
+            .collect();
+        context
+            .goal_post_foot_points_in_image
+            .fill_if_subscribed(|| {
+                foot_points
+                    .iter()
+                    .map(|foot_point| foot_point.position_in_image)
+                    .collect()
+            });
+
+        let goal_posts = group_foot_points(&foot_points, *context.maximum_foot_point_distance)
+            .into_iter()
+            .filter_map(|group| {
+                goal_post_from_foot_points(
+                    &group,
+                    *context.minimum_scan_lines_per_post,
+                    *context.maximum_post_width,
+                    *context.goal_post_diameter,
+                )
+            })
+            .collect();
+        Ok(MainOutputs {
+            goal_posts: Some(goal_posts).into(),
+        })
+    }
+}
+
+/// Posts are bright, not field colored and reach from above the field border down onto the field
+fn is_post_segment(
+    segment: &Segment,
+    scan_line_position: u16,
+    field_border: Option<&FieldBorder>,
+    minimum_luminance: u8,
+    minimum_segment_length: u16,
+) -> bool {
+    if segment.field_color != Intensity::Low
+        || segment.color.y < minimum_luminance
+        || segment.length() < minimum_segment_length
+        || segment.end_edge_type != EdgeType::Falling
+    {
+        return false;
+    }
+    let start = point![scan_line_position as f32, segment.start as f32];
+    let end = point![scan_line_position as f32, segment.end as f32];
+    match field_border {
+        Some(field_border) => {
+            !field_border.is_inside_field(start) && field_border.is_inside_field(end)
+        }
+        None => segment.start_edge_type == EdgeType::ImageBorder,
+    }
+}
+
+/// Groups foot points of neighboring scan lines that are close to each other on the ground
+fn group_foot_points(
+    foot_points: &[FootPoint],
+    maximum_foot_point_distance: f32,
+) -> Vec<Vec<FootPoint>> {
+    let mut groups: Vec<Vec<FootPoint>> = Vec::new();
+    for &foot_point in foot_points {
+        let continues_last_group =
+            groups
+                .last()
+                .and_then(|group| group.last())
+                .is_some_and(|last_foot_point| {
+                    foot_point.scan_line_index == last_foot_point.scan_line_index + 1
+                        && distance(
+                            &foot_point.position_in_ground,
+                            &last_foot_point.position_in_ground,
+                        ) <= maximum_foot_point_distance
+                });
+        match groups.last_mut() {
+            Some(group) if continues_last_group => group.push(foot_point),
+            _ => groups.push(vec![foot_point]),
+        }
+    }
+    groups
+}
+
+fn goal_post_from_foot_points(
+    foot_points: &[FootPoint],
+    minimum_scan_lines_per_post: usize,
+    maximum_post_width: f32,
+    goal_post_diameter: f32,
+) -> Option<GoalPost> {
+    let first = foot_points.first()?;
+    let last = foot_points.last()?;
+    if foot_points.len() < minimum_scan_lines_per_post
+        || distance(&first.position_in_ground, &last.position_in_ground) > maximum_post_width
+    {
+        return None;
+    }
+    let visible_foot = foot_points
+        .iter()
+        .map(|foot_point| foot_point.position_in_ground.coords)
+        .sum::<Vector2<f32>>()
+        / foot_points.len() as f32;
+    // the foot points lie on the front of the post, its center is half a diameter further away
+    let position = visible_foot
+        + visible_foot
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector2::zeros)
+            * goal_post_diameter
+            / 2.0;
+    Some(GoalPost {
+        position: position.into(),
+        number_of_scan_lines: foot_points.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn foot_point(scan_line_index: usize, x: f32, y: f32) -> FootPoint {
+        FootPoint {
+            scan_line_index,
+            position_in_image: Point2::origin(),
+            position_in_ground: point![x, y],
+        }
+    }
+
+    #[test]
+    fn neighboring_foot_points_form_one_post() {
+        let foot_points = [
+            foot_point(3, 3.0, 0.82),
+            foot_point(4, 3.0, 0.80),
+            foot_point(5, 3.0, 0.78),
+            foot_point(7, 3.0, -0.78),
+            foot_point(8, 3.5, -0.80),
+        ];
+
+        let groups = group_foot_points(&foot_points, 0.1);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].len(), 3);
+        let goal_post = goal_post_from_foot_points(&groups[0], 3, 0.3, 0.1).unwrap();
+        assert_relative_eq!(
+            goal_post.position,
+            point![3.0, 0.8] + point![3.0, 0.8].coords.normalize() * 0.05,
+            epsilon = 0.0001
+        );
+        assert!(goal_post_from_foot_points(&groups[1], 3, 0.3, 0.1).is_none());
+    }
+
+    #[test]
+    fn wide_structures_are_no_posts() {
+        let foot_points = [
+            foot_point(0, 2.0, 0.0),
+            foot_point(1, 2.0, 0.1),
+            foot_point(2, 2.0, 0.2),
+            foot_point(3, 2.0, 0.3),
+            foot_point(4, 2.0, 0.4),
+        ];
+
+        let groups = group_foot_points(&foot_points, 0.15);
+
+        assert_eq!(groups.len(), 1);
+        assert!(goal_post_from_foot_points(&groups[0], 3, 0.3, 0.1).is_none());
+    }
+}
@@ -12,7 +12,9 @@ use types::{
     filtered_segments::FilteredSegments,
     image_segments::{EdgeType, Segment},
     line::Line,
-    line_data::{ImageLines, LineData, LineDiscardReason},
+    line_data::{
+        intersections_of_lines, ImageLines, LineData, LineDiscardReason, LineIntersection,
+    },
     ycbcr422_image::YCbCr422Image,
 };
 
@@ -41,6 +43,10 @@ pub struct CycleContext {
     maximum_fit_distance_in_ground:
         Parameter<f32, "line_detection.$cycler_instance.maximum_fit_distance_in_ground">,
     maximum_gap_on_line: Parameter<f32, "line_detection.$cycler_instance.maximum_gap_on_line">,
+    maximum_intersection_angle_deviation:
+        Parameter<f32, "line_detection.$cycler_instance.maximum_intersection_angle_deviation">,
+    maximum_intersection_end_distance:
+        Parameter<f32, "line_detection.$cycler_instance.maximum_intersection_end_distance">,
     maximum_number_of_lines:
         Parameter<usize, "line_detection.$cycler_instance.maximum_number_of_lines">,
     maximum_projected_segment_length:
@@ -177,8 +183,29 @@ impl LineDetection {
                     .push(Line(start_point_in_image, end_point_in_image));
             }
         }
+        let intersections_in_robot = intersections_of_lines(
+            &lines_in_robot,
+            *context.maximum_intersection_angle_deviation,
+            *context.maximum_intersection_end_distance,
+        );
+        if context.lines_in_image.is_subscribed() {
+            image_lines.intersections = intersections_in_robot
+                .iter()
+                .filter_map(|intersection| {
+                    let position = context
+                        .camera_matrix
+                        .ground_to_pixel(intersection.position)
+                        .ok()?;
+                    Some(LineIntersection {
+                        position,
+                        kind: intersection.kind,
+                    })
+                })
+                .collect();
+        }
         let line_data = LineData {
             lines_in_robot,
+            intersections_in_robot,
             used_vertical_filtered_segments,
         };
 
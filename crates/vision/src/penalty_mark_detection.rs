@@ -0,0 +1,277 @@
+use std::f32::consts::TAU;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{AdditionalOutput, MainOutput};
+use nalgebra::{distance, point, vector, Point2};
+use projection::Projection;
+use serde::{Deserialize, Serialize};
+use types::{
+    camera_matrix::CameraMatrix,
+    filtered_segments::FilteredSegments,
+    image_segments::EdgeType,
+    line_data::LineData,
+    penalty_mark::{PenaltyMark, PenaltyMarkCandidate},
+    ycbcr422_image::YCbCr422Image,
+};
+
+const NUMBER_OF_SURROUNDING_SAMPLES: usize = 16;
+
+#[derive(Deserialize, Serialize)]
+pub struct PenaltyMarkDetection {}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    penalty_mark_candidates: AdditionalOutput<Vec<PenaltyMarkCandidate>, "penalty_mark_candidates">,
+
+    enable: Parameter<bool, "penalty_mark_detection.$cycler_instance.enable">,
+    classifier_confidence_threshold:
+        Parameter<f32, "penalty_mark_detection.$cycler_instance.classifier_confidence_threshold">,
+    maximum_distance_to_robot:
+        Parameter<f32, "penalty_mark_detection.$cycler_instance.maximum_distance_to_robot">,
+    minimum_luminance_contrast:
+        Parameter<f32, "penalty_mark_detection.$cycler_instance.minimum_luminance_contrast">,
+    minimum_segments_per_candidate:
+        Parameter<usize, "penalty_mark_detection.$cycler_instance.minimum_segments_per_candidate">,
+    surrounding_radius_factor:
+        Parameter<f32, "penalty_mark_detection.$cycler_instance.surrounding_radius_factor">,
+    penalty_marker_size: Parameter<f32, "field_dimensions.penalty_marker_size">,
+
+    camera_matrix: RequiredInput<Option<CameraMatrix>, "camera_matrix?">,
+    filtered_segments: Input<FilteredSegments, "filtered_segments">,
+    image: Input<YCbCr422Image, "image">,
+    line_data: RequiredInput<Option<LineData>, "line_data?">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub penalty_marks: MainOutput<Option<Vec<PenaltyMark>>>,
+}
+
+impl PenaltyMarkDetection {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {})
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        if !context.enable {
+            return Ok(MainOutputs::default());
+        }
+
+        let candidate_points = find_candidate_points(
+            context.filtered_segments,
+            context.line_data,
+            context.camera_matrix,
+            *context.penalty_marker_size,
+            *context.maximum_distance_to_robot,
+        );
+        let clusters = cluster_points(candidate_points, *context.penalty_marker_size);
+
+        let candidates: Vec<_> = clusters
+            .into_iter()
+            .filter(|cluster| cluster.samples >= *context.minimum_segments_per_candidate)
+            .filter_map(|cluster| {
+                let center_in_image = context.camera_matrix.ground_to_pixel(cluster.mean).ok()?;
+                let (confidence, radius_in_image) = classify_candidate(
+                    context.image,
+                    context.camera_matrix,
+                    cluster.mean,
+                    center_in_image,
+                    *context.penalty_marker_size * *context.surrounding_radius_factor,
+                    *context.minimum_luminance_contrast,
+                );
+                Some((
+                    cluster.mean,
+                    PenaltyMarkCandidate {
+                        center_in_image,
+                        radius_in_image,
+                        number_of_segments: cluster.samples,
+                        confidence,
+                        is_accepted: confidence >= *context.classifier_confidence_threshold,
+                    },
+                ))
+            })
+            .collect();
+        context.penalty_mark_candidates.fill_if_subscribed(|| {
+            candidates
+                .iter()
+                .map(|(_position, candidate)| *candidate)
+                .collect()
+        });
+
+        let penalty_marks = candidates
+            .into_iter()
+            .filter(|(_position, candidate)| candidate.is_accepted)
+            .map(|(position, candidate)| PenaltyMark {
+                position,
+                confidence: candidate.confidence,
+            })
+            .collect();
+        Ok(MainOutputs {
+            penalty_marks: Some(penalty_marks).into(),
+        })
+    }
+}
+
+fn find_candidate_points(
+    filtered_segments: &FilteredSegments,
+    line_data: &LineData,
+    camera_matrix: &CameraMatrix,
+    penalty_marker_size: f32,
+    maximum_distance_to_robot: f32,
+) -> Vec<Point2<f32>> {
+    filtered_segments
+        .scan_grid
+        .vertical_scan_lines
+        .iter()
+        .flat_map(|scan_line| {
+            scan_line
+                .segments
+                .iter()
+                .map(move |segment| (scan_line.position, segment))
+        })
+        .filter(|(scan_line_position, segment)| {
+            segment.start_edge_type == EdgeType::Rising
+                && segment.end_edge_type == EdgeType::Falling
+                && !line_data
+                    .used_vertical_filtered_segments
+                    .contains(&point![*scan_line_position, segment.start])
+        })
+        .filter_map(|(scan_line_position, segment)| {
+            let x = scan_line_position as f32;
+            let start = camera_matrix
+                .pixel_to_ground(point![x, segment.start as f32])
+                .ok()?;
+            let end = camera_matrix
+                .pixel_to_ground(point![x, segment.end as f32])
+                .ok()?;
+            // a scan line crosses at most the diagonal of the cross
+            if distance(&start, &end) > penalty_marker_size * 2.0_f32.sqrt() {
+                return None;
+            }
+            let center = camera_matrix
+                .pixel_to_ground(point![x, segment.center() as f32])
+                .ok()?;
+            (center.coords.norm() <= maximum_distance_to_robot).then_some(center)
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Cluster {
+    mean: Point2<f32>,
+    samples: usize,
+}
+
+fn cluster_points(points: Vec<Point2<f32>>, maximum_cluster_distance: f32) -> Vec<Cluster> {
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for point in points {
+        let nearest_cluster = clusters
+            .iter_mut()
+            .map(|cluster| {
+                let distance = distance(&cluster.mean, &point);
+                (cluster, distance)
+            })
+            .filter(|(_cluster, distance)| *distance < maximum_cluster_distance)
+            .min_by(|(_, left_distance), (_, right_distance)| {
+                left_distance.total_cmp(right_distance)
+            });
+        match nearest_cluster {
+            Some((cluster, _distance)) => {
+                cluster.samples += 1;
+                cluster.mean += (point - cluster.mean) / cluster.samples as f32;
+            }
+            None => clusters.push(Cluster {
+                mean: point,
+                samples: 1,
+            }),
+        }
+    }
+    clusters
+}
+
+/// Rates how well the surrounding of a candidate looks like plain field around a bright mark
+///
+/// Returns the fraction of surrounding samples that are darker than the center and the mean
+/// distance of the samples to the center in the image.
+fn classify_candidate(
+    image: &YCbCr422Image,
+    camera_matrix: &CameraMatrix,
+    center_in_ground: Point2<f32>,
+    center_in_image: Point2<f32>,
+    surrounding_radius: f32,
+    minimum_luminance_contrast: f32,
+) -> (f32, f32) {
+    let Some(center_luminance) = luminance_at(image, center_in_image) else {
+        return (0.0, 0.0);
+    };
+    let surrounding_samples: Vec<_> = (0..NUMBER_OF_SURROUNDING_SAMPLES)
+        .filter_map(|index| {
+            let angle = index as f32 * TAU / NUMBER_OF_SURROUNDING_SAMPLES as f32;
+            let sample_in_ground =
+                center_in_ground + vector![angle.cos(), angle.sin()] * surrounding_radius;
+            let sample_in_image = camera_matrix.ground_to_pixel(sample_in_ground).ok()?;
+            let luminance = luminance_at(image, sample_in_image)?;
+            Some((distance(&center_in_image, &sample_in_image), luminance))
+        })
+        .collect();
+    if surrounding_samples.len() < NUMBER_OF_SURROUNDING_SAMPLES / 2 {
+        return (0.0, 0.0);
+    }
+    let number_of_darker_samples = surrounding_samples
+        .iter()
+        .filter(|(_distance, luminance)| center_luminance - luminance >= minimum_luminance_contrast)
+        .count();
+    let radius_in_image = surrounding_samples
+        .iter()
+        .map(|(distance, _luminance)| distance)
+        .sum::<f32>()
+        / surrounding_samples.len() as f32;
+    (
+        number_of_darker_samples as f32 / NUMBER_OF_SURROUNDING_SAMPLES as f32,
+        radius_in_image,
+    )
+}
+
+fn luminance_at(image: &YCbCr422Image, pixel: Point2<f32>) -> Option<f32> {
+    if pixel.x < 0.0 || pixel.y < 0.0 {
+        return None;
+    }
+    image
+        .try_at(pixel.x as u32, pixel.y as u32)
+        .map(|pixel| pixel.y as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearby_points_are_clustered() {
+        let points = vec![
+            point![2.0, 0.0],
+            point![2.04, 0.02],
+            point![1.98, -0.02],
+            point![3.0, 1.0],
+        ];
+
+        let clusters = cluster_points(points, 0.1);
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].samples, 3);
+        assert!(distance(&clusters[0].mean, &point![2.0067, 0.0]) < 0.001);
+        assert_eq!(clusters[1].samples, 1);
+    }
+
+    #[test]
+    fn luminance_is_only_sampled_inside_image() {
+        let image = YCbCr422Image::zero(8, 8);
+
+        assert_eq!(luminance_at(&image, point![-1.0, 2.0]), None);
+        assert_eq!(luminance_at(&image, point![2.0, 2.0]), Some(0.0));
+    }
+}
@@ -0,0 +1,107 @@
+local inspect = require 'inspect'
+
+function spawn_robot(number)
+    table.insert(state.robots, create_robot(number))
+end
+
+spawn_robot(1)
+spawn_robot(2)
+spawn_robot(3)
+spawn_robot(4)
+spawn_robot(5)
+spawn_robot(6)
+spawn_robot(7)
+
+table.insert(state.opponents, {
+    position = { 4.3, 0.0 },
+    behavior = "Static",
+})
+-- stands in the way of robot 7 walking to its kick off position
+table.insert(state.opponents, {
+    position = { -0.75, -1.4 },
+    behavior = "Static",
+})
+table.insert(state.opponents, {
+    position = { 2.5, 1.0 },
+    behavior = {
+        Waypoints = {
+            waypoints = { { 2.5, 1.0 }, { 2.5, -1.0 } },
+            speed = 0.2,
+        },
+    },
+})
+table.insert(state.opponents, {
+    position = { 1.0, -2.0 },
+    behavior = {
+        ChaseBall = {
+            speed = 0.2,
+        },
+    },
+})
+
+function on_goal()
+    print("Goal scored, resetting ball!")
+    print("Ball: " .. inspect(state.ball))
+    state.ball = nil
+end
+
+function on_collision(first, second)
+    print("Robots " .. first .. " and " .. second .. " collided")
+end
+
+-- opponents are only known from robot detections, walking into a static one means the obstacle
+-- filter or the path planning did not work
+function expect_no_contact_with_static_opponents()
+    local contact_distance = 2.0 * state.physics.robot_radius + 0.01
+    for number = 1, 7 do
+        local position = robot_position(number)
+        for index, opponent in ipairs(state.opponents) do
+            if position ~= nil and opponent.behavior == "Static" then
+                local x = position[1] - opponent.position[1]
+                local y = position[2] - opponent.position[2]
+                expect(
+                    math.sqrt(x * x + y * y) > contact_distance,
+                    "robot " .. number .. " walked into static opponent " .. index
+                )
+            end
+        end
+    end
+end
+
+function on_cycle()
+    if state.ball == nil and state.cycle_count % 1000 == 0 then
+        state.ball = {
+            position = { 0.0, 0.0 },
+            velocity = { 0.0, 0.0 },
+        }
+    end
+
+    if state.cycle_count == 100 then
+        state.game_controller_state.game_state = "Ready"
+        state.filtered_game_state = {
+            Ready = {
+                kicking_team = "Hulks",
+            }
+        }
+    end
+
+    if state.cycle_count == 1600 then
+        state.filtered_game_state = "Set"
+    end
+
+    if state.cycle_count == 1700 then
+        state.filtered_game_state = {
+            Playing = {
+                ball_is_free = true,
+                kick_off = true
+            }
+        }
+        expect_goal_within(30)
+    end
+
+    expect_no_contact_with_static_opponents()
+
+    if state.cycle_count == 6000 then
+        state.finished = true
+    end
+end
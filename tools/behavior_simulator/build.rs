@@ -1,42 +1,13 @@
 use code_generation::{generate, write_to_file::WriteToFile};
 use color_eyre::eyre::{Result, WrapErr};
 use source_analyzer::{
-    cyclers::{CyclerKind, Cyclers},
-    manifest::{CyclerManifest, FrameworkManifest},
-    pretty::to_string_pretty,
-    structs::Structs,
+    cyclers::Cyclers, manifest::FrameworkManifest, pretty::to_string_pretty, structs::Structs,
 };
 
 fn main() -> Result<()> {
-    let manifest = FrameworkManifest {
-        cyclers: vec![
-            CyclerManifest {
-                name: "Control",
-                kind: CyclerKind::RealTime,
-                instances: vec![""],
-                setup_nodes: vec!["control::fake_data"],
-                nodes: vec![
-                    "control::active_vision",
-                    "control::ball_state_composer",
-                    "control::behavior::node",
-                    "control::game_controller_state_filter",
-                    "control::kick_selector",
-                    "control::motion::look_around",
-                    "control::role_assignment",
-                    "control::rule_obstacle_composer",
-                    "control::time_to_reach_kick_position",
-                    "control::world_state_composer",
-                ],
-            },
-            CyclerManifest {
-                name: "SplNetwork",
-                kind: CyclerKind::Perception,
-                instances: vec![""],
-                setup_nodes: vec!["spl_network::message_receiver"],
-                nodes: vec![],
-            },
-        ],
-    };
+    let manifest_path = "../../crates/hulk/framework.toml";
+    println!("cargo:rerun-if-changed={manifest_path}");
+    let manifest = FrameworkManifest::try_from_file(manifest_path, "simulator")?;
     let root = "../../crates/";
 
     let mut cyclers = Cyclers::try_from_manifest(manifest, root)?;
@@ -0,0 +1,270 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use mlua::{Error as LuaError, Lua, LuaSerdeExt, Value};
+use nalgebra::Point2;
+use parking_lot::Mutex;
+use types::roles::Role;
+
+use crate::{
+    report::ScenarioReport,
+    robot::{from_player_number, to_player_number},
+    state::State,
+};
+
+/// Expectations registered by a scenario script which are checked every cycle
+#[derive(Default)]
+pub struct Expectations {
+    goal_deadlines: Vec<GoalDeadline>,
+    minimum_robot_distance: Option<f32>,
+    violating_pairs: BTreeSet<(usize, usize)>,
+}
+
+struct GoalDeadline {
+    registered_at: f32,
+    deadline: f32,
+}
+
+impl Expectations {
+    pub fn check(
+        &mut self,
+        time: f32,
+        robot_positions: &BTreeMap<usize, Point2<f32>>,
+        report: &mut ScenarioReport,
+    ) {
+        self.goal_deadlines.retain(|goal_deadline| {
+            if goal_deadline.is_met(report) {
+                return false;
+            }
+            if time > goal_deadline.deadline {
+                report.record_failed_assertion(
+                    time,
+                    format!(
+                        "expected a goal within {:.2}s after {:.2}s",
+                        goal_deadline.deadline - goal_deadline.registered_at,
+                        goal_deadline.registered_at
+                    ),
+                );
+                return false;
+            }
+            true
+        });
+
+        if let Some(minimum_robot_distance) = self.minimum_robot_distance {
+            let mut violating_pairs = BTreeSet::new();
+            for (&first_player, first_position) in robot_positions {
+                for (&second_player, second_position) in robot_positions.range(first_player + 1..) {
+                    let distance = (first_position - second_position).norm();
+                    if distance >= minimum_robot_distance {
+                        continue;
+                    }
+                    let players = (first_player, second_player);
+                    if !self.violating_pairs.contains(&players) {
+                        report.record_failed_assertion(
+                            time,
+                            format!(
+                                "expected robots {first_player} and {second_player} to be at least {minimum_robot_distance:.2}m apart, but they are {distance:.2}m apart"
+                            ),
+                        );
+                    }
+                    violating_pairs.insert(players);
+                }
+            }
+            self.violating_pairs = violating_pairs;
+        }
+    }
+
+    /// Fails all expectations which can no longer be met because the scenario has finished
+    pub fn finish(&mut self, time: f32, report: &mut ScenarioReport) {
+        for goal_deadline in self.goal_deadlines.drain(..) {
+            if !goal_deadline.is_met(report) {
+                report.record_failed_assertion(
+                    time,
+                    format!(
+                        "expected a goal within {:.2}s after {:.2}s, but the scenario finished before",
+                        goal_deadline.deadline - goal_deadline.registered_at,
+                        goal_deadline.registered_at
+                    ),
+                );
+            }
+        }
+    }
+}
+
+impl GoalDeadline {
+    fn is_met(&self, report: &ScenarioReport) -> bool {
+        report
+            .goals
+            .iter()
+            .any(|goal| (self.registered_at..=self.deadline).contains(&goal.time))
+    }
+}
+
+/// Registers the `expect*` assertions and the query helpers as lua globals
+///
+/// Failed assertions do not abort the scenario but are collected in the report, so that a single
+/// run shows all regressions.
+pub fn register_assertions(
+    lua: &Lua,
+    state: Arc<Mutex<State>>,
+    report: Arc<Mutex<ScenarioReport>>,
+    expectations: Arc<Mutex<Expectations>>,
+) -> Result<()> {
+    let globals = lua.globals();
+
+    {
+        let state = state.clone();
+        let report = report.clone();
+        let expect = lua
+            .create_function(move |_lua, (condition, message): (bool, String)| {
+                if !condition {
+                    let time = state.lock().time_elapsed.as_secs_f32();
+                    report.lock().record_failed_assertion(time, message);
+                }
+                Ok(())
+            })
+            .wrap_err("failed to create function expect")?;
+        globals
+            .set("expect", expect)
+            .wrap_err("failed to insert expect")?;
+    }
+
+    {
+        let state = state.clone();
+        let expectations = expectations.clone();
+        let expect_goal_within = lua
+            .create_function(move |_lua, seconds: f32| {
+                let registered_at = state.lock().time_elapsed.as_secs_f32();
+                expectations.lock().goal_deadlines.push(GoalDeadline {
+                    registered_at,
+                    deadline: registered_at + seconds,
+                });
+                Ok(())
+            })
+            .wrap_err("failed to create function expect_goal_within")?;
+        globals
+            .set("expect_goal_within", expect_goal_within)
+            .wrap_err("failed to insert expect_goal_within")?;
+    }
+
+    {
+        let expect_minimum_robot_distance = lua
+            .create_function(move |_lua, distance: f32| {
+                expectations.lock().minimum_robot_distance = Some(distance);
+                Ok(())
+            })
+            .wrap_err("failed to create function expect_minimum_robot_distance")?;
+        globals
+            .set(
+                "expect_minimum_robot_distance",
+                expect_minimum_robot_distance,
+            )
+            .wrap_err("failed to insert expect_minimum_robot_distance")?;
+    }
+
+    {
+        let state = state.clone();
+        let report = report.clone();
+        let expect_role = lua
+            .create_function(move |lua, (player_number, role): (usize, Value)| {
+                let expected_role: Role = lua.from_value(role)?;
+                let player_number = to_player_number(player_number).map_err(LuaError::external)?;
+                let state = state.lock();
+                let role = state
+                    .robots
+                    .get(&player_number)
+                    .map(|robot| robot.database.main_outputs.role);
+                if role != Some(expected_role) {
+                    report.lock().record_failed_assertion(
+                        state.time_elapsed.as_secs_f32(),
+                        format!(
+                            "expected robot {} to be {expected_role:?}, but it is {role:?}",
+                            from_player_number(player_number)
+                        ),
+                    );
+                }
+                Ok(())
+            })
+            .wrap_err("failed to create function expect_role")?;
+        globals
+            .set("expect_role", expect_role)
+            .wrap_err("failed to insert expect_role")?;
+    }
+
+    {
+        let goals_scored = lua
+            .create_function(move |_lua, ()| Ok(report.lock().goals.len()))
+            .wrap_err("failed to create function goals_scored")?;
+        globals
+            .set("goals_scored", goals_scored)
+            .wrap_err("failed to insert goals_scored")?;
+    }
+
+    {
+        let state = state.clone();
+        let role_of = lua
+            .create_function(move |lua, player_number: usize| {
+                let player_number = to_player_number(player_number).map_err(LuaError::external)?;
+                let role = state
+                    .lock()
+                    .robots
+                    .get(&player_number)
+                    .map(|robot| robot.database.main_outputs.role);
+                lua.to_value(&role)
+            })
+            .wrap_err("failed to create function role_of")?;
+        globals
+            .set("role_of", role_of)
+            .wrap_err("failed to insert role_of")?;
+    }
+
+    {
+        let state = state.clone();
+        let robot_position = lua
+            .create_function(move |lua, player_number: usize| {
+                let player_number = to_player_number(player_number).map_err(LuaError::external)?;
+                let position = robot_positions(&state.lock())
+                    .get(&from_player_number(player_number))
+                    .copied();
+                lua.to_value(&position)
+            })
+            .wrap_err("failed to create function robot_position")?;
+        globals
+            .set("robot_position", robot_position)
+            .wrap_err("failed to insert robot_position")?;
+    }
+
+    {
+        let distance_between_robots = lua
+            .create_function(move |_lua, (first, second): (usize, usize)| {
+                let robot_positions = robot_positions(&state.lock());
+                match (robot_positions.get(&first), robot_positions.get(&second)) {
+                    (Some(first), Some(second)) => Ok(Some((first - second).norm())),
+                    _ => Ok(None),
+                }
+            })
+            .wrap_err("failed to create function distance_between_robots")?;
+        globals
+            .set("distance_between_robots", distance_between_robots)
+            .wrap_err("failed to insert distance_between_robots")?;
+    }
+
+    Ok(())
+}
+
+pub fn robot_positions(state: &State) -> BTreeMap<usize, Point2<f32>> {
+    state
+        .robots
+        .iter()
+        .filter_map(|(player_number, robot)| {
+            let robot_to_field = robot.database.main_outputs.robot_to_field?;
+            Some((
+                from_player_number(*player_number),
+                Point2::from(robot_to_field.translation.vector),
+            ))
+        })
+        .collect()
+}
@@ -0,0 +1,240 @@
+use std::{
+    fs::{create_dir_all, read_dir, read_to_string, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread::spawn,
+};
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::{
+    report::ScenarioReport, robot::ParameterOverrides, simulator::Simulator, structs::Parameters,
+};
+
+/// Parameter values to sweep over by path, every combination of values is simulated
+///
+/// ```json
+/// {
+///   "role_positions.striker_supporter_distance_to_ball": [1.0, 1.2, 1.5],
+///   "role_positions.defender_y_offset": [0.6, 0.8]
+/// }
+/// ```
+pub type Sweep = Vec<(String, Vec<Value>)>;
+
+pub struct Run {
+    pub scenario_file: PathBuf,
+    pub parameter_set: usize,
+    pub parameter_overrides: ParameterOverrides,
+    pub repetition: u64,
+}
+
+#[derive(Serialize)]
+pub struct RunSummary {
+    pub scenario_file: PathBuf,
+    pub parameter_set: usize,
+    pub parameter_overrides: ParameterOverrides,
+    pub repetition: u64,
+    pub succeeded: bool,
+    pub error: Option<String>,
+    pub report: ScenarioReport,
+}
+
+pub fn read_sweep(path: impl AsRef<Path>) -> Result<Sweep> {
+    let path = path.as_ref();
+    let sweep: serde_json::Map<String, Value> = serde_json::from_str(
+        &read_to_string(path).wrap_err_with(|| format!("failed to read {path:?}"))?,
+    )
+    .wrap_err_with(|| format!("failed to parse sweep {path:?}"))?;
+    sweep
+        .into_iter()
+        .map(|(parameter_path, values)| {
+            if !Parameters::exists(&parameter_path) {
+                bail!("parameter {parameter_path} does not exist");
+            }
+            match values {
+                Value::Array(values) if !values.is_empty() => Ok((parameter_path, values)),
+                _ => bail!("values of {parameter_path} have to be a non-empty array"),
+            }
+        })
+        .collect()
+}
+
+/// All combinations of the swept parameter values, a single empty set if nothing is swept
+pub fn parameter_sets(sweep: &Sweep) -> Vec<ParameterOverrides> {
+    sweep
+        .iter()
+        .fold(vec![ParameterOverrides::new()], |sets, (path, values)| {
+            sets.iter()
+                .flat_map(|set| {
+                    values.iter().map(move |value| {
+                        let mut set = set.clone();
+                        set.insert(path.clone(), value.clone());
+                        set
+                    })
+                })
+                .collect()
+        })
+}
+
+/// Lua files in `path` sorted by name, or `path` itself if it is a file
+pub fn collect_scenario_files(path: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let path = path.as_ref();
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut scenario_files = read_dir(path)
+        .wrap_err_with(|| format!("failed to read directory {path:?}"))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    scenario_files.retain(|path| path.extension().is_some_and(|extension| extension == "lua"));
+    scenario_files.sort();
+    if scenario_files.is_empty() {
+        bail!("no lua scenarios found in {path:?}");
+    }
+    Ok(scenario_files)
+}
+
+/// Runs all `runs` on `number_of_jobs` threads, the summaries are in the order of `runs`
+pub fn run_in_parallel(runs: Vec<Run>, number_of_jobs: usize) -> Result<Vec<RunSummary>> {
+    let number_of_runs = runs.len();
+    let queue = Arc::new(Mutex::new(
+        runs.into_iter().enumerate().rev().collect::<Vec<_>>(),
+    ));
+    let summaries = Arc::new(Mutex::new(Vec::with_capacity(number_of_runs)));
+
+    let workers: Vec<_> = (0..number_of_jobs.max(1))
+        .map(|_| {
+            let queue = queue.clone();
+            let summaries = summaries.clone();
+            spawn(move || loop {
+                let Some((index, run)) = queue.lock().pop() else {
+                    break;
+                };
+                let summary = run_single(run);
+                eprintln!(
+                    "[{}/{number_of_runs}] {} parameter set {} repetition {}: {}",
+                    index + 1,
+                    summary.scenario_file.display(),
+                    summary.parameter_set,
+                    summary.repetition,
+                    if summary.succeeded { "ok" } else { "failed" },
+                );
+                summaries.lock().push((index, summary));
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker
+            .join()
+            .map_err(|_| eyre!("simulation worker panicked"))?;
+    }
+
+    let mut summaries = Arc::try_unwrap(summaries)
+        .map_err(|_| eyre!("simulation workers are still running"))?
+        .into_inner();
+    summaries.sort_by_key(|(index, _summary)| *index);
+    Ok(summaries
+        .into_iter()
+        .map(|(_index, summary)| summary)
+        .collect())
+}
+
+fn run_single(run: Run) -> RunSummary {
+    let mut report = ScenarioReport::default();
+    let result = Simulator::try_new_with_parameter_overrides(run.parameter_overrides.clone())
+        .and_then(|mut simulator| {
+            simulator.record_frames = false;
+            let result = simulator.execute_script(&run.scenario_file).and_then(|()| {
+                // the script may set its own seed, which would override a seed applied before
+                let mut state = simulator.state.lock();
+                let random_seed = repetition_seed(state.random_seed, run.repetition);
+                state.reseed(random_seed);
+                drop(state);
+                simulator.run()
+            });
+            report = simulator.report();
+            result
+        });
+    RunSummary {
+        scenario_file: run.scenario_file,
+        parameter_set: run.parameter_set,
+        parameter_overrides: run.parameter_overrides,
+        repetition: run.repetition,
+        succeeded: result.is_ok(),
+        error: result.err().map(|error| format!("{error:#}")),
+        report,
+    }
+}
+
+/// Repetition 0 keeps the seed of the script, every other repetition offsets it
+///
+/// Seeds pass through lua numbers and therefore have to stay small.
+fn repetition_seed(script_seed: u64, repetition: u64) -> u64 {
+    script_seed.wrapping_add(repetition)
+}
+
+pub fn write_summaries(summaries: &[RunSummary], output_directory: impl AsRef<Path>) -> Result<()> {
+    let output_directory = output_directory.as_ref();
+    create_dir_all(output_directory)
+        .wrap_err_with(|| format!("failed to create {output_directory:?}"))?;
+
+    let json_path = output_directory.join("summary.json");
+    let file =
+        File::create(&json_path).wrap_err_with(|| format!("failed to create {json_path:?}"))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), summaries)
+        .wrap_err_with(|| format!("failed to write {json_path:?}"))?;
+
+    let csv_path = output_directory.join("summary.csv");
+    let file =
+        File::create(&csv_path).wrap_err_with(|| format!("failed to create {csv_path:?}"))?;
+    write_csv(summaries, BufWriter::new(file))
+        .wrap_err_with(|| format!("failed to write {csv_path:?}"))
+}
+
+fn write_csv(summaries: &[RunSummary], mut writer: impl Write) -> Result<()> {
+    writeln!(
+        writer,
+        "scenario,parameter_set,parameters,repetition,succeeded,goals,first_goal_time,collisions,failed_assertions,simulated_time,wall_time,error"
+    )?;
+    for summary in summaries {
+        let report = &summary.report;
+        let first_goal_time = report
+            .goals
+            .first()
+            .map(|goal| goal.time.to_string())
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            escape_csv(&summary.scenario_file.display().to_string()),
+            summary.parameter_set,
+            escape_csv(&serde_json::to_string(&summary.parameter_overrides)?),
+            summary.repetition,
+            summary.succeeded,
+            report.goals.len(),
+            first_goal_time,
+            report.collisions.len(),
+            report.failed_assertions.len(),
+            report.simulated_time,
+            report.wall_time,
+            escape_csv(summary.error.as_deref().unwrap_or_default()),
+        )?;
+    }
+    Ok(())
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
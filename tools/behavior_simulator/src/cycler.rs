@@ -7,17 +7,23 @@ use control::{
     behavior::node::{self, Behavior},
     kick_selector::{self, KickSelector},
     motion::look_around::LookAround,
+    obstacle_filter::{self, ObstacleFilter},
     role_assignment::{self, RoleAssignment},
     rule_obstacle_composer::RuleObstacleComposer,
+    team_ball_filter::{self, TeamBallFilter},
     time_to_reach_kick_position::{self, TimeToReachKickPosition},
     world_state_composer::{self, WorldStateComposer},
 };
 
 use framework::{AdditionalOutput, PerceptionInput};
+use nalgebra::Isometry2;
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use tokio::sync::Notify;
-use types::messages::IncomingMessage;
+use types::{
+    detected_robots::DetectedRobots, messages::IncomingMessage, obstacles::Obstacle,
+    parameters::ObstacleFilterParameters,
+};
 
 use crate::{
     interfake::Interfake,
@@ -33,6 +39,16 @@ pub struct Database {
     pub additional_outputs: AdditionalOutputs,
 }
 
+/// Inputs of the obstacle filter which the simulation provides in place of the vision cyclers
+pub struct ObstacleInputs<'a> {
+    /// Not part of the simulator parameters, the obstacle filter is no node of the simulator target
+    pub parameters: &'a ObstacleFilterParameters,
+    pub current_odometry_to_last_odometry: Isometry2<f32>,
+    pub detected_robots: &'a DetectedRobots,
+    /// Appended to the filtered obstacles, in robot coordinates
+    pub scripted_obstacles: &'a [Obstacle],
+}
+
 pub struct BehaviorCycler {
     hardware_interface: Arc<Interfake>,
     own_changed: Arc<Notify>,
@@ -41,8 +57,10 @@ pub struct BehaviorCycler {
     behavior: Behavior,
     kick_selector: KickSelector,
     look_around: LookAround,
+    obstacle_filter: ObstacleFilter,
     role_assignment: RoleAssignment,
     rule_obstacle_composer: RuleObstacleComposer,
+    team_ball_filter: TeamBallFilter,
     world_state_composer: WorldStateComposer,
     time_to_reach_kick_position: TimeToReachKickPosition,
 }
@@ -70,12 +88,16 @@ impl BehaviorCycler {
             control::motion::look_around::CreationContext::new(),
         )
         .wrap_err("failed to create node `LookAround`")?;
+        let obstacle_filter = ObstacleFilter::new(obstacle_filter::CreationContext {})
+            .wrap_err("failed to create node `ObstacleFilter`")?;
         let role_assignment = RoleAssignment::new(role_assignment::CreationContext::new())
             .wrap_err("failed to create node `RoleAssignment`")?;
         let rule_obstacle_composer = control::rule_obstacle_composer::RuleObstacleComposer::new(
             control::rule_obstacle_composer::CreationContext {},
         )
         .wrap_err("failed to create node `RuleObstacleComposer`")?;
+        let team_ball_filter = TeamBallFilter::new(team_ball_filter::CreationContext {})
+            .wrap_err("failed to create node `TeamBallFilter`")?;
         let world_state_composer =
             WorldStateComposer::new(world_state_composer::CreationContext::new())
                 .wrap_err("failed to create node `WorldStateComposer`")?;
@@ -89,8 +111,10 @@ impl BehaviorCycler {
             behavior,
             kick_selector,
             look_around,
+            obstacle_filter,
             role_assignment,
             rule_obstacle_composer,
+            team_ball_filter,
             world_state_composer,
         })
     }
@@ -100,6 +124,7 @@ impl BehaviorCycler {
         own_database: &mut Database,
         cycler_state: &mut CyclerState,
         parameters: &Parameters,
+        obstacle_inputs: ObstacleInputs,
         incoming_messages: BTreeMap<SystemTime, Vec<&IncomingMessage>>,
     ) -> Result<()> {
         if own_database
@@ -126,16 +151,43 @@ impl BehaviorCycler {
         } else {
             own_database.main_outputs.rule_obstacles = Default::default();
         }
+        {
+            let main_outputs = self
+                .team_ball_filter
+                .cycle(team_ball_filter::CycleContext::new(
+                    own_database.main_outputs.ball_event.as_ref(),
+                    own_database.main_outputs.ball_position.as_ref(),
+                    &own_database.main_outputs.cycle_time,
+                    own_database
+                        .main_outputs
+                        .filtered_game_controller_state
+                        .as_ref(),
+                    own_database.main_outputs.robot_to_field.as_ref(),
+                    PerceptionInput {
+                        persistent: incoming_messages.clone(),
+                        temporary: Default::default(),
+                    },
+                    &parameters.player_number,
+                    &parameters.team_ball_filter,
+                ))
+                .wrap_err("failed to execute cycle of node `TeamBallFilter`")?;
+            own_database.main_outputs.fused_team_ball = main_outputs.fused_team_ball.value;
+        }
         {
             let main_outputs = self
                 .role_assignment
                 .cycle(role_assignment::CycleContext::new(
+                    AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.message_budget,
+                    ),
                     own_database.main_outputs.ball_position.as_ref(),
                     &own_database.main_outputs.fall_state,
                     own_database
                         .main_outputs
                         .filtered_game_controller_state
                         .as_ref(),
+                    own_database.main_outputs.fused_team_ball.as_ref(),
                     &own_database.main_outputs.primary_state,
                     own_database.main_outputs.robot_to_field.as_ref(),
                     &own_database.main_outputs.cycle_time,
@@ -144,6 +196,7 @@ impl BehaviorCycler {
                         temporary: Default::default(),
                     },
                     &mut cycler_state.time_to_reach_kick_position,
+                    &mut cycler_state.intention,
                     &parameters.field_dimensions,
                     parameters.role_assignment.forced_role.as_ref(),
                     &parameters
@@ -156,10 +209,64 @@ impl BehaviorCycler {
                     &self.hardware_interface,
                 ))
                 .wrap_err("failed to execute cycle of node `RoleAssignment`")?;
-            own_database.main_outputs.team_ball = main_outputs.team_ball.value;
             own_database.main_outputs.network_robot_obstacles =
                 main_outputs.network_robot_obstacles.value;
             own_database.main_outputs.role = main_outputs.role.value;
+            own_database.main_outputs.teammate_intentions = main_outputs.teammate_intentions.value;
+        }
+        {
+            let now = own_database.main_outputs.cycle_time.start_time;
+            let no_sonar_obstacles = Vec::new();
+            let mut obstacle_filter_hypotheses = None;
+            let main_outputs = self
+                .obstacle_filter
+                .cycle(obstacle_filter::CycleContext::new(
+                    AdditionalOutput::new(false, &mut obstacle_filter_hypotheses),
+                    BTreeMap::from([(
+                        now,
+                        Some(&obstacle_inputs.current_odometry_to_last_odometry),
+                    )])
+                    .into(),
+                    BTreeMap::from([(now, &own_database.main_outputs.network_robot_obstacles)])
+                        .into(),
+                    BTreeMap::from([(now, own_database.main_outputs.robot_to_field.as_ref())])
+                        .into(),
+                    BTreeMap::from([(now, &no_sonar_obstacles)]).into(),
+                    &own_database.main_outputs.cycle_time,
+                    &own_database.main_outputs.primary_state,
+                    &parameters.field_dimensions,
+                    &obstacle_inputs.parameters.goal_post_obstacle_radius,
+                    obstacle_inputs.parameters,
+                    &obstacle_inputs
+                        .parameters
+                        .robot_obstacle_radius_at_foot_height,
+                    &obstacle_inputs
+                        .parameters
+                        .robot_obstacle_radius_at_hip_height,
+                    &obstacle_inputs.parameters.unknown_obstacle_radius,
+                    PerceptionInput {
+                        persistent: BTreeMap::from([(now, Vec::new())]),
+                        temporary: Default::default(),
+                    },
+                    PerceptionInput {
+                        persistent: BTreeMap::from([(now, Vec::new())]),
+                        temporary: Default::default(),
+                    },
+                    PerceptionInput {
+                        persistent: BTreeMap::from([(now, Vec::new())]),
+                        temporary: Default::default(),
+                    },
+                    PerceptionInput {
+                        persistent: BTreeMap::from([(now, vec![obstacle_inputs.detected_robots])]),
+                        temporary: Default::default(),
+                    },
+                ))
+                .wrap_err("failed to execute cycle of node `ObstacleFilter`")?;
+            own_database.main_outputs.obstacles = main_outputs.obstacles.value;
+            own_database
+                .main_outputs
+                .obstacles
+                .extend_from_slice(obstacle_inputs.scripted_obstacles);
         }
         {
             let main_outputs = self
@@ -167,9 +274,10 @@ impl BehaviorCycler {
                 .cycle(ball_state_composer::CycleContext::new(
                     &own_database.main_outputs.cycle_time,
                     own_database.main_outputs.ball_position.as_ref(),
+                    own_database.main_outputs.ball_trajectory.as_ref(),
                     own_database.main_outputs.penalty_shot_direction.as_ref(),
                     own_database.main_outputs.robot_to_field.as_ref(),
-                    own_database.main_outputs.team_ball.as_ref(),
+                    own_database.main_outputs.fused_team_ball.as_ref(),
                     &own_database.main_outputs.primary_state,
                     own_database
                         .main_outputs
@@ -244,7 +352,9 @@ impl BehaviorCycler {
                 .world_state_composer
                 .cycle(world_state_composer::CycleContext::new(
                     own_database.main_outputs.ball_state.as_ref(),
+                    own_database.main_outputs.ball_event.as_ref(),
                     own_database.main_outputs.rule_ball_state.as_ref(),
+                    own_database.main_outputs.fused_team_ball.as_ref(),
                     own_database
                         .main_outputs
                         .filtered_game_controller_state
@@ -280,17 +390,22 @@ impl BehaviorCycler {
                     &true,
                     &own_database.main_outputs.world_state,
                     &own_database.main_outputs.cycle_time,
+                    own_database.main_outputs.calibration_command.as_ref(),
+                    &mut cycler_state.intention,
                     &parameters.behavior,
                     &parameters.in_walk_kicks,
                     &parameters.field_dimensions,
                     &parameters.behavior.lost_ball,
                     &parameters.behavior.intercept_ball,
                     &parameters.step_planner.max_step_size,
+                    &parameters.walking_engine.base_step_duration,
                     &parameters.behavior.role_positions.striker_set_position,
                 ))
                 .wrap_err("failed to execute cycle of node `Behavior`")?;
             own_database.main_outputs.motion_command = main_outputs.motion_command.value;
             own_database.main_outputs.dribble_path = main_outputs.dribble_path.value;
+            own_database.main_outputs.dribble_arrival_time =
+                main_outputs.dribble_arrival_time.value;
         }
         {
             let main_outputs = {
@@ -312,7 +427,7 @@ impl BehaviorCycler {
             let _main_outputs = self
                 .time_to_reach_kick_position
                 .cycle(control::time_to_reach_kick_position::CycleContext::new(
-                    own_database.main_outputs.dribble_path.as_ref(),
+                    own_database.main_outputs.dribble_arrival_time.as_ref(),
                     framework::AdditionalOutput::new(
                         true,
                         &mut own_database
@@ -320,7 +435,6 @@ impl BehaviorCycler {
                             .time_to_reach_kick_position_output,
                     ),
                     &mut cycler_state.time_to_reach_kick_position,
-                    &parameters.behavior,
                     own_database
                         .main_outputs
                         .stand_up_back_estimated_remaining_duration
@@ -1,7 +1,13 @@
 use hardware::{NetworkInterface, RecordingInterface, TimeInterface};
 
+pub mod assertions;
+pub mod batch;
 pub mod cycler;
 pub mod interfake;
+pub mod opponent;
+pub mod perception;
+pub mod physics;
+pub mod report;
 pub mod robot;
 pub mod server;
 pub mod simulator;
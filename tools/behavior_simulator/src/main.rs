@@ -1,4 +1,4 @@
-use std::{io::stdout, path::PathBuf, time::Instant};
+use std::{io::stdout, path::PathBuf, thread::available_parallelism, time::Instant};
 
 use chrono::Local;
 use clap::Parser;
@@ -7,17 +7,45 @@ use fern::{Dispatch, InitError};
 use log::LevelFilter;
 use tokio_util::sync::CancellationToken;
 
-use behavior_simulator::{server, simulator::Simulator};
+use behavior_simulator::{
+    batch::{
+        collect_scenario_files, parameter_sets, read_sweep, run_in_parallel, write_summaries, Run,
+    },
+    server,
+    simulator::Simulator,
+};
 
 #[derive(Parser)]
 enum Arguments {
     Run(RunArguments),
     Serve(ServeArguments),
+    Batch(BatchArguments),
 }
 
 #[derive(Parser)]
 struct RunArguments {
     scenario_file: PathBuf,
+    /// Write a JSON report of the scenario to this file
+    #[arg(long)]
+    report: Option<PathBuf>,
+}
+
+/// Runs scenarios headless in parallel and writes a summary.json and summary.csv
+#[derive(Parser)]
+struct BatchArguments {
+    /// A lua scenario or a directory of lua scenarios
+    scenarios: PathBuf,
+    /// JSON object mapping parameter paths to arrays of values, every combination is simulated
+    #[arg(long)]
+    sweep: Option<PathBuf>,
+    /// Number of runs of each scenario and parameter set, each with a different random seed
+    #[arg(long, default_value = "1")]
+    repetitions: u64,
+    /// Number of simulations to run in parallel, defaults to the number of CPUs
+    #[arg(short, long)]
+    jobs: Option<usize>,
+    #[arg(short, long, default_value = "behavior_simulator_results")]
+    output_directory: PathBuf,
 }
 
 #[derive(Parser)]
@@ -56,6 +84,7 @@ fn main() -> Result<()> {
     match arguments {
         Arguments::Run(arguments) => run(arguments),
         Arguments::Serve(arguments) => serve(arguments),
+        Arguments::Batch(arguments) => batch(arguments),
     }
 }
 
@@ -64,11 +93,53 @@ fn run(arguments: RunArguments) -> Result<()> {
     simulator.execute_script(arguments.scenario_file)?;
 
     let start = Instant::now();
-    simulator.run().wrap_err("failed to run simulation")?;
+    let result = simulator.run();
     let duration = Instant::now() - start;
     println!("Took {:.2} seconds", duration.as_secs_f32());
 
-    Ok(())
+    if let Some(report_file) = arguments.report {
+        simulator.report().write_to_file(report_file)?;
+    }
+    result.wrap_err("failed to run simulation")
+}
+
+fn batch(arguments: BatchArguments) -> Result<()> {
+    let scenario_files = collect_scenario_files(&arguments.scenarios)?;
+    let sweep = match &arguments.sweep {
+        Some(path) => read_sweep(path)?,
+        None => Default::default(),
+    };
+    let parameter_sets = parameter_sets(&sweep);
+    let runs: Vec<_> = scenario_files
+        .iter()
+        .flat_map(|scenario_file| {
+            parameter_sets.iter().enumerate().flat_map(
+                move |(parameter_set, parameter_overrides)| {
+                    (0..arguments.repetitions).map(move |repetition| Run {
+                        scenario_file: scenario_file.clone(),
+                        parameter_set,
+                        parameter_overrides: parameter_overrides.clone(),
+                        repetition,
+                    })
+                },
+            )
+        })
+        .collect();
+    let number_of_jobs = match arguments.jobs {
+        Some(jobs) => jobs,
+        None => available_parallelism()?.get(),
+    };
+
+    let start = Instant::now();
+    let summaries = run_in_parallel(runs, number_of_jobs)?;
+    println!(
+        "{} of {} runs succeeded, took {:.2} seconds",
+        summaries.iter().filter(|summary| summary.succeeded).count(),
+        summaries.len(),
+        start.elapsed().as_secs_f32()
+    );
+
+    write_summaries(&summaries, &arguments.output_directory)
 }
 
 fn serve(arguments: ServeArguments) -> Result<()> {
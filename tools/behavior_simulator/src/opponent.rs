@@ -0,0 +1,59 @@
+use nalgebra::Point2;
+use serde::{Deserialize, Serialize};
+
+/// A robot of the opposing team, scripted from lua via `state.opponents`
+///
+/// ```lua
+/// table.insert(state.opponents, {
+///     position = { 1.0, 0.0 },
+///     behavior = { ChaseBall = { speed = 0.2 } },
+/// })
+/// ```
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Opponent {
+    pub position: Point2<f32>,
+    #[serde(default)]
+    pub behavior: OpponentBehavior,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum OpponentBehavior {
+    #[default]
+    Static,
+    /// Walks along the waypoints in a loop
+    Waypoints {
+        waypoints: Vec<Point2<f32>>,
+        speed: f32,
+        #[serde(default)]
+        next_waypoint: usize,
+    },
+    /// Walks straight towards the ball
+    ChaseBall { speed: f32 },
+}
+
+impl Opponent {
+    pub fn step(&mut self, ball_position: Option<Point2<f32>>, time_step: f32) {
+        let (target, speed) = match &mut self.behavior {
+            OpponentBehavior::Static => return,
+            OpponentBehavior::Waypoints {
+                waypoints,
+                speed,
+                next_waypoint,
+            } => {
+                if waypoints.is_empty() {
+                    return;
+                }
+                *next_waypoint %= waypoints.len();
+                if (waypoints[*next_waypoint] - self.position).norm() < *speed * time_step {
+                    *next_waypoint = (*next_waypoint + 1) % waypoints.len();
+                }
+                (waypoints[*next_waypoint], *speed)
+            }
+            OpponentBehavior::ChaseBall { speed } => match ball_position {
+                Some(ball_position) => (ball_position, *speed),
+                None => return,
+            },
+        };
+        self.position += (target - self.position).cap_magnitude(speed * time_step);
+    }
+}
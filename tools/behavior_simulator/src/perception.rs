@@ -0,0 +1,132 @@
+use nalgebra::{vector, Isometry2, Point2, UnitComplex, Vector2};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use serde::{Deserialize, Serialize};
+
+use crate::robot::Robot;
+
+/// Tunables of the simulated perception, exposed to lua as `state.perception`
+///
+/// All noise and dropouts are disabled by default, the field of view is always limited.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PerceptionParameters {
+    /// Maximum distance at which balls and robots are detected
+    pub maximum_detection_distance: f32,
+    /// Height of both cameras above the ground
+    pub camera_height: f32,
+    /// Pitch of the top camera relative to the head, positive is looking down
+    pub top_camera_pitch: f32,
+    /// Pitch of the bottom camera relative to the head, positive is looking down
+    pub bottom_camera_pitch: f32,
+    /// Probability that a visible ball is not detected in a cycle
+    pub ball_dropout_probability: f32,
+    /// Standard deviation of the detected ball position
+    pub ball_position_noise: f32,
+    /// Additional standard deviation of the detected ball position per meter of distance
+    pub ball_position_noise_distance_factor: f32,
+    /// Probability that the pose a robot believes to be at is not updated in a cycle
+    pub pose_dropout_probability: f32,
+    /// Standard deviation of the translation of the pose a robot believes to be at
+    pub pose_translation_noise: f32,
+    /// Standard deviation of the rotation of the pose a robot believes to be at
+    pub pose_rotation_noise: f32,
+    /// Probability that an SPL message is not received by a teammate
+    pub message_drop_probability: f32,
+    /// SPL messages are delayed uniformly up to this many seconds
+    pub maximum_message_delay: f32,
+}
+
+impl Default for PerceptionParameters {
+    fn default() -> Self {
+        Self {
+            maximum_detection_distance: 3.0,
+            camera_height: 0.5,
+            top_camera_pitch: 0.02,
+            bottom_camera_pitch: 0.69,
+            ball_dropout_probability: 0.0,
+            ball_position_noise: 0.0,
+            ball_position_noise_distance_factor: 0.0,
+            pose_dropout_probability: 0.0,
+            pose_translation_noise: 0.0,
+            pose_rotation_noise: 0.0,
+            message_drop_probability: 0.0,
+            maximum_message_delay: 0.0,
+        }
+    }
+}
+
+/// Whether `position` (in field coordinates) is inside the frustum of the top or bottom camera
+pub fn is_visible(
+    robot: &Robot,
+    robot_to_field: Isometry2<f32>,
+    position: Point2<f32>,
+    parameters: &PerceptionParameters,
+) -> bool {
+    let position_in_ground = robot_to_field.inverse() * position;
+    let head = &robot.database.main_outputs.sensor_data.positions.head;
+    let position_in_head = UnitComplex::from_angle(head.yaw).inverse() * position_in_ground;
+    let distance = position_in_head.coords.norm();
+    let field_of_view = robot.field_of_view();
+    if distance >= parameters.maximum_detection_distance
+        || position_in_head.coords.angle(&Vector2::x_axis()) >= field_of_view.x / 2.0
+    {
+        return false;
+    }
+
+    let angle_below_horizon = parameters.camera_height.atan2(distance);
+    [parameters.top_camera_pitch, parameters.bottom_camera_pitch]
+        .iter()
+        .any(|camera_pitch| {
+            (angle_below_horizon - head.pitch - camera_pitch).abs() < field_of_view.y / 2.0
+        })
+}
+
+/// Noisy detection of `position` relative to the robot, `None` if the detection dropped out
+pub fn detect_ball(
+    position_in_ground: Point2<f32>,
+    parameters: &PerceptionParameters,
+    random_number_generator: &mut impl Rng,
+) -> Option<Point2<f32>> {
+    if random_number_generator.gen_bool(parameters.ball_dropout_probability.clamp(0.0, 1.0) as f64)
+    {
+        return None;
+    }
+    let standard_deviation = parameters.ball_position_noise
+        + parameters.ball_position_noise_distance_factor * position_in_ground.coords.norm();
+    Some(
+        position_in_ground
+            + vector![
+                sample_normal(standard_deviation, random_number_generator),
+                sample_normal(standard_deviation, random_number_generator)
+            ],
+    )
+}
+
+/// Pose the robot believes to be at, `None` if the localization does not update in this cycle
+pub fn perceive_pose(
+    robot_to_field: Isometry2<f32>,
+    parameters: &PerceptionParameters,
+    random_number_generator: &mut impl Rng,
+) -> Option<Isometry2<f32>> {
+    if random_number_generator.gen_bool(parameters.pose_dropout_probability.clamp(0.0, 1.0) as f64)
+    {
+        return None;
+    }
+    Some(
+        robot_to_field
+            * Isometry2::new(
+                vector![
+                    sample_normal(parameters.pose_translation_noise, random_number_generator),
+                    sample_normal(parameters.pose_translation_noise, random_number_generator)
+                ],
+                sample_normal(parameters.pose_rotation_noise, random_number_generator),
+            ),
+    )
+}
+
+fn sample_normal(standard_deviation: f32, random_number_generator: &mut impl Rng) -> f32 {
+    if standard_deviation <= 0.0 {
+        return 0.0;
+    }
+    standard_deviation * random_number_generator.sample::<f32, _>(StandardNormal)
+}
@@ -0,0 +1,250 @@
+use nalgebra::{point, vector, Point2, Vector2};
+use serde::{Deserialize, Serialize};
+use types::{
+    field_dimensions::FieldDimensions, motion_command::KickVariant,
+    parameters::InWalkKicksParameters, support_foot::Side,
+};
+
+use crate::state::Ball;
+
+/// Tunables of the simulated physics, exposed to lua as `state.physics`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PhysicsParameters {
+    /// Radius of the circle approximating a robot in collisions with other robots
+    pub robot_radius: f32,
+    /// Distance between the centers of a robot and the ball at which the robot pushes the ball
+    ///
+    /// Smaller than the robot radius plus the ball radius, otherwise robots would already push
+    /// the ball while approaching their kick poses.
+    pub ball_contact_distance: f32,
+    /// Constant deceleration of a rolling ball in m/s^2
+    pub ball_deceleration: f32,
+    /// Fraction of the normal velocity kept when the ball bounces off robots and goal posts
+    pub ball_restitution: f32,
+    /// Fraction of the normal velocity kept when the ball bounces off the field boundary
+    pub boundary_restitution: f32,
+    /// Maximum distance from the robot center to the ball to execute a kick
+    pub kick_reach: f32,
+    /// Minimum time in seconds between two kicks of the same robot
+    pub kick_cooldown: f32,
+}
+
+impl Default for PhysicsParameters {
+    fn default() -> Self {
+        Self {
+            robot_radius: 0.15,
+            ball_contact_distance: 0.17,
+            ball_deceleration: 0.8,
+            ball_restitution: 0.5,
+            boundary_restitution: 0.3,
+            kick_reach: 0.4,
+            kick_cooldown: 1.0,
+        }
+    }
+}
+
+/// Ball velocity in robot coordinates after a kick
+///
+/// The speed is chosen such that the decelerating ball rolls the shot distance of the kick scaled
+/// by its strength.
+pub fn kick_velocity(
+    kick: KickVariant,
+    kicking_side: Side,
+    strength: f32,
+    in_walk_kicks: &InWalkKicksParameters,
+    ball_deceleration: f32,
+) -> Vector2<f32> {
+    let side = match kicking_side {
+        Side::Left => 1.0,
+        Side::Right => -1.0,
+    };
+    let direction = match kick {
+        KickVariant::Forward => vector![1.0, 0.0],
+        KickVariant::Turn => vector![0.707, 0.707 * side],
+        KickVariant::Side => vector![0.0, 1.0 * -side],
+    };
+    let distance = in_walk_kicks[kick].shot_distance * strength.max(0.0);
+    direction * (2.0 * ball_deceleration * distance).sqrt()
+}
+
+pub fn apply_rolling_friction(ball: &mut Ball, deceleration: f32, time_step: f32) {
+    let speed = ball.velocity.norm();
+    if speed <= f32::EPSILON {
+        ball.velocity = Vector2::zeros();
+        return;
+    }
+    let decelerated_speed = (speed - deceleration * time_step).max(0.0);
+    ball.velocity *= decelerated_speed / speed;
+}
+
+/// Pushes the ball out of a (possibly moving) circle and reflects its velocity
+///
+/// Returns whether the ball touched the circle.
+pub fn collide_ball_with_circle(
+    ball: &mut Ball,
+    center: Point2<f32>,
+    circle_velocity: Vector2<f32>,
+    contact_distance: f32,
+    restitution: f32,
+) -> bool {
+    let difference = ball.position - center;
+    let distance = difference.norm();
+    if distance >= contact_distance {
+        return false;
+    }
+    let normal = if distance > f32::EPSILON {
+        difference / distance
+    } else {
+        circle_velocity
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector2::x)
+    };
+    ball.position = center + normal * contact_distance;
+    let normal_speed = (ball.velocity - circle_velocity).dot(&normal);
+    if normal_speed < 0.0 {
+        ball.velocity -= (1.0 + restitution) * normal_speed * normal;
+    }
+    true
+}
+
+pub fn goal_post_positions(field_dimensions: &FieldDimensions) -> [Point2<f32>; 4] {
+    let x = field_dimensions.length / 2.0;
+    let y = (field_dimensions.goal_inner_width + field_dimensions.goal_post_diameter) / 2.0;
+    [point![x, y], point![x, -y], point![-x, y], point![-x, -y]]
+}
+
+/// Keeps the ball on the carpet including the border strip and inside the goal nets
+pub fn collide_ball_with_boundaries(
+    ball: &mut Ball,
+    field_dimensions: &FieldDimensions,
+    restitution: f32,
+) {
+    let is_inside_goal = ball.position.y.abs()
+        < field_dimensions.goal_inner_width / 2.0 - field_dimensions.ball_radius
+        && ball.position.x.abs() < field_dimensions.length / 2.0 + field_dimensions.goal_depth;
+    let maximum_x = if is_inside_goal {
+        field_dimensions.length / 2.0 + field_dimensions.goal_depth
+    } else {
+        field_dimensions.length / 2.0 + field_dimensions.border_strip_width
+    } - field_dimensions.ball_radius;
+    let maximum_y = field_dimensions.width / 2.0 + field_dimensions.border_strip_width
+        - field_dimensions.ball_radius;
+
+    if ball.position.x.abs() > maximum_x {
+        ball.position.x = maximum_x.copysign(ball.position.x);
+        if ball.velocity.x * ball.position.x > 0.0 {
+            ball.velocity.x *= -restitution;
+        }
+    }
+    if ball.position.y.abs() > maximum_y {
+        ball.position.y = maximum_y.copysign(ball.position.y);
+        if ball.velocity.y * ball.position.y > 0.0 {
+            ball.velocity.y *= -restitution;
+        }
+    }
+}
+
+pub fn is_ball_in_goal(ball: &Ball, field_dimensions: &FieldDimensions) -> bool {
+    ball.position.x.abs() > field_dimensions.length / 2.0 + field_dimensions.ball_radius
+        && ball.position.y.abs() < field_dimensions.goal_inner_width / 2.0
+}
+
+/// Moves overlapping robots apart, each by half of the overlap
+///
+/// Returns the indices of all pairs of robots that touched each other.
+pub fn separate_robots(positions: &mut [Point2<f32>], robot_radius: f32) -> Vec<(usize, usize)> {
+    let minimum_distance = 2.0 * robot_radius;
+    let mut touching_pairs = Vec::new();
+    for first in 0..positions.len() {
+        for second in first + 1..positions.len() {
+            let difference = positions[second] - positions[first];
+            let distance = difference.norm();
+            if distance >= minimum_distance {
+                continue;
+            }
+            let direction = difference
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector2::x);
+            let correction = direction * (minimum_distance - distance) / 2.0;
+            positions[first] -= correction;
+            positions[second] += correction;
+            touching_pairs.push((first, second));
+        }
+    }
+    touching_pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use types::parameters::InWalkKickInfoParameters;
+
+    use super::*;
+
+    #[test]
+    fn rolling_ball_decelerates_to_rest() {
+        let mut ball = Ball {
+            position: Point2::origin(),
+            velocity: vector![1.2, 1.6],
+        };
+
+        for _ in 0..10 {
+            apply_rolling_friction(&mut ball, 0.8, 0.125);
+        }
+        assert_relative_eq!(ball.velocity, vector![0.6, 0.8], epsilon = 1e-5);
+
+        for _ in 0..20 {
+            apply_rolling_friction(&mut ball, 0.8, 0.125);
+        }
+        assert_eq!(ball.velocity, Vector2::zeros());
+    }
+
+    #[test]
+    fn ball_bounces_off_circle_with_restitution() {
+        let mut ball = Ball {
+            position: point![0.1, 0.0],
+            velocity: vector![-1.0, 0.5],
+        };
+
+        let touched =
+            collide_ball_with_circle(&mut ball, Point2::origin(), Vector2::zeros(), 0.17, 0.5);
+
+        assert!(touched);
+        assert_relative_eq!(ball.position, point![0.17, 0.0]);
+        assert_relative_eq!(ball.velocity, vector![0.5, 0.5]);
+
+        let mut distant_ball = Ball {
+            position: point![1.0, 0.0],
+            velocity: vector![-1.0, 0.0],
+        };
+        assert!(!collide_ball_with_circle(
+            &mut distant_ball,
+            Point2::origin(),
+            Vector2::zeros(),
+            0.17,
+            0.5
+        ));
+        assert_eq!(distant_ball.velocity, vector![-1.0, 0.0]);
+    }
+
+    #[test]
+    fn kicked_ball_rolls_the_shot_distance() {
+        let kick = InWalkKickInfoParameters {
+            shot_distance: 2.0,
+            ..Default::default()
+        };
+        let in_walk_kicks = InWalkKicksParameters {
+            forward: kick.clone(),
+            turn: kick.clone(),
+            side: kick,
+        };
+
+        let forward = kick_velocity(KickVariant::Forward, Side::Left, 1.0, &in_walk_kicks, 0.8);
+        let side = kick_velocity(KickVariant::Side, Side::Left, 0.5, &in_walk_kicks, 0.8);
+
+        assert_relative_eq!(forward, vector![3.2_f32.sqrt(), 0.0]);
+        assert_relative_eq!(side, vector![0.0, -(1.6_f32.sqrt())]);
+        let rolled_distance = forward.norm_squared() / (2.0 * 0.8);
+        assert_relative_eq!(rolled_distance, 2.0, epsilon = 1e-5);
+    }
+}
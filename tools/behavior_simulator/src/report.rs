@@ -0,0 +1,119 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::BufWriter,
+    path::Path,
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use nalgebra::Point2;
+use serde::Serialize;
+use types::roles::Role;
+
+/// Machine-readable summary of a simulated scenario
+///
+/// Times are given in seconds of simulated time since the start of the scenario.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ScenarioReport {
+    pub scenario: String,
+    pub cycle_count: usize,
+    pub simulated_time: f32,
+    pub wall_time: f32,
+    pub goals: Vec<Goal>,
+    pub collisions: Vec<Collision>,
+    pub role_history: BTreeMap<usize, Vec<RoleChange>>,
+    pub failed_assertions: Vec<FailedAssertion>,
+    #[serde(skip)]
+    ball_was_in_goal: bool,
+    #[serde(skip)]
+    colliding_pairs: BTreeSet<(usize, usize)>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Goal {
+    pub time: f32,
+    pub ball_position: Point2<f32>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Collision {
+    pub time: f32,
+    pub players: (usize, usize),
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RoleChange {
+    pub time: f32,
+    pub role: Role,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FailedAssertion {
+    pub time: f32,
+    pub message: String,
+}
+
+impl ScenarioReport {
+    pub fn new(scenario: impl Into<String>) -> Self {
+        Self {
+            scenario: scenario.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Records a goal only when the ball entered the goal, not for every cycle it stays inside
+    pub fn record_ball(&mut self, time: f32, ball_position: Option<Point2<f32>>, is_in_goal: bool) {
+        if is_in_goal && !self.ball_was_in_goal {
+            if let Some(ball_position) = ball_position {
+                self.goals.push(Goal {
+                    time,
+                    ball_position,
+                });
+            }
+        }
+        self.ball_was_in_goal = is_in_goal;
+    }
+
+    /// Records the pairs of robots touching each other in this cycle
+    ///
+    /// Pairs staying in contact are only recorded once.
+    pub fn record_collisions(&mut self, time: f32, touching_pairs: &[(usize, usize)]) {
+        let colliding_pairs: BTreeSet<_> = touching_pairs
+            .iter()
+            .map(|&(first, second)| (first.min(second), first.max(second)))
+            .collect();
+        for &players in colliding_pairs.difference(&self.colliding_pairs) {
+            self.collisions.push(Collision { time, players });
+        }
+        self.colliding_pairs = colliding_pairs;
+    }
+
+    pub fn record_role(&mut self, time: f32, player_number: usize, role: Role) {
+        let history = self.role_history.entry(player_number).or_default();
+        if history.last().map(|change| change.role) != Some(role) {
+            history.push(RoleChange { time, role });
+        }
+    }
+
+    pub fn record_failed_assertion(&mut self, time: f32, message: impl Into<String>) {
+        self.failed_assertions.push(FailedAssertion {
+            time,
+            message: message.into(),
+        });
+    }
+
+    pub fn roles_of(&self, player_number: usize) -> impl Iterator<Item = Role> + '_ {
+        self.role_history
+            .get(&player_number)
+            .into_iter()
+            .flatten()
+            .map(|change| change.role)
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path).wrap_err_with(|| format!("failed to create {path:?}"))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)
+            .wrap_err_with(|| format!("failed to write report to {path:?}"))
+    }
+}
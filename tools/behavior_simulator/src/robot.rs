@@ -7,17 +7,27 @@ use std::{
 
 use color_eyre::{eyre::WrapErr, Result};
 use control::localization::generate_initial_pose;
-use nalgebra::vector;
+use nalgebra::{vector, Isometry2, Vector2};
 use parameters::directory::deserialize;
+use serde::Deserialize;
+use serialize_hierarchy::SerializeHierarchy;
 use spl_network_messages::PlayerNumber;
-use types::{camera_matrix::CameraMatrix, messages::IncomingMessage};
+use types::{
+    camera_matrix::CameraMatrix, detected_robots::DetectedRobots, messages::IncomingMessage,
+    obstacles::Obstacle, parameters::ObstacleFilterParameters,
+};
 
 use crate::{
-    cycler::{BehaviorCycler, Database},
+    cycler::{BehaviorCycler, Database, ObstacleInputs},
     interfake::Interfake,
+    state::Ball,
     structs::{control::CyclerState, Parameters},
 };
 
+/// Parameter values by path, e.g. `role_positions.striker_supporter_distance_to_ball`, replacing
+/// the values from the parameter files
+pub type ParameterOverrides = BTreeMap<String, serde_json::Value>;
+
 pub struct Robot {
     pub interface: Arc<Interfake>,
     pub cycler: BehaviorCycler,
@@ -27,35 +37,63 @@ pub struct Robot {
     pub is_penalized: bool,
     pub last_kick_time: Duration,
     pub ball_last_seen: Option<SystemTime>,
+    /// Ball in field coordinates as it was detected at `ball_last_seen`
+    pub perceived_ball: Option<Ball>,
+    /// Pose the robot believes to be at, kept while the localization does not update
+    pub perceived_robot_to_field: Isometry2<f32>,
+    /// Obstacles created by the scenario script, in robot coordinates
+    pub scripted_obstacles: Vec<Obstacle>,
+    pub obstacle_filter_parameters: ObstacleFilterParameters,
+    /// Opponents detected in the current cycle, in robot coordinates
+    pub detected_robots: DetectedRobots,
+    /// Movement of the robot in the current cycle
+    pub current_odometry_to_last_odometry: Isometry2<f32>,
+}
+
+/// Parameters of nodes which are run by hand instead of being part of the simulator target
+#[derive(Deserialize)]
+struct AdditionalParameters {
+    obstacle_filter: ObstacleFilterParameters,
 }
 
 impl Robot {
-    pub fn try_new(player_number: PlayerNumber) -> Result<Self> {
+    pub fn try_new(
+        player_number: PlayerNumber,
+        parameter_overrides: &ParameterOverrides,
+    ) -> Result<Self> {
         let interface: Arc<_> = Interfake::default().into();
 
         let runtime = tokio::runtime::Builder::new_current_thread()
             .build()
             .unwrap();
+        let id = format!("behavior_simulator.{}", from_player_number(player_number));
         let mut parameter: Parameters = runtime.block_on(async {
-            deserialize(
-                "etc/parameters",
-                &format!("behavior_simulator.{}", from_player_number(player_number)),
-                &format!("behavior_simulator.{}", from_player_number(player_number)),
-            )
-            .await
-            .wrap_err("could not load initial parameters")
+            deserialize("etc/parameters", &id, &id)
+                .await
+                .wrap_err("could not load initial parameters")
+        })?;
+        let additional_parameters: AdditionalParameters = runtime.block_on(async {
+            deserialize("etc/parameters", &id, &id)
+                .await
+                .wrap_err("could not load additional parameters")
         })?;
         parameter.player_number = player_number;
+        for (path, value) in parameter_overrides {
+            parameter
+                .deserialize_path(path, value.clone())
+                .wrap_err_with(|| format!("failed to override parameter {path}"))?;
+        }
 
         let cycler = BehaviorCycler::new(interface.clone(), Default::default(), &parameter)
             .wrap_err("failed to create cycler")?;
 
         let mut database = Database::default();
 
-        database.main_outputs.robot_to_field = Some(generate_initial_pose(
+        let initial_pose = generate_initial_pose(
             &parameter.localization.initial_poses[player_number],
             &parameter.field_dimensions,
-        ));
+        );
+        database.main_outputs.robot_to_field = Some(initial_pose);
 
         let cycler_state = Default::default();
 
@@ -68,6 +106,12 @@ impl Robot {
             is_penalized: false,
             last_kick_time: Duration::default(),
             ball_last_seen: None,
+            perceived_ball: None,
+            perceived_robot_to_field: initial_pose,
+            scripted_obstacles: Vec::new(),
+            obstacle_filter_parameters: additional_parameters.obstacle_filter,
+            detected_robots: DetectedRobots::default(),
+            current_odometry_to_last_odometry: Isometry2::identity(),
         })
     }
 
@@ -76,11 +120,18 @@ impl Robot {
             &mut self.database,
             &mut self.cycler_state,
             &self.parameters,
+            ObstacleInputs {
+                parameters: &self.obstacle_filter_parameters,
+                current_odometry_to_last_odometry: self.current_odometry_to_last_odometry,
+                detected_robots: &self.detected_robots,
+                scripted_obstacles: &self.scripted_obstacles,
+            },
             messages,
         )
     }
 
-    pub fn field_of_view(&self) -> f32 {
+    /// Horizontal and vertical field of view of the top camera
+    pub fn field_of_view(&self) -> Vector2<f32> {
         let image_size = vector![640.0, 480.0];
         let focal_lengths = self
             .parameters
@@ -88,9 +139,7 @@ impl Robot {
             .vision_top
             .focal_lengths;
         let focal_lengths_scaled = image_size.component_mul(&focal_lengths);
-        let field_of_view = CameraMatrix::calculate_field_of_view(focal_lengths_scaled, image_size);
-
-        field_of_view.x
+        CameraMatrix::calculate_field_of_view(focal_lengths_scaled, image_size)
     }
 }
 
@@ -1,11 +1,22 @@
-use std::{fs::read_to_string, path::Path, sync::Arc, time::Duration};
+use std::{
+    fs::read_to_string,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::{cycler::Database, robot::to_player_number, state::Ball};
+use crate::{
+    assertions::{register_assertions, robot_positions, Expectations},
+    cycler::Database,
+    report::ScenarioReport,
+    robot::{from_player_number, to_player_number, ParameterOverrides},
+    state::Ball,
+};
 use color_eyre::{
-    eyre::{eyre, WrapErr},
+    eyre::{bail, eyre, WrapErr},
     Result,
 };
-use mlua::{Error as LuaError, Function, Lua, LuaSerdeExt, SerializeOptions, Value};
+use mlua::{Error as LuaError, Function, Lua, LuaSerdeExt, SerializeOptions, ToLuaMulti, Value};
 use nalgebra::{Isometry2, Point2, Vector2};
 use parking_lot::Mutex;
 use types::{obstacles::Obstacle, players::Players};
@@ -25,18 +36,32 @@ pub struct Frame {
 pub struct Simulator {
     pub state: Arc<Mutex<State>>,
     pub frames: Vec<Frame>,
+    /// Frames are only needed to inspect the scenario in twix afterwards
+    pub record_frames: bool,
+    report: Arc<Mutex<ScenarioReport>>,
+    expectations: Arc<Mutex<Expectations>>,
+    parameter_overrides: ParameterOverrides,
     lua: Lua,
 }
 
 impl Simulator {
     pub fn try_new() -> Result<Self> {
+        Self::try_new_with_parameter_overrides(Default::default())
+    }
+
+    /// Robots created by the scenario script use `parameter_overrides` on top of their parameters
+    pub fn try_new_with_parameter_overrides(
+        parameter_overrides: ParameterOverrides,
+    ) -> Result<Self> {
         let state = Arc::new(Mutex::new(State::default()));
 
         let lua = Lua::new();
+        let robot_parameter_overrides = parameter_overrides.clone();
         let create_robot = lua
-            .create_function(|lua, player_number: usize| {
+            .create_function(move |lua, player_number: usize| {
                 let player_number = to_player_number(player_number).map_err(LuaError::external)?;
-                let robot = Robot::try_new(player_number).map_err(LuaError::external)?;
+                let robot = Robot::try_new(player_number, &robot_parameter_overrides)
+                    .map_err(LuaError::external)?;
                 Ok(lua.to_value(&LuaRobot::new(&robot)))
             })
             .wrap_err("failed to create function create_robot")?;
@@ -52,10 +77,19 @@ impl Simulator {
             .set("error", error)
             .wrap_err("failed to insert create_robot")?;
 
+        let report = Arc::new(Mutex::new(ScenarioReport::default()));
+        let expectations = Arc::new(Mutex::new(Expectations::default()));
+        register_assertions(&lua, state.clone(), report.clone(), expectations.clone())
+            .wrap_err("failed to register assertions")?;
+
         Ok(Self {
             state,
             lua,
             frames: Vec::new(),
+            record_frames: true,
+            report,
+            expectations,
+            parameter_overrides,
         })
     }
 
@@ -63,14 +97,14 @@ impl Simulator {
         self.serialze_state()?;
 
         let script_text = read_to_string(&file_name)?;
-        let script = self.lua.load(&script_text).set_name(
-            file_name
-                .as_ref()
-                .file_name()
-                .ok_or_else(|| eyre!("path contains no filename"))?
-                .to_str()
-                .ok_or_else(|| eyre!("filename is not valid unicode"))?,
-        )?;
+        let script_name = file_name
+            .as_ref()
+            .file_name()
+            .ok_or_else(|| eyre!("path contains no filename"))?
+            .to_str()
+            .ok_or_else(|| eyre!("filename is not valid unicode"))?;
+        *self.report.lock() = ScenarioReport::new(script_name);
+        let script = self.lua.load(&script_text).set_name(script_name)?;
         script
             .exec()
             .wrap_err("failed to execute scenario script")?;
@@ -78,32 +112,68 @@ impl Simulator {
         self.deserialize_state()
     }
 
+    /// Runs the scenario until it is finished
+    ///
+    /// Fails if any assertion of the scenario failed, the report is available either way.
     pub fn run(&mut self) -> Result<()> {
+        let start = Instant::now();
         loop {
             self.cycle()?;
 
             let state = self.state.lock();
-            let mut robots = Players::<Option<Database>>::default();
-            for (player_number, robot) in &state.robots {
-                robots[*player_number] = Some(robot.database.clone())
+            if self.record_frames {
+                let mut robots = Players::<Option<Database>>::default();
+                for (player_number, robot) in &state.robots {
+                    robots[*player_number] = Some(robot.database.clone())
+                }
+                self.frames.push(Frame {
+                    robots,
+                    ball: state.ball.clone(),
+                });
             }
-            self.frames.push(Frame {
-                robots,
-                ball: state.ball.clone(),
-            });
 
             if state.finished {
                 break;
             }
         }
 
+        let (time_elapsed, cycle_count) = {
+            let state = self.state.lock();
+            (state.time_elapsed.as_secs_f32(), state.cycle_count)
+        };
+        let mut report = self.report.lock();
+        report.cycle_count = cycle_count;
+        report.simulated_time = time_elapsed;
+        report.wall_time = start.elapsed().as_secs_f32();
+        self.expectations.lock().finish(time_elapsed, &mut report);
+
+        if !report.failed_assertions.is_empty() {
+            let failures: Vec<_> = report
+                .failed_assertions
+                .iter()
+                .map(|failure| format!("{:.2}s: {}", failure.time, failure.message))
+                .collect();
+            bail!(
+                "{} assertion(s) failed in {}:\n{}",
+                failures.len(),
+                report.scenario,
+                failures.join("\n")
+            );
+        }
+
         Ok(())
     }
 
+    pub fn report(&self) -> ScenarioReport {
+        self.report.lock().clone()
+    }
+
     pub fn cycle(&mut self) -> Result<()> {
         let events = {
             let mut state = self.state.lock();
-            state.cycle(Duration::from_millis(12))?
+            let events = state.cycle(Duration::from_millis(12))?;
+            self.record(&state, &events);
+            events
         };
 
         self.serialze_state()?;
@@ -186,9 +256,7 @@ impl Simulator {
                             .robots
                             .get_mut(&player_number)
                             .unwrap()
-                            .database
-                            .main_outputs
-                            .obstacles
+                            .scripted_obstacles
                             .push(Obstacle::robot(
                                 robot_to_field.inverse() * position,
                                 radius,
@@ -211,9 +279,7 @@ impl Simulator {
                         .robots
                         .get_mut(&player_number)
                         .unwrap()
-                        .database
-                        .main_outputs
-                        .obstacles
+                        .scripted_obstacles
                         .clear();
 
                     Ok(())
@@ -222,8 +288,12 @@ impl Simulator {
 
             for event in events {
                 match event {
-                    Event::Cycle => self.execute_event_callback("on_cycle")?,
-                    Event::Goal => self.execute_event_callback("on_goal")?,
+                    Event::Cycle => self.execute_event_callback("on_cycle", ())?,
+                    Event::Goal => self.execute_event_callback("on_goal", ())?,
+                    Event::Collision(first, second) => self.execute_event_callback(
+                        "on_collision",
+                        (from_player_number(first), from_player_number(second)),
+                    )?,
                 }
             }
 
@@ -233,9 +303,46 @@ impl Simulator {
         self.deserialize_state()
     }
 
-    fn execute_event_callback(&self, name: &str) -> Result<(), LuaError> {
-        if let Ok(on_goal) = self.lua.globals().get::<_, Function>(name) {
-            on_goal.call(())?;
+    fn record(&self, state: &State, events: &[Event]) {
+        let time = state.time_elapsed.as_secs_f32();
+        let robot_positions = robot_positions(state);
+        let mut report = self.report.lock();
+
+        let ball_is_in_goal = events.iter().any(|event| matches!(event, Event::Goal));
+        report.record_ball(
+            time,
+            state.ball.as_ref().map(|ball| ball.position),
+            ball_is_in_goal,
+        );
+        let touching_pairs: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Collision(first, second) => {
+                    Some((from_player_number(*first), from_player_number(*second)))
+                }
+                _ => None,
+            })
+            .collect();
+        report.record_collisions(time, &touching_pairs);
+        for (player_number, robot) in &state.robots {
+            report.record_role(
+                time,
+                from_player_number(*player_number),
+                robot.database.main_outputs.role,
+            );
+        }
+        self.expectations
+            .lock()
+            .check(time, &robot_positions, &mut report);
+    }
+
+    fn execute_event_callback<'lua>(
+        &'lua self,
+        name: &str,
+        arguments: impl ToLuaMulti<'lua>,
+    ) -> Result<(), LuaError> {
+        if let Ok(callback) = self.lua.globals().get::<_, Function>(name) {
+            callback.call(arguments)?;
         }
 
         Ok(())
@@ -265,7 +372,7 @@ impl Simulator {
             .wrap_err("failed to deserialize state")?;
         self.state
             .lock()
-            .load_lua_state(lua_state)
+            .load_lua_state(lua_state, &self.parameter_overrides)
             .wrap_err("failed to load lua state")
     }
 }
@@ -4,37 +4,46 @@ use std::{
     time::{Duration, UNIX_EPOCH},
 };
 
-use color_eyre::Result;
+use color_eyre::{eyre::WrapErr, Result};
 use geometry::line_segment::LineSegment;
-use nalgebra::{vector, Isometry2, Point2, UnitComplex, Vector2};
+use nalgebra::{Isometry2, Point2, UnitComplex, Vector2};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
-use spl_network_messages::{GamePhase, GameState, HulkMessage, PlayerNumber, Team};
+use spl_network_messages::{GamePhase, GameState, Half, HulkMessage, PlayerNumber, Team};
 use types::motion_command::{HeadMotion, OrientationMode};
 use types::{
     ball_position::BallPosition,
+    ball_trajectory::BallTrajectory,
+    detected_robots::DetectedRobots,
+    field_dimensions::FieldDimensions,
     filtered_game_state::FilteredGameState,
     game_controller_state::GameControllerState,
     messages::{IncomingMessage, OutgoingMessage},
     motion_command::MotionCommand,
     players::Players,
     primary_state::PrimaryState,
-    support_foot::Side,
 };
 use types::{
-    filtered_game_controller_state::FilteredGameControllerState, motion_command::KickVariant,
-    planned_path::PathSegment,
+    filtered_game_controller_state::FilteredGameControllerState, planned_path::PathSegment,
 };
 
 use crate::{
     cycler::Database,
-    robot::Robot,
+    opponent::Opponent,
+    perception::{detect_ball, is_visible, perceive_pose, PerceptionParameters},
+    physics::{
+        apply_rolling_friction, collide_ball_with_boundaries, collide_ball_with_circle,
+        goal_post_positions, is_ball_in_goal, kick_velocity, separate_robots, PhysicsParameters,
+    },
+    robot::{from_player_number, ParameterOverrides, Robot},
     structs::{control::AdditionalOutputs, Parameters},
 };
 
 pub enum Event {
     Cycle,
     Goal,
+    Collision(PlayerNumber, PlayerNumber),
 }
 
 #[derive(Default, Clone, Deserialize, Serialize, SerializeHierarchy)]
@@ -52,17 +61,46 @@ pub struct State {
     pub finished: bool,
     pub game_controller_state: GameControllerState,
     pub filtered_game_state: FilteredGameState,
+    pub physics: PhysicsParameters,
+    pub perception: PerceptionParameters,
+    pub opponents: Vec<Opponent>,
+    /// Messages which are delivered in a later cycle
+    pub delayed_messages: Vec<DelayedMessage>,
+    /// Seed of the random number generator of the perception, changing it from lua reseeds
+    pub random_seed: u64,
+    pub random_number_generator: StdRng,
+    /// Taken from the parameters of the robots, unknown as long as there are no robots
+    pub field_dimensions: Option<FieldDimensions>,
+}
+
+pub struct DelayedMessage {
+    pub delivery_time: Duration,
+    pub sender: PlayerNumber,
+    pub message: HulkMessage,
 }
 
 impl State {
+    pub fn reseed(&mut self, random_seed: u64) {
+        self.random_seed = random_seed;
+        self.random_number_generator = StdRng::seed_from_u64(random_seed);
+    }
+
     pub fn cycle(&mut self, time_step: Duration) -> Result<Vec<Event>> {
         let now = UNIX_EPOCH + self.time_elapsed;
 
         let mut events = vec![Event::Cycle];
 
+        let previous_robot_positions = self.robot_positions();
+        let previous_opponent_positions = self.opponent_positions();
         self.move_robots(time_step);
+        self.move_opponents(time_step);
+        events.extend(self.separate_robots());
         self.cycle_robots(now)?;
-        events.extend(self.move_ball(time_step));
+        events.extend(self.move_ball(
+            time_step,
+            &previous_robot_positions,
+            &previous_opponent_positions,
+        ));
 
         self.time_elapsed += time_step;
         self.cycle_count += 1;
@@ -80,6 +118,7 @@ impl State {
                 .expect("simulated robots should always have a known pose");
 
             robot.database.additional_outputs = AdditionalOutputs::default();
+            robot.current_odometry_to_last_odometry = Isometry2::identity();
             let head_motion = match &robot.database.main_outputs.motion_command {
                 MotionCommand::Walk {
                     head,
@@ -117,7 +156,9 @@ impl State {
                             ),
                     );
 
-                    for obstacle in robot.database.main_outputs.obstacles.iter_mut() {
+                    robot.current_odometry_to_last_odometry =
+                        previous_robot_to_field.inverse() * *robot_to_field;
+                    for obstacle in robot.scripted_obstacles.iter_mut() {
                         obstacle.position =
                             robot_to_field.inverse() * previous_robot_to_field * obstacle.position;
                     }
@@ -131,20 +172,21 @@ impl State {
                     strength,
                 } => {
                     if let Some(ball) = self.ball.as_mut() {
-                        let side = match kicking_side {
-                            Side::Left => 1.0,
-                            Side::Right => -1.0,
-                        };
-
-                        // TODO: Check if ball is even in range
-                        // let kick_location = robot_to_field * ();
-                        if (self.time_elapsed - robot.last_kick_time).as_secs_f32() > 1.0 {
-                            let direction = match kick {
-                                KickVariant::Forward => vector![1.0, 0.0],
-                                KickVariant::Turn => vector![0.707, 0.707 * side],
-                                KickVariant::Side => vector![0.0, 1.0 * -side],
-                            };
-                            ball.velocity += *robot_to_field * direction * *strength * 2.5;
+                        let ball_in_robot = robot_to_field.inverse() * ball.position;
+                        let is_in_reach = ball_in_robot.x > 0.0
+                            && ball_in_robot.coords.norm() < self.physics.kick_reach;
+                        if is_in_reach
+                            && (self.time_elapsed - robot.last_kick_time).as_secs_f32()
+                                > self.physics.kick_cooldown
+                        {
+                            ball.velocity += *robot_to_field
+                                * kick_velocity(
+                                    *kick,
+                                    *kicking_side,
+                                    *strength,
+                                    &robot.parameters.in_walk_kicks,
+                                    self.physics.ball_deceleration,
+                                );
                             robot.last_kick_time = self.time_elapsed;
                         };
                     }
@@ -167,6 +209,7 @@ impl State {
                     target.coords.angle(&Vector2::x_axis())
                         + glance_factor * robot.parameters.look_at.glance_angle
                 }
+                HeadMotion::Angles { yaw, .. } => *yaw,
                 HeadMotion::Unstiff => 0.0,
             };
 
@@ -181,14 +224,33 @@ impl State {
     }
 
     fn cycle_robots(&mut self, now: std::time::SystemTime) -> Result<()> {
-        let incoming_messages = take(&mut self.messages);
-
-        for (player_number, robot) in self.robots.iter_mut() {
+        let mut incoming_messages = take(&mut self.messages);
+        let time_elapsed = self.time_elapsed;
+        let (due_messages, delayed_messages): (Vec<_>, Vec<_>) = take(&mut self.delayed_messages)
+            .into_iter()
+            .partition(|message| message.delivery_time <= time_elapsed);
+        self.delayed_messages = delayed_messages;
+        incoming_messages.extend(
+            due_messages
+                .into_iter()
+                .map(|message| (message.sender, message.message)),
+        );
+
+        let mut robots: Vec<_> = self.robots.iter_mut().collect();
+        // draw random numbers in a deterministic order
+        robots.sort_by_key(|(player_number, _robot)| from_player_number(**player_number));
+        for (player_number, robot) in robots {
+            let message_drop_probability =
+                self.perception.message_drop_probability.clamp(0.0, 1.0) as f64;
             let incoming_messages: Vec<_> = incoming_messages
                 .iter()
-                .filter_map(|(sender, message)| {
-                    (sender != player_number).then_some(IncomingMessage::Spl(*message))
+                .filter(|(sender, _message)| sender != player_number)
+                .filter(|_| {
+                    !self
+                        .random_number_generator
+                        .gen_bool(message_drop_probability)
                 })
+                .map(|(_sender, message)| IncomingMessage::Spl(*message))
                 .collect();
             let messages_with_time =
                 BTreeMap::from_iter([(now, incoming_messages.iter().collect())]);
@@ -200,33 +262,64 @@ impl State {
                 .main_outputs
                 .robot_to_field
                 .expect("simulated robots should always have a known pose");
-            let ball_visible = self.ball.as_ref().is_some_and(|ball| {
-                let ball_in_ground = robot_to_field.inverse() * ball.position;
-                let head_rotation = UnitComplex::from_angle(
-                    robot.database.main_outputs.sensor_data.positions.head.yaw,
-                );
-                let ball_in_head = head_rotation.inverse() * ball_in_ground;
-                let field_of_view = robot.field_of_view();
-                let angle_to_ball = ball_in_head.coords.angle(&Vector2::x_axis());
-
-                angle_to_ball.abs() < field_of_view / 2.0 && ball_in_head.coords.norm() < 3.0
-            });
-            if ball_visible {
+            let detected_ball = self
+                .ball
+                .as_ref()
+                .filter(|ball| is_visible(robot, robot_to_field, ball.position, &self.perception))
+                .and_then(|ball| {
+                    let detected_position = detect_ball(
+                        robot_to_field.inverse() * ball.position,
+                        &self.perception,
+                        &mut self.random_number_generator,
+                    )?;
+                    Some(Ball {
+                        position: robot_to_field * detected_position,
+                        velocity: ball.velocity,
+                    })
+                });
+            if let Some(ball) = detected_ball {
                 robot.ball_last_seen = Some(now);
+                robot.perceived_ball = Some(ball);
             }
             robot.database.main_outputs.ball_position =
-                if robot.ball_last_seen.is_some_and(|last_seen| {
-                    now.duration_since(last_seen).expect("time ran backwards")
-                        < robot.parameters.ball_filter.hypothesis_timeout
-                }) {
-                    self.ball.as_ref().map(|ball| BallPosition {
-                        position: robot_to_field.inverse() * ball.position,
-                        velocity: robot_to_field.inverse() * ball.velocity,
-                        last_seen: now,
-                    })
-                } else {
-                    None
+                match (robot.ball_last_seen, &robot.perceived_ball) {
+                    (Some(last_seen), Some(ball))
+                        if now.duration_since(last_seen).expect("time ran backwards")
+                            < robot.parameters.ball_filter.hypothesis_timeout =>
+                    {
+                        // the perceived ball keeps rolling while it is not seen
+                        let age = now
+                            .duration_since(last_seen)
+                            .expect("time ran backwards")
+                            .as_secs_f32();
+                        Some(BallPosition {
+                            position: robot_to_field.inverse()
+                                * (ball.position + ball.velocity * age),
+                            velocity: robot_to_field.inverse() * ball.velocity,
+                            last_seen,
+                        })
+                    }
+                    _ => None,
                 };
+            robot.database.main_outputs.ball_trajectory = robot
+                .database
+                .main_outputs
+                .ball_position
+                .map(|ball_position| {
+                    BallTrajectory::from_ball_filter(ball_position, &robot.parameters.ball_filter)
+                });
+            robot.detected_robots = DetectedRobots {
+                in_image: Vec::new(),
+                on_ground: self
+                    .opponents
+                    .iter()
+                    .filter(|opponent| {
+                        is_visible(robot, robot_to_field, opponent.position, &self.perception)
+                    })
+                    .map(|opponent| robot_to_field.inverse() * opponent.position)
+                    .collect(),
+            };
+
             robot.database.main_outputs.primary_state =
                 match (robot.is_penalized, self.filtered_game_state) {
                     (true, _) => PrimaryState::Penalized,
@@ -243,11 +336,33 @@ impl State {
                 });
             robot.database.main_outputs.game_controller_state = Some(self.game_controller_state);
 
-            robot.cycle(messages_with_time)?;
+            // the robot acts on the pose it believes to be at, the simulation continues from the
+            // true pose
+            if let Some(perceived_robot_to_field) = perceive_pose(
+                robot_to_field,
+                &self.perception,
+                &mut self.random_number_generator,
+            ) {
+                robot.perceived_robot_to_field = perceived_robot_to_field;
+            }
+            robot.database.main_outputs.robot_to_field = Some(robot.perceived_robot_to_field);
+            let result = robot.cycle(messages_with_time);
+            robot.database.main_outputs.robot_to_field = Some(robot_to_field);
+            result?;
 
             for message in robot.interface.take_outgoing_messages() {
                 if let OutgoingMessage::Spl(message) = message {
-                    self.messages.push((*player_number, message));
+                    let delay = self.random_number_generator.gen_range(0.0..=1.0)
+                        * self.perception.maximum_message_delay.max(0.0);
+                    if delay > 0.0 {
+                        self.delayed_messages.push(DelayedMessage {
+                            delivery_time: time_elapsed + Duration::from_secs_f32(delay),
+                            sender: *player_number,
+                            message,
+                        });
+                    } else {
+                        self.messages.push((*player_number, message));
+                    }
                     self.game_controller_state.remaining_amount_of_messages -= 1
                 }
             }
@@ -256,13 +371,137 @@ impl State {
         Ok(())
     }
 
-    fn move_ball(&mut self, time_step: Duration) -> Vec<Event> {
+    fn robot_positions(&self) -> HashMap<PlayerNumber, Point2<f32>> {
+        self.robots
+            .iter()
+            .map(|(player_number, robot)| {
+                let robot_to_field = robot
+                    .database
+                    .main_outputs
+                    .robot_to_field
+                    .expect("simulated robots should always have a known pose");
+                (
+                    *player_number,
+                    Point2::from(robot_to_field.translation.vector),
+                )
+            })
+            .collect()
+    }
+
+    fn opponent_positions(&self) -> Vec<Point2<f32>> {
+        self.opponents
+            .iter()
+            .map(|opponent| opponent.position)
+            .collect()
+    }
+
+    fn move_opponents(&mut self, time_step: Duration) {
+        let ball_position = self.ball.as_ref().map(|ball| ball.position);
+        for opponent in self.opponents.iter_mut() {
+            opponent.step(ball_position, time_step.as_secs_f32());
+        }
+    }
+
+    /// Opponents take part in the separation, but only touching own robots are reported
+    fn separate_robots(&mut self) -> Vec<Event> {
+        let mut robots: Vec<_> = self.robots.iter_mut().collect();
+        // resolve collisions in a deterministic order
+        robots.sort_by_key(|(player_number, _robot)| from_player_number(**player_number));
+        let mut positions: Vec<_> = robots
+            .iter()
+            .map(|(_player_number, robot)| {
+                let robot_to_field = robot
+                    .database
+                    .main_outputs
+                    .robot_to_field
+                    .expect("simulated robots should always have a known pose");
+                Point2::from(robot_to_field.translation.vector)
+            })
+            .collect();
+        let number_of_robots = positions.len();
+        positions.extend(self.opponents.iter().map(|opponent| opponent.position));
+
+        let touching_pairs = separate_robots(&mut positions, self.physics.robot_radius);
+
+        for (opponent, position) in self
+            .opponents
+            .iter_mut()
+            .zip(positions.split_off(number_of_robots))
+        {
+            opponent.position = position;
+        }
+        for ((_player_number, robot), position) in robots.iter_mut().zip(positions) {
+            let robot_to_field = robot
+                .database
+                .main_outputs
+                .robot_to_field
+                .as_mut()
+                .expect("simulated robots should always have a known pose");
+            robot_to_field.translation.vector = position.coords;
+        }
+        touching_pairs
+            .into_iter()
+            .filter(|(_first, second)| *second < number_of_robots)
+            .map(|(first, second)| Event::Collision(*robots[first].0, *robots[second].0))
+            .collect()
+    }
+
+    fn move_ball(
+        &mut self,
+        time_step: Duration,
+        previous_robot_positions: &HashMap<PlayerNumber, Point2<f32>>,
+        previous_opponent_positions: &[Point2<f32>],
+    ) -> Vec<Event> {
         let mut events = Vec::new();
-        if let Some(ball) = self.ball.as_mut() {
-            ball.position += ball.velocity * time_step.as_secs_f32();
-            ball.velocity *= 0.98;
+        let robot_positions = self.robot_positions();
+        let opponent_positions = self.opponent_positions();
+        let Some(ball) = self.ball.as_mut() else {
+            return events;
+        };
 
-            if ball.position.x.abs() > 4.5 && ball.position.y < 0.75 {
+        ball.position += ball.velocity * time_step.as_secs_f32();
+        apply_rolling_friction(
+            ball,
+            self.physics.ball_deceleration,
+            time_step.as_secs_f32(),
+        );
+
+        let robots_with_previous_positions = robot_positions
+            .iter()
+            .map(|(player_number, position)| {
+                (position, previous_robot_positions.get(player_number))
+            })
+            .chain(
+                opponent_positions
+                    .iter()
+                    .zip(previous_opponent_positions.iter().map(Some)),
+            );
+        for (position, previous_position) in robots_with_previous_positions {
+            let velocity = previous_position.map_or(Vector2::zeros(), |previous_position| {
+                (position - previous_position) / time_step.as_secs_f32()
+            });
+            collide_ball_with_circle(
+                ball,
+                *position,
+                velocity,
+                self.physics.ball_contact_distance,
+                self.physics.ball_restitution,
+            );
+        }
+
+        if let Some(field_dimensions) = &self.field_dimensions {
+            for goal_post in goal_post_positions(field_dimensions) {
+                collide_ball_with_circle(
+                    ball,
+                    goal_post,
+                    Vector2::zeros(),
+                    field_dimensions.goal_post_diameter / 2.0 + field_dimensions.ball_radius,
+                    self.physics.ball_restitution,
+                );
+            }
+            collide_ball_with_boundaries(ball, field_dimensions, self.physics.boundary_restitution);
+
+            if is_ball_in_goal(ball, field_dimensions) {
                 events.push(Event::Goal);
             }
         }
@@ -283,17 +522,28 @@ impl State {
 
             game_controller_state: self.game_controller_state,
             filtered_game_state: self.filtered_game_state,
+            physics: self.physics.clone(),
+            perception: self.perception.clone(),
+            opponents: self.opponents.clone(),
+            random_seed: self.random_seed,
         }
     }
 
-    pub fn load_lua_state(&mut self, lua_state: LuaState) -> Result<()> {
+    /// Robots are recreated with the `parameter_overrides` of the run, the lua state does not contain
+    /// what their nodes were created with
+    pub fn load_lua_state(
+        &mut self,
+        lua_state: LuaState,
+        parameter_overrides: &ParameterOverrides,
+    ) -> Result<()> {
         self.ball = lua_state.ball;
         self.cycle_count = lua_state.cycle_count;
         for lua_robot in lua_state.robots {
-            let mut robot = Robot::try_new(lua_robot.parameters.player_number)
-                .expect("Creating dummy robot should never fail");
+            let mut robot = Robot::try_new(lua_robot.parameters.player_number, parameter_overrides)
+                .wrap_err("failed to recreate robot")?;
             robot.database = lua_robot.database;
             robot.parameters = lua_robot.parameters;
+            self.field_dimensions = Some(robot.parameters.field_dimensions.clone());
             self.robots.insert(robot.parameters.player_number, robot);
         }
 
@@ -301,6 +551,12 @@ impl State {
 
         self.game_controller_state = lua_state.game_controller_state;
         self.filtered_game_state = lua_state.filtered_game_state;
+        self.physics = lua_state.physics;
+        self.perception = lua_state.perception;
+        self.opponents = lua_state.opponents;
+        if lua_state.random_seed != self.random_seed {
+            self.reseed(lua_state.random_seed);
+        }
 
         Ok(())
     }
@@ -315,6 +571,8 @@ impl Default for State {
             game_phase: GamePhase::Normal,
             kicking_team: Team::Hulks,
             last_game_state_change: UNIX_EPOCH,
+            half: Half::First,
+            remaining_time_in_half: Duration::from_secs(600),
             penalties: Players {
                 one: None,
                 two: None,
@@ -338,6 +596,13 @@ impl Default for State {
             finished: false,
             game_controller_state,
             filtered_game_state: FilteredGameState::Initial,
+            physics: Default::default(),
+            perception: Default::default(),
+            opponents: Vec::new(),
+            delayed_messages: Vec::new(),
+            random_seed: 0,
+            random_number_generator: StdRng::seed_from_u64(0),
+            field_dimensions: None,
         }
     }
 }
@@ -352,6 +617,10 @@ pub struct LuaState {
     pub finished: bool,
     pub game_controller_state: GameControllerState,
     pub filtered_game_state: FilteredGameState,
+    pub physics: PhysicsParameters,
+    pub perception: PerceptionParameters,
+    pub opponents: Vec<Opponent>,
+    pub random_seed: u64,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -0,0 +1,67 @@
+use std::{collections::BTreeMap, env::temp_dir, fs::write};
+
+use color_eyre::Result;
+use nalgebra::Isometry2;
+use serde_json::json;
+use spl_network_messages::PlayerNumber;
+
+use behavior_simulator::{
+    batch::{run_in_parallel, Run},
+    robot::ParameterOverrides,
+    simulator::Simulator,
+};
+
+#[test]
+fn repetitions_of_a_seeded_scenario_differ() -> Result<()> {
+    let runs = (0..2)
+        .map(|repetition| Run {
+            scenario_file: "../../tests/behavior/golden_goal_noisy_perception.lua".into(),
+            parameter_set: 0,
+            parameter_overrides: Default::default(),
+            repetition,
+        })
+        .collect();
+    let summaries = run_in_parallel(runs, 2)?;
+
+    let goals: Vec<Vec<_>> = summaries
+        .iter()
+        .map(|summary| {
+            assert!(summary.succeeded, "{:?}", summary.error);
+            summary
+                .report
+                .goals
+                .iter()
+                .map(|goal| (goal.time, goal.ball_position))
+                .collect()
+        })
+        .collect();
+    assert_ne!(goals[0], goals[1]);
+    Ok(())
+}
+
+#[test]
+fn parameter_overrides_reach_creation_contexts() -> Result<()> {
+    let scenario_file = temp_dir().join("single_robot.lua");
+    write(
+        &scenario_file,
+        "table.insert(state.robots, create_robot(7))",
+    )?;
+    // the center circle junctions in front of the robot are out of sight on a larger circle
+    let parameter_overrides = ParameterOverrides::from([(
+        "field_dimensions.center_circle_diameter".into(),
+        json!(10.0),
+    )]);
+    let mut simulator = Simulator::try_new_with_parameter_overrides(parameter_overrides)?;
+    simulator.execute_script(&scenario_file)?;
+
+    let mut state = simulator.state.lock();
+    let robot = state.robots.get_mut(&PlayerNumber::Seven).unwrap();
+    robot.database.main_outputs.robot_to_field = Some(Isometry2::translation(-1.0, 0.0));
+    robot.cycle(BTreeMap::new())?;
+
+    assert_eq!(
+        robot.database.main_outputs.position_of_interest,
+        robot.parameters.behavior.look_action.look_forward_position
+    );
+    Ok(())
+}
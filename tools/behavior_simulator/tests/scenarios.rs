@@ -2,9 +2,9 @@ use std::{path::Path, time::Instant};
 
 use color_eyre::{eyre::Context, Result};
 
-use behavior_simulator::simulator::Simulator;
+use behavior_simulator::{report::ScenarioReport, simulator::Simulator};
 
-fn test_scenario(path: impl AsRef<Path>) -> Result<()> {
+fn test_scenario(path: impl AsRef<Path>) -> Result<ScenarioReport> {
     let mut simulator = Simulator::try_new()?;
     simulator.execute_script(path)?;
 
@@ -13,15 +13,33 @@ fn test_scenario(path: impl AsRef<Path>) -> Result<()> {
     let duration = Instant::now() - start;
     eprintln!("Took {:.2} seconds", duration.as_secs_f32());
 
-    Ok(())
+    Ok(simulator.report())
 }
 
 #[test]
 fn test_golden_goal() -> Result<()> {
-    test_scenario("../../tests/behavior/golden_goal.lua")
+    let report = test_scenario("../../tests/behavior/golden_goal.lua")?;
+    assert!(!report.goals.is_empty(), "no goal was scored");
+    Ok(())
+}
+
+#[test]
+fn test_golden_goal_noisy_perception() -> Result<()> {
+    let report = test_scenario("../../tests/behavior/golden_goal_noisy_perception.lua")?;
+    assert!(!report.goals.is_empty(), "no goal was scored");
+    Ok(())
 }
 
 #[test]
 fn test_demonstration() -> Result<()> {
-    test_scenario("../../tests/behavior/demonstration.lua")
+    let report = test_scenario("../../tests/behavior/demonstration.lua")?;
+    assert_eq!(report.role_history.len(), 7);
+    Ok(())
+}
+
+#[test]
+fn test_opponents() -> Result<()> {
+    let report = test_scenario("../../tests/behavior/opponents.lua")?;
+    assert!(!report.goals.is_empty(), "no goal was scored");
+    Ok(())
 }
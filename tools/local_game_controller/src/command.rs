@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use color_eyre::{eyre::bail, Report, Result};
+use spl_network_messages::{Penalty, PlayerNumber, SubState, Team};
+
+use crate::game::{Game, PENALTY_DURATION};
+
+/// Referee action in the textual form accepted by the `local_game_controller` binary
+///
+/// ```text
+/// ready <team> | set | playing | finished | second-half | goal <team>
+/// set-play <team> <goal-kick|pushing-free-kick|corner-kick|kick-in|penalty-kick>
+/// penalize <team> <player> <penalty> | unpenalize <team> <player>
+/// ```
+///
+/// Teams are `hulks` or `opponent`, players are numbered from 1 to 7.
+#[derive(Clone, Copy, Debug)]
+pub enum Command {
+    Ready {
+        kicking_team: Team,
+    },
+    Set,
+    Playing,
+    Finished,
+    SecondHalf,
+    Goal {
+        scoring_team: Team,
+    },
+    SetPlay {
+        kicking_team: Team,
+        sub_state: SubState,
+    },
+    Penalize {
+        team: Team,
+        player_number: PlayerNumber,
+        penalty: Penalty,
+    },
+    Unpenalize {
+        team: Team,
+        player_number: PlayerNumber,
+    },
+}
+
+impl Command {
+    pub fn apply(self, game: &mut Game) {
+        match self {
+            Command::Ready { kicking_team } => game.ready(kicking_team),
+            Command::Set => game.set(),
+            Command::Playing => game.play(),
+            Command::Finished => game.finish(),
+            Command::SecondHalf => game.start_second_half(),
+            Command::Goal { scoring_team } => game.goal(scoring_team),
+            Command::SetPlay {
+                kicking_team,
+                sub_state,
+            } => game.start_set_play(kicking_team, sub_state),
+            Command::Penalize {
+                team,
+                player_number,
+                penalty,
+            } => game.penalize(team, player_number, penalty),
+            Command::Unpenalize {
+                team,
+                player_number,
+            } => game.unpenalize(team, player_number),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = Report;
+
+    fn from_str(command: &str) -> Result<Self> {
+        let words: Vec<_> = command.split_whitespace().collect();
+        Ok(match words.as_slice() {
+            ["ready", team] => Command::Ready {
+                kicking_team: parse_team(team)?,
+            },
+            ["set"] => Command::Set,
+            ["playing"] => Command::Playing,
+            ["finished"] => Command::Finished,
+            ["second-half"] => Command::SecondHalf,
+            ["goal", team] => Command::Goal {
+                scoring_team: parse_team(team)?,
+            },
+            ["set-play", team, sub_state] => Command::SetPlay {
+                kicking_team: parse_team(team)?,
+                sub_state: parse_sub_state(sub_state)?,
+            },
+            ["penalize", team, player_number, penalty] => Command::Penalize {
+                team: parse_team(team)?,
+                player_number: parse_player_number(player_number)?,
+                penalty: parse_penalty(penalty)?,
+            },
+            ["unpenalize", team, player_number] => Command::Unpenalize {
+                team: parse_team(team)?,
+                player_number: parse_player_number(player_number)?,
+            },
+            _ => bail!("unknown command `{command}`"),
+        })
+    }
+}
+
+fn parse_team(team: &str) -> Result<Team> {
+    match team {
+        "hulks" => Ok(Team::Hulks),
+        "opponent" => Ok(Team::Opponent),
+        _ => bail!("unknown team `{team}`"),
+    }
+}
+
+fn parse_player_number(player_number: &str) -> Result<PlayerNumber> {
+    match player_number {
+        "1" => Ok(PlayerNumber::One),
+        "2" => Ok(PlayerNumber::Two),
+        "3" => Ok(PlayerNumber::Three),
+        "4" => Ok(PlayerNumber::Four),
+        "5" => Ok(PlayerNumber::Five),
+        "6" => Ok(PlayerNumber::Six),
+        "7" => Ok(PlayerNumber::Seven),
+        _ => bail!("unknown player number `{player_number}`"),
+    }
+}
+
+fn parse_sub_state(sub_state: &str) -> Result<SubState> {
+    match sub_state {
+        "goal-kick" => Ok(SubState::GoalKick),
+        "pushing-free-kick" => Ok(SubState::PushingFreeKick),
+        "corner-kick" => Ok(SubState::CornerKick),
+        "kick-in" => Ok(SubState::KickIn),
+        "penalty-kick" => Ok(SubState::PenaltyKick),
+        _ => bail!("unknown set play `{sub_state}`"),
+    }
+}
+
+fn parse_penalty(penalty: &str) -> Result<Penalty> {
+    let remaining = PENALTY_DURATION;
+    Ok(match penalty {
+        "illegal-ball-contact" => Penalty::IllegalBallContact { remaining },
+        "player-pushing" => Penalty::PlayerPushing { remaining },
+        "illegal-motion-in-set" => Penalty::IllegalMotionInSet { remaining },
+        "inactive-player" => Penalty::InactivePlayer { remaining },
+        "illegal-position" => Penalty::IllegalPosition { remaining },
+        "leaving-the-field" => Penalty::LeavingTheField { remaining },
+        "request-for-pickup" => Penalty::RequestForPickup { remaining },
+        "local-game-stuck" => Penalty::LocalGameStuck { remaining },
+        "illegal-position-in-set" => Penalty::IllegalPositionInSet { remaining },
+        "player-stance" => Penalty::PlayerStance { remaining },
+        "substitute" => Penalty::Substitute { remaining },
+        "manual" => Penalty::Manual { remaining },
+        _ => bail!("unknown penalty `{penalty}`"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert!(matches!(
+            "ready opponent".parse::<Command>(),
+            Ok(Command::Ready {
+                kicking_team: Team::Opponent
+            })
+        ));
+        assert!(matches!(
+            "penalize hulks 4 player-pushing".parse::<Command>(),
+            Ok(Command::Penalize {
+                team: Team::Hulks,
+                player_number: PlayerNumber::Four,
+                penalty: Penalty::PlayerPushing { .. },
+            })
+        ));
+        assert!("penalize hulks 8 player-pushing"
+            .parse::<Command>()
+            .is_err());
+        assert!("kick-off".parse::<Command>().is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use spl_network_messages::{
+    CompetitionPhase, CompetitionType, GameControllerStateMessage, GamePhase, GameState, Half,
+    Penalty, Player, PlayerNumber, SubState, Team, TeamColor, TeamState, HULKS_TEAM_NUMBER,
+};
+
+pub const PENALTY_DURATION: Duration = Duration::from_secs(45);
+const HALF_DURATION: Duration = Duration::from_secs(600);
+const READY_DURATION: Duration = Duration::from_secs(45);
+const SET_PLAY_DURATION: Duration = Duration::from_secs(30);
+const MESSAGE_BUDGET: u16 = 1200;
+const PLAYERS_PER_TEAM: usize = 7;
+
+/// State of the emulated game, advanced by [`Game::advance`] and modified by the referee actions
+#[derive(Clone, Debug)]
+pub struct Game {
+    state: GameControllerStateMessage,
+}
+
+impl Game {
+    pub fn new(opponent_team_number: u8) -> Self {
+        Self {
+            state: GameControllerStateMessage {
+                competition_phase: CompetitionPhase::RoundRobin,
+                competition_type: CompetitionType::Normal,
+                game_phase: GamePhase::Normal,
+                game_state: GameState::Initial,
+                sub_state: None,
+                half: Half::First,
+                remaining_time_in_half: HALF_DURATION,
+                secondary_time: Duration::ZERO,
+                hulks_team: team_state(HULKS_TEAM_NUMBER, TeamColor::Blue, TeamColor::Yellow),
+                opponent_team: team_state(opponent_team_number, TeamColor::Red, TeamColor::Black),
+                kicking_team: Team::Hulks,
+                hulks_team_is_home_after_coin_toss: true,
+            },
+        }
+    }
+
+    pub fn state(&self) -> &GameControllerStateMessage {
+        &self.state
+    }
+
+    pub fn ready(&mut self, kicking_team: Team) {
+        self.state.game_state = GameState::Ready;
+        self.state.kicking_team = kicking_team;
+        self.state.sub_state = None;
+        self.state.secondary_time = READY_DURATION;
+    }
+
+    pub fn set(&mut self) {
+        self.state.game_state = GameState::Set;
+        self.state.secondary_time = Duration::ZERO;
+    }
+
+    pub fn play(&mut self) {
+        self.state.game_state = GameState::Playing;
+        self.state.secondary_time = Duration::ZERO;
+    }
+
+    pub fn finish(&mut self) {
+        self.state.game_state = GameState::Finished;
+        self.state.sub_state = None;
+        self.state.secondary_time = Duration::ZERO;
+    }
+
+    pub fn start_second_half(&mut self) {
+        self.state.half = Half::Second;
+        self.state.game_state = GameState::Initial;
+        self.state.remaining_time_in_half = HALF_DURATION;
+        self.state.kicking_team = opposing(self.state.kicking_team);
+        self.state.hulks_team_is_home_after_coin_toss =
+            !self.state.hulks_team_is_home_after_coin_toss;
+    }
+
+    pub fn goal(&mut self, scoring_team: Team) {
+        let team = self.team_mut(scoring_team);
+        team.score = team.score.saturating_add(1);
+        self.ready(opposing(scoring_team));
+    }
+
+    pub fn start_set_play(&mut self, kicking_team: Team, sub_state: SubState) {
+        self.state.kicking_team = kicking_team;
+        self.state.sub_state = Some(sub_state);
+        self.state.secondary_time = SET_PLAY_DURATION;
+        if let SubState::PenaltyKick = sub_state {
+            self.state.game_state = GameState::Ready;
+        }
+    }
+
+    pub fn penalize(&mut self, team: Team, player_number: PlayerNumber, penalty: Penalty) {
+        self.player_mut(team, player_number).penalty = Some(penalty);
+    }
+
+    pub fn unpenalize(&mut self, team: Team, player_number: PlayerNumber) {
+        self.player_mut(team, player_number).penalty = None;
+    }
+
+    /// Deducts a received team message from the budget of the team
+    pub fn record_team_message(&mut self, team: Team) {
+        let team = self.team_mut(team);
+        team.remaining_amount_of_messages = team.remaining_amount_of_messages.saturating_sub(1);
+    }
+
+    /// Runs the clocks like the GameController does between two packets
+    pub fn advance(&mut self, elapsed: Duration) {
+        if self.state.game_state == GameState::Playing {
+            self.state.remaining_time_in_half =
+                self.state.remaining_time_in_half.saturating_sub(elapsed);
+            if self.state.remaining_time_in_half.is_zero() {
+                self.finish();
+            }
+        }
+        if !self.state.secondary_time.is_zero() {
+            self.state.secondary_time = self.state.secondary_time.saturating_sub(elapsed);
+            if self.state.secondary_time.is_zero() && self.state.game_state == GameState::Playing {
+                self.state.sub_state = None;
+            }
+        }
+        if matches!(
+            self.state.game_state,
+            GameState::Ready | GameState::Set | GameState::Playing
+        ) {
+            for player in self
+                .state
+                .hulks_team
+                .players
+                .iter_mut()
+                .chain(self.state.opponent_team.players.iter_mut())
+            {
+                player.penalty = player.penalty.map(|penalty| {
+                    with_remaining(penalty, penalty.remaining().saturating_sub(elapsed))
+                });
+            }
+        }
+    }
+
+    fn team_mut(&mut self, team: Team) -> &mut TeamState {
+        match team {
+            Team::Hulks => &mut self.state.hulks_team,
+            Team::Opponent | Team::Uncertain => &mut self.state.opponent_team,
+        }
+    }
+
+    fn player_mut(&mut self, team: Team, player_number: PlayerNumber) -> &mut Player {
+        let index = match player_number {
+            PlayerNumber::One => 0,
+            PlayerNumber::Two => 1,
+            PlayerNumber::Three => 2,
+            PlayerNumber::Four => 3,
+            PlayerNumber::Five => 4,
+            PlayerNumber::Six => 5,
+            PlayerNumber::Seven => 6,
+        };
+        &mut self.team_mut(team).players[index]
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(HULKS_TEAM_NUMBER + 1)
+    }
+}
+
+fn team_state(
+    team_number: u8,
+    field_player_color: TeamColor,
+    goal_keeper_color: TeamColor,
+) -> TeamState {
+    TeamState {
+        team_number,
+        field_player_color,
+        goal_keeper_color,
+        goal_keeper_player_number: PlayerNumber::One,
+        score: 0,
+        penalty_shoot_index: 0,
+        penalty_shoots: Vec::new(),
+        remaining_amount_of_messages: MESSAGE_BUDGET,
+        players: vec![Player { penalty: None }; PLAYERS_PER_TEAM],
+    }
+}
+
+fn opposing(team: Team) -> Team {
+    match team {
+        Team::Hulks => Team::Opponent,
+        Team::Opponent | Team::Uncertain => Team::Hulks,
+    }
+}
+
+fn with_remaining(penalty: Penalty, remaining: Duration) -> Penalty {
+    match penalty {
+        Penalty::IllegalBallContact { .. } => Penalty::IllegalBallContact { remaining },
+        Penalty::PlayerPushing { .. } => Penalty::PlayerPushing { remaining },
+        Penalty::IllegalMotionInSet { .. } => Penalty::IllegalMotionInSet { remaining },
+        Penalty::InactivePlayer { .. } => Penalty::InactivePlayer { remaining },
+        Penalty::IllegalPosition { .. } => Penalty::IllegalPosition { remaining },
+        Penalty::LeavingTheField { .. } => Penalty::LeavingTheField { remaining },
+        Penalty::RequestForPickup { .. } => Penalty::RequestForPickup { remaining },
+        Penalty::LocalGameStuck { .. } => Penalty::LocalGameStuck { remaining },
+        Penalty::IllegalPositionInSet { .. } => Penalty::IllegalPositionInSet { remaining },
+        Penalty::PlayerStance { .. } => Penalty::PlayerStance { remaining },
+        Penalty::Substitute { .. } => Penalty::Substitute { remaining },
+        Penalty::Manual { .. } => Penalty::Manual { remaining },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_only_runs_while_playing() {
+        let mut game = Game::default();
+        game.advance(Duration::from_secs(10));
+        assert_eq!(game.state().remaining_time_in_half, HALF_DURATION);
+
+        game.ready(Team::Hulks);
+        game.set();
+        game.play();
+        game.advance(Duration::from_secs(10));
+        assert_eq!(
+            game.state().remaining_time_in_half,
+            HALF_DURATION - Duration::from_secs(10)
+        );
+
+        game.advance(HALF_DURATION);
+        assert_eq!(game.state().game_state, GameState::Finished);
+    }
+
+    #[test]
+    fn penalties_count_down() {
+        let mut game = Game::default();
+        game.play();
+        game.penalize(
+            Team::Hulks,
+            PlayerNumber::Three,
+            Penalty::PlayerPushing {
+                remaining: PENALTY_DURATION,
+            },
+        );
+        game.advance(Duration::from_secs(5));
+        let penalty = game.state().hulks_team.players[2].penalty.unwrap();
+        assert_eq!(penalty.remaining(), Duration::from_secs(40));
+
+        game.unpenalize(Team::Hulks, PlayerNumber::Three);
+        assert!(game.state().hulks_team.players[2].penalty.is_none());
+    }
+
+    #[test]
+    fn goals_and_set_plays_hand_over_the_kick_off() {
+        let mut game = Game::default();
+        game.play();
+        game.start_set_play(Team::Opponent, SubState::CornerKick);
+        assert!(matches!(game.state().sub_state, Some(SubState::CornerKick)));
+        game.advance(SET_PLAY_DURATION);
+        assert!(game.state().sub_state.is_none());
+
+        game.goal(Team::Hulks);
+        assert_eq!(game.state().hulks_team.score, 1);
+        assert_eq!(game.state().game_state, GameState::Ready);
+        assert_eq!(game.state().kicking_team, Team::Opponent);
+    }
+
+    #[test]
+    fn team_messages_are_deducted_from_the_budget() {
+        let mut game = Game::default();
+        game.record_team_message(Team::Hulks);
+        assert_eq!(
+            game.state().hulks_team.remaining_amount_of_messages,
+            MESSAGE_BUDGET - 1
+        );
+        assert_eq!(
+            game.state().opponent_team.remaining_amount_of_messages,
+            MESSAGE_BUDGET
+        );
+    }
+}
@@ -0,0 +1,13 @@
+//! Stand-in for the GameController which runs on localhost
+//!
+//! It broadcasts `RoboCupGameControlData` packets, tracks `RoboCupGameControlReturnData` packets
+//! of the robots and deducts team messages from the message budget. The game is either scripted
+//! from tests via [`LocalGameController`] or interactively via the `local_game_controller` binary.
+
+mod command;
+mod game;
+mod server;
+
+pub use command::Command;
+pub use game::{Game, PENALTY_DURATION};
+pub use server::{Addresses, LocalGameController, ReturnMessageRecord};
@@ -0,0 +1,65 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+use color_eyre::Result;
+use local_game_controller::{Addresses, Command, Game, LocalGameController};
+use log::{error, info};
+use tokio::io::{stdin, AsyncBufReadExt, BufReader};
+
+use crate::logging::setup_logger;
+
+mod logging;
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct CommandlineArguments {
+    /// Destination of the GameController state packets
+    #[clap(long, default_value = "127.0.0.1:3838")]
+    state_address: SocketAddr,
+    /// Address to receive the GameController return packets on
+    #[clap(long, default_value = "127.0.0.1:3939")]
+    return_address: SocketAddr,
+    /// Address to receive team messages on, deducts them from the message budget if given
+    #[clap(long)]
+    spl_address: Option<SocketAddr>,
+    #[clap(long, default_value_t = 25)]
+    opponent_team_number: u8,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    setup_logger()?;
+
+    let arguments = CommandlineArguments::parse();
+    let game_controller = LocalGameController::start(
+        Game::new(arguments.opponent_team_number),
+        Addresses {
+            game_controller_state: arguments.state_address,
+            game_controller_return: arguments.return_address,
+            spl: arguments.spl_address,
+        },
+    )
+    .await?;
+    info!("Reading commands from stdin, e.g. `ready hulks`, `penalize hulks 3 player-pushing`");
+
+    let mut lines = BufReader::new(stdin()).lines();
+    while let Some(line) = lines.next_line().await? {
+        match line.trim() {
+            "" => {}
+            "status" => {
+                info!("{:#?}", game_controller.game().state());
+                for (player_number, record) in game_controller.return_messages() {
+                    info!(
+                        "Player {player_number}: {} return messages, last {:?}",
+                        record.number_of_received_messages, record.message
+                    );
+                }
+            }
+            command => match command.parse::<Command>() {
+                Ok(command) => game_controller.apply(command),
+                Err(error) => error!("{error}"),
+            },
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,273 @@
+use std::{
+    collections::BTreeMap,
+    future::pending,
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use log::warn;
+use parking_lot::Mutex;
+use spl_network_messages::{GameControllerReturnMessage, PlayerNumber, Team, HULK_MESSAGE_HEADER};
+use tokio::{
+    net::UdpSocket,
+    select,
+    task::JoinHandle,
+    time::{interval, sleep, Instant},
+};
+
+use crate::{command::Command, game::Game};
+
+const SEND_INTERVAL: Duration = Duration::from_millis(500);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Copy, Debug)]
+pub struct Addresses {
+    /// Destination of the state packets
+    pub game_controller_state: SocketAddr,
+    /// Local address receiving the return packets of the robots, state packets are sent from it
+    pub game_controller_return: SocketAddr,
+    /// Local address receiving HULKs team messages which are deducted from the message budget
+    ///
+    /// Robots on the same host already bind the SPL port, so this is disabled by default.
+    pub spl: Option<SocketAddr>,
+}
+
+impl Default for Addresses {
+    fn default() -> Self {
+        Self {
+            game_controller_state: (Ipv4Addr::LOCALHOST, 3838).into(),
+            game_controller_return: (Ipv4Addr::LOCALHOST, 3939).into(),
+            spl: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReturnMessageRecord {
+    pub message: GameControllerReturnMessage,
+    pub received_at: SystemTime,
+    pub number_of_received_messages: usize,
+}
+
+#[derive(Default)]
+struct Shared {
+    game: Mutex<Game>,
+    return_messages: Mutex<BTreeMap<PlayerNumber, ReturnMessageRecord>>,
+}
+
+/// Runs a [`Game`] in the background and exchanges packets with the robots until dropped
+pub struct LocalGameController {
+    shared: Arc<Shared>,
+    game_controller_return_address: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl LocalGameController {
+    pub async fn start(game: Game, addresses: Addresses) -> Result<Self> {
+        let socket = UdpSocket::bind(addresses.game_controller_return)
+            .await
+            .wrap_err("failed to bind GameController return socket")?;
+        socket
+            .set_broadcast(true)
+            .wrap_err("failed to enable broadcast socket option")?;
+        let game_controller_return_address = socket.local_addr()?;
+        let spl_socket = match addresses.spl {
+            Some(address) => Some(
+                UdpSocket::bind(address)
+                    .await
+                    .wrap_err("failed to bind SPL socket")?,
+            ),
+            None => None,
+        };
+        let shared = Arc::new(Shared {
+            game: Mutex::new(game),
+            return_messages: Default::default(),
+        });
+        let task = tokio::spawn(run(
+            shared.clone(),
+            socket,
+            spl_socket,
+            addresses.game_controller_state,
+        ));
+        Ok(Self {
+            shared,
+            game_controller_return_address,
+            task,
+        })
+    }
+
+    /// Address the return packets are received on, useful when binding to port 0
+    pub fn game_controller_return_address(&self) -> SocketAddr {
+        self.game_controller_return_address
+    }
+
+    pub fn apply(&self, command: Command) {
+        command.apply(&mut self.shared.game.lock());
+    }
+
+    pub fn update(&self, update: impl FnOnce(&mut Game)) {
+        update(&mut self.shared.game.lock());
+    }
+
+    pub fn game(&self) -> Game {
+        self.shared.game.lock().clone()
+    }
+
+    pub fn return_messages(&self) -> BTreeMap<PlayerNumber, ReturnMessageRecord> {
+        self.shared.return_messages.lock().clone()
+    }
+
+    pub async fn wait_for_return_message(
+        &self,
+        player_number: PlayerNumber,
+        timeout: Duration,
+    ) -> Result<ReturnMessageRecord> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(record) = self.shared.return_messages.lock().get(&player_number) {
+                return Ok(*record);
+            }
+            if Instant::now() >= deadline {
+                bail!("no return message of player {player_number} within {timeout:?}");
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for LocalGameController {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(
+    shared: Arc<Shared>,
+    socket: UdpSocket,
+    spl_socket: Option<UdpSocket>,
+    game_controller_state_address: SocketAddr,
+) {
+    let mut send_interval = interval(SEND_INTERVAL);
+    let mut last_send = Instant::now();
+    let mut return_buffer = [0; 1024];
+    let mut spl_buffer = [0; 1024];
+    loop {
+        select! {
+            _ = send_interval.tick() => {
+                let now = Instant::now();
+                let state = {
+                    let mut game = shared.game.lock();
+                    game.advance(now - last_send);
+                    game.state().clone()
+                };
+                last_send = now;
+                let message: Vec<u8> = state.into();
+                if let Err(error) = socket.send_to(&message, game_controller_state_address).await {
+                    warn!("Failed to send GameController state message: {error:?}");
+                }
+            },
+            result = socket.recv_from(&mut return_buffer) => {
+                let received_bytes = match result {
+                    Ok((received_bytes, _address)) => received_bytes,
+                    Err(error) => {
+                        warn!("Failed to receive GameController return message: {error:?}");
+                        continue;
+                    }
+                };
+                match GameControllerReturnMessage::try_from(&return_buffer[..received_bytes]) {
+                    Ok(message) => {
+                        let mut return_messages = shared.return_messages.lock();
+                        let number_of_received_messages = return_messages
+                            .get(&message.player_number)
+                            .map_or(0, |record| record.number_of_received_messages);
+                        return_messages.insert(
+                            message.player_number,
+                            ReturnMessageRecord {
+                                message,
+                                received_at: SystemTime::now(),
+                                number_of_received_messages: number_of_received_messages + 1,
+                            },
+                        );
+                    }
+                    Err(error) => {
+                        warn!("Failed to parse GameController return message (will be discarded): {error:?}");
+                    }
+                }
+            },
+            result = receive(spl_socket.as_ref(), &mut spl_buffer) => match result {
+                // other traffic on the SPL port must not be charged to the HULKs budget
+                Ok(received_bytes) => {
+                    if spl_buffer[..received_bytes].starts_with(&HULK_MESSAGE_HEADER) {
+                        shared.game.lock().record_team_message(Team::Hulks);
+                    }
+                }
+                Err(error) => warn!("Failed to receive SPL message: {error:?}"),
+            },
+        }
+    }
+}
+
+async fn receive(socket: Option<&UdpSocket>, buffer: &mut [u8]) -> io::Result<usize> {
+    match socket {
+        Some(socket) => socket.recv(buffer).await,
+        None => pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spl_network_messages::HulkMessage;
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn remaining_hulks_messages(game: &Game) -> u16 {
+        game.state().hulks_team.remaining_amount_of_messages
+    }
+
+    #[tokio::test]
+    async fn only_hulks_messages_on_the_spl_port_are_deducted_from_the_budget() {
+        let state_receiver = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let spl_address = std::net::UdpSocket::bind((Ipv4Addr::LOCALHOST, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let game_controller = LocalGameController::start(
+            Game::default(),
+            Addresses {
+                game_controller_state: state_receiver.local_addr().unwrap(),
+                game_controller_return: (Ipv4Addr::LOCALHOST, 0).into(),
+                spl: Some(spl_address),
+            },
+        )
+        .await
+        .unwrap();
+        let initial_budget = remaining_hulks_messages(&game_controller.game());
+
+        let robot = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        robot
+            .send_to(b"not a team message", spl_address)
+            .await
+            .unwrap();
+        let message: Vec<u8> = HulkMessage::default().into();
+        robot.send_to(&message, spl_address).await.unwrap();
+
+        // datagrams are handled in order, so the foreign one has been seen once the budget changed
+        let deadline = Instant::now() + TIMEOUT;
+        while remaining_hulks_messages(&game_controller.game()) == initial_budget {
+            assert!(Instant::now() < deadline, "team message was not deducted");
+            sleep(POLL_INTERVAL).await;
+        }
+        assert_eq!(
+            remaining_hulks_messages(&game_controller.game()),
+            initial_budget - 1
+        );
+    }
+}
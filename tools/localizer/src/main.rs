@@ -79,8 +79,15 @@ fn merge_line_data(line_data: &BTreeMap<SystemTime, Vec<Option<LineData>>>) -> L
         .flatten()
         .flat_map(|line_data| line_data.lines_in_robot.clone())
         .collect();
+    let intersections_in_robot = line_data
+        .values()
+        .flatten()
+        .flatten()
+        .flat_map(|line_data| line_data.intersections_in_robot.clone())
+        .collect();
     LineData {
         lines_in_robot,
+        intersections_in_robot,
         used_vertical_filtered_segments: HashSet::new(),
     }
 }
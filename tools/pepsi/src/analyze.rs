@@ -1,11 +1,17 @@
-use std::path::PathBuf;
+use std::{fs::read_to_string, path::PathBuf};
 
 use bat::{PagingMode, PrettyPrinter};
-use clap::Subcommand;
-use color_eyre::{eyre::WrapErr, Result};
+use clap::{Subcommand, ValueEnum};
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
 
 use repository::Repository;
-use source_analyzer::{contexts::Contexts, node::parse_rust_file, pretty::to_string_pretty};
+use source_analyzer::{
+    contexts::Contexts, cyclers::Cyclers, graph::DependencyGraph, lint::lint,
+    manifest::FrameworkManifest, node::parse_rust_file, pretty::to_string_pretty,
+};
 
 #[derive(Subcommand)]
 #[allow(clippy::enum_variant_names)]
@@ -18,6 +24,25 @@ pub enum Arguments {
         /// File name to dump (may contain wildcard characters usable by glob())
         file_name: String,
     },
+    Graph {
+        /// Output format of the node dependency graph
+        #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+        /// Target of the framework manifest to select nodes for
+        #[arg(long, default_value = "webots")]
+        target: String,
+    },
+    Lint {
+        /// Target of the framework manifest to select nodes for
+        #[arg(long, default_value = "webots")]
+        target: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum GraphFormat {
+    Dot,
+    Json,
 }
 
 pub async fn analyze(arguments: Arguments, repository: &Repository) -> Result<()> {
@@ -46,7 +71,46 @@ pub async fn analyze(arguments: Arguments, repository: &Repository) -> Result<()
                 .print()
                 .wrap_err("failed to print file")?;
         }
+        Arguments::Graph { format, target } => {
+            let cyclers = load_cyclers(repository, &target)?;
+            let graph = DependencyGraph::from_cyclers(&cyclers);
+            let string = match format {
+                GraphFormat::Dot => graph.to_dot()?,
+                GraphFormat::Json => serde_json::to_string_pretty(&graph)?,
+            };
+            println!("{string}");
+        }
+        Arguments::Lint { target } => {
+            let cyclers = load_cyclers(repository, &target)?;
+            let parameters_path = repository.parameters_root().join("default.json");
+            let parameters = read_to_string(&parameters_path)
+                .wrap_err_with(|| format!("failed to read {}", parameters_path.display()))?;
+            let parameters = serde_json::from_str(&parameters)
+                .wrap_err_with(|| format!("failed to parse {}", parameters_path.display()))?;
+            let lints = lint(&cyclers, &parameters);
+            for lint in &lints {
+                let level = if lint.is_error() { "error" } else { "warning" };
+                println!("{level}: {lint}");
+            }
+            let number_of_errors = lints.iter().filter(|lint| lint.is_error()).count();
+            if number_of_errors > 0 {
+                bail!("found {number_of_errors} errors");
+            }
+        }
     }
 
     Ok(())
 }
+
+fn load_cyclers(repository: &Repository, target: &str) -> Result<Cyclers> {
+    let crates_directory = repository.crates_directory();
+    let manifest =
+        FrameworkManifest::try_from_file(crates_directory.join("hulk/framework.toml"), target)
+            .wrap_err("failed to load framework manifest")?;
+    let mut cyclers = Cyclers::try_from_manifest(manifest, crates_directory)
+        .wrap_err("failed to analyze cyclers")?;
+    cyclers
+        .sort_nodes()
+        .wrap_err("failed to sort nodes of cyclers")?;
+    Ok(cyclers)
+}
@@ -0,0 +1,114 @@
+use std::time::SystemTime;
+
+use color_eyre::{eyre::bail, Result};
+use hardware::{
+    ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
+    PathsInterface, RecordingInterface, SensorInterface, SpeakerInterface, TimeInterface,
+};
+use parking_lot::Mutex;
+use types::{
+    audio::SpeakerRequest,
+    camera_position::CameraPosition,
+    hardware::{Ids, Paths},
+    joints::Joints,
+    led::Leds,
+    messages::{IncomingMessage, OutgoingMessage},
+    samples::Samples,
+    sensor_data::SensorData,
+    ycbcr422_image::YCbCr422Image,
+};
+
+/// Hardware interface for replayed cyclers
+///
+/// Setup nodes are never executed during replay, their outputs are taken from the recording.
+/// Reading from hardware therefore fails and everything written to hardware is discarded.
+pub struct ReplayerHardwareInterface {
+    ids: Ids,
+    paths: Paths,
+    now: Mutex<SystemTime>,
+}
+
+impl ReplayerHardwareInterface {
+    pub fn new(ids: Ids, paths: Paths) -> Self {
+        Self {
+            ids,
+            paths,
+            now: Mutex::new(SystemTime::UNIX_EPOCH),
+        }
+    }
+
+    pub fn set_now(&self, now: SystemTime) {
+        *self.now.lock() = now;
+    }
+}
+
+impl ActuatorInterface for ReplayerHardwareInterface {
+    fn write_to_actuators(
+        &self,
+        _positions: Joints<f32>,
+        _stiffnesses: Joints<f32>,
+        _leds: Leds,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl CameraInterface for ReplayerHardwareInterface {
+    fn read_from_camera(&self, _camera_position: CameraPosition) -> Result<YCbCr422Image> {
+        bail!("camera images are not available during replay")
+    }
+}
+
+impl IdInterface for ReplayerHardwareInterface {
+    fn get_ids(&self) -> Ids {
+        self.ids.clone()
+    }
+}
+
+impl MicrophoneInterface for ReplayerHardwareInterface {
+    fn read_from_microphones(&self) -> Result<Samples> {
+        bail!("microphone samples are not available during replay")
+    }
+}
+
+impl NetworkInterface for ReplayerHardwareInterface {
+    fn read_from_network(&self) -> Result<IncomingMessage> {
+        bail!("network messages are not available during replay")
+    }
+
+    fn write_to_network(&self, _message: OutgoingMessage) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl PathsInterface for ReplayerHardwareInterface {
+    fn get_paths(&self) -> Paths {
+        self.paths.clone()
+    }
+}
+
+impl RecordingInterface for ReplayerHardwareInterface {
+    fn should_record(&self) -> bool {
+        false
+    }
+
+    fn set_whether_to_record(&self, _enable: bool) {}
+}
+
+impl SensorInterface for ReplayerHardwareInterface {
+    fn read_from_sensors(&self) -> Result<SensorData> {
+        bail!("sensor data is not available during replay")
+    }
+}
+
+impl SpeakerInterface for ReplayerHardwareInterface {
+    fn write_to_speakers(&self, _request: SpeakerRequest) {}
+}
+
+impl TimeInterface for ReplayerHardwareInterface {
+    fn get_now(&self) -> SystemTime {
+        *self.now.lock()
+    }
+}
+
+impl hulk::HardwareInterface for ReplayerHardwareInterface {}
@@ -0,0 +1,255 @@
+use std::{
+    collections::BTreeMap,
+    io::stdin,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{channel, RecvTimeoutError, Sender},
+        Arc,
+    },
+    thread::spawn,
+    time::{Duration, Instant, SystemTime},
+};
+
+use clap::Parser;
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    install, Result,
+};
+use ctrlc::set_handler;
+use framework::RecordingIndex;
+use hulk::replayer::Replayer;
+use tokio_util::sync::CancellationToken;
+use types::hardware::{Ids, Paths};
+
+use hardware_interface::ReplayerHardwareInterface;
+
+mod hardware_interface;
+
+#[derive(Parser)]
+struct Arguments {
+    #[arg(short, long, default_value = "[::]:1337")]
+    listen_address: String,
+    #[arg(long, default_value = "etc/parameters")]
+    parameters_directory: PathBuf,
+    #[arg(long, default_value = "replayer")]
+    body_id: String,
+    #[arg(long, default_value = "replayer")]
+    head_id: String,
+    /// Recording files named `<instance>.<seconds>.bincode`, e.g. `logs/Control.1700000000.bincode`
+    #[arg(required = true)]
+    recording_files: Vec<PathBuf>,
+}
+
+enum Command {
+    Play,
+    Pause,
+    Step,
+    StepBack,
+    Seek(Duration),
+    Replay,
+    Quit,
+}
+
+impl Command {
+    fn parse(line: &str) -> Result<Self> {
+        let mut words = line.split_whitespace();
+        let command = match (words.next(), words.next()) {
+            (Some("play"), None) => Command::Play,
+            (Some("pause"), None) => Command::Pause,
+            (Some("step"), None) | (None, None) => Command::Step,
+            (Some("back"), None) => Command::StepBack,
+            (Some("seek"), Some(seconds)) => Command::Seek(Duration::from_secs_f32(
+                seconds
+                    .parse()
+                    .wrap_err_with(|| format!("failed to parse seconds `{seconds}`"))?,
+            )),
+            (Some("quit"), None) => Command::Quit,
+            _ => bail!(
+                "unknown command `{line}`, try play, pause, step, back, seek <seconds>, or quit"
+            ),
+        };
+        Ok(command)
+    }
+}
+
+fn main() -> Result<()> {
+    install()?;
+    let arguments = Arguments::parse();
+
+    let recording_indices = arguments
+        .recording_files
+        .iter()
+        .map(|path| {
+            let instance = instance_name_from_path(path)?;
+            let recording_index = RecordingIndex::read_from_path(path)
+                .wrap_err_with(|| format!("failed to index recording {path:?}"))?;
+            Ok((instance, recording_index))
+        })
+        .collect::<Result<BTreeMap<_, _>>>()?;
+    let timestamps = collect_timestamps(&recording_indices);
+    if timestamps.is_empty() {
+        bail!("recordings do not contain any frames");
+    }
+
+    let keep_running = CancellationToken::new();
+    let hardware_interface = Arc::new(ReplayerHardwareInterface::new(
+        Ids {
+            body_id: arguments.body_id.clone(),
+            head_id: arguments.head_id.clone(),
+        },
+        Paths {
+            motions: "etc/motions".into(),
+            neural_networks: "etc/neural_networks".into(),
+            sounds: "etc/sounds".into(),
+        },
+    ));
+    let mut replayer = Replayer::new(
+        hardware_interface.clone(),
+        Some(arguments.listen_address),
+        arguments.parameters_directory,
+        arguments.body_id,
+        arguments.head_id,
+        keep_running.clone(),
+        recording_indices,
+    )
+    .wrap_err("failed to create replayer")?;
+
+    let (command_sender, command_receiver) = channel();
+    spawn_command_reader(command_sender.clone());
+    spawn_parameter_change_forwarder(
+        command_sender.clone(),
+        replayer.get_parameters_changed(),
+        keep_running.clone(),
+    )?;
+    set_handler(move || {
+        command_sender.send(Command::Quit).ok();
+    })?;
+
+    let mut position = 0;
+    let mut is_playing = false;
+    let mut replay_started_at = Instant::now();
+    let mut replay_started_from = timestamps[position];
+    loop {
+        let timestamp = timestamps[position];
+        hardware_interface.set_now(timestamp);
+        replayer
+            .seek(timestamp)
+            .wrap_err_with(|| format!("failed to replay frame {position}"))?;
+        println!(
+            "frame {position}/{} at {:.3}s",
+            timestamps.len() - 1,
+            seconds_since_start(&timestamps, timestamp),
+        );
+
+        let command = if is_playing {
+            let next_position = (position + 1).min(timestamps.len() - 1);
+            let due_in = (timestamps[next_position]
+                .duration_since(replay_started_from)
+                .unwrap_or_default())
+            .saturating_sub(replay_started_at.elapsed());
+            match command_receiver.recv_timeout(due_in) {
+                Ok(command) => command,
+                Err(RecvTimeoutError::Timeout) if next_position == position => Command::Pause,
+                Err(RecvTimeoutError::Timeout) => Command::Step,
+                Err(RecvTimeoutError::Disconnected) => Command::Quit,
+            }
+        } else {
+            command_receiver.recv().unwrap_or(Command::Quit)
+        };
+
+        match command {
+            Command::Play => {
+                is_playing = true;
+                replay_started_at = Instant::now();
+                replay_started_from = timestamp;
+            }
+            Command::Pause => is_playing = false,
+            Command::Step => position = (position + 1).min(timestamps.len() - 1),
+            Command::StepBack => position = position.saturating_sub(1),
+            Command::Seek(offset) => {
+                let target = timestamps[0] + offset;
+                position = timestamps
+                    .partition_point(|timestamp| *timestamp <= target)
+                    .saturating_sub(1);
+                replay_started_at = Instant::now();
+                replay_started_from = timestamps[position];
+            }
+            Command::Replay => {}
+            Command::Quit => break,
+        }
+    }
+
+    keep_running.cancel();
+    replayer.join()
+}
+
+fn instance_name_from_path(path: &Path) -> Result<String> {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .and_then(|file_name| file_name.split('.').next())
+        .filter(|instance| !instance.is_empty())
+        .map(ToString::to_string)
+        .ok_or_else(|| eyre!("failed to extract cycler instance from {path:?}"))
+}
+
+fn collect_timestamps(recording_indices: &BTreeMap<String, RecordingIndex>) -> Vec<SystemTime> {
+    let mut timestamps: Vec<_> = recording_indices
+        .values()
+        .flat_map(|recording_index| recording_index.frames().iter().map(|frame| frame.timestamp))
+        .collect();
+    timestamps.sort();
+    timestamps.dedup();
+    timestamps
+}
+
+fn seconds_since_start(timestamps: &[SystemTime], timestamp: SystemTime) -> f32 {
+    timestamp
+        .duration_since(timestamps[0])
+        .unwrap_or_default()
+        .as_secs_f32()
+}
+
+fn spawn_command_reader(command_sender: Sender<Command>) {
+    spawn(move || {
+        for line in stdin().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            match Command::parse(&line) {
+                Ok(command) => {
+                    if command_sender.send(command).is_err() {
+                        break;
+                    }
+                }
+                Err(error) => eprintln!("{error}"),
+            }
+        }
+    });
+}
+
+/// Replays the current frame again whenever parameters are changed, e.g. from twix
+fn spawn_parameter_change_forwarder(
+    command_sender: Sender<Command>,
+    parameters_changed: Arc<tokio::sync::Notify>,
+    keep_running: CancellationToken,
+) -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .wrap_err("failed to create runtime")?;
+    spawn(move || {
+        runtime.block_on(async {
+            loop {
+                tokio::select! {
+                    _ = parameters_changed.notified() => {
+                        if command_sender.send(Command::Replay).is_err() {
+                            break;
+                        }
+                    }
+                    _ = keep_running.cancelled() => break,
+                }
+            }
+        })
+    });
+    Ok(())
+}
@@ -8,7 +8,9 @@ use serde_json::{json, Value};
 
 use crate::{nao::Nao, twix_painter::TwixPainter};
 
-use super::overlays::{BallDetection, FeetDetection, LineDetection, PenaltyBoxes, RobotDetection};
+use super::overlays::{
+    BallDetection, FeetDetection, LineDetection, PenaltyBoxes, PenaltyMarkDetection, RobotDetection,
+};
 
 pub trait Overlay {
     const NAME: &'static str;
@@ -80,6 +82,7 @@ pub struct Overlays {
     pub line_detection: EnabledOverlay<LineDetection>,
     pub ball_detection: EnabledOverlay<BallDetection>,
     pub penalty_boxes: EnabledOverlay<PenaltyBoxes>,
+    pub penalty_mark_detection: EnabledOverlay<PenaltyMarkDetection>,
     pub feet_detection: EnabledOverlay<FeetDetection>,
     pub robot_detection: EnabledOverlay<RobotDetection>,
 }
@@ -89,12 +92,15 @@ impl Overlays {
         let line_detection = EnabledOverlay::new(nao.clone(), storage, true, selected_cycler);
         let ball_detection = EnabledOverlay::new(nao.clone(), storage, true, selected_cycler);
         let penalty_boxes = EnabledOverlay::new(nao.clone(), storage, true, selected_cycler);
+        let penalty_mark_detection =
+            EnabledOverlay::new(nao.clone(), storage, false, selected_cycler);
         let feet_detection = EnabledOverlay::new(nao.clone(), storage, true, selected_cycler);
         let robot_detection = EnabledOverlay::new(nao, storage, true, selected_cycler);
         Self {
             line_detection,
             ball_detection,
             penalty_boxes,
+            penalty_mark_detection,
             feet_detection,
             robot_detection,
         }
@@ -104,6 +110,7 @@ impl Overlays {
         self.line_detection.update_cycler(selected_cycler);
         self.ball_detection.update_cycler(selected_cycler);
         self.penalty_boxes.update_cycler(selected_cycler);
+        self.penalty_mark_detection.update_cycler(selected_cycler);
         self.feet_detection.update_cycler(selected_cycler);
         self.robot_detection.update_cycler(selected_cycler);
     }
@@ -113,6 +120,7 @@ impl Overlays {
             self.line_detection.checkbox(ui, selected_cycler);
             self.ball_detection.checkbox(ui, selected_cycler);
             self.penalty_boxes.checkbox(ui, selected_cycler);
+            self.penalty_mark_detection.checkbox(ui, selected_cycler);
             self.feet_detection.checkbox(ui, selected_cycler);
             self.robot_detection.checkbox(ui, selected_cycler);
         });
@@ -122,6 +130,7 @@ impl Overlays {
         let _ = self.line_detection.paint(painter);
         let _ = self.ball_detection.paint(painter);
         let _ = self.penalty_boxes.paint(painter);
+        let _ = self.penalty_mark_detection.paint(painter);
         let _ = self.feet_detection.paint(painter);
         let _ = self.robot_detection.paint(painter);
         Ok(())
@@ -132,6 +141,7 @@ impl Overlays {
             "line_detection": self.line_detection.save(),
             "ball_detection": self.ball_detection.save(),
             "penalty_boxes": self.penalty_boxes.save(),
+            "penalty_mark_detection": self.penalty_mark_detection.save(),
             "feet_detection": self.feet_detection.save(),
             "robot_detection": self.robot_detection.save(),
         })
@@ -4,7 +4,7 @@ use color_eyre::Result;
 use communication::client::{Cycler, CyclerOutput};
 use eframe::epaint::{Color32, Stroke};
 use types::line_data::ImageLines;
-use types::line_data::LineDiscardReason;
+use types::line_data::{IntersectionKind, LineDiscardReason};
 
 use crate::{
     panels::image::overlay::Overlay, twix_painter::TwixPainter, value_buffer::ValueBuffer,
@@ -43,6 +43,14 @@ impl Overlay for LineDetection {
         for line in lines_in_image.lines {
             painter.line_segment(line.0, line.1, Stroke::new(3.0, Color32::BLUE));
         }
+        for intersection in lines_in_image.intersections {
+            let color = match intersection.kind {
+                IntersectionKind::L => Color32::LIGHT_GREEN,
+                IntersectionKind::T => Color32::GOLD,
+                IntersectionKind::X => Color32::LIGHT_RED,
+            };
+            painter.circle_filled(intersection.position, 6.0, color);
+        }
         Ok(())
     }
 }
@@ -2,10 +2,12 @@ mod ball_detection;
 mod feet_detection;
 mod line_detection;
 mod penalty_boxes;
+mod penalty_mark_detection;
 mod robot_detection;
 
 pub use ball_detection::BallDetection;
 pub use feet_detection::FeetDetection;
 pub use line_detection::LineDetection;
 pub use penalty_boxes::PenaltyBoxes;
+pub use penalty_mark_detection::PenaltyMarkDetection;
 pub use robot_detection::RobotDetection;
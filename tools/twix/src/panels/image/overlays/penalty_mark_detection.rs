@@ -0,0 +1,47 @@
+use std::str::FromStr;
+
+use color_eyre::Result;
+use communication::client::{Cycler, CyclerOutput};
+use eframe::epaint::{Color32, Stroke};
+use types::penalty_mark::PenaltyMarkCandidate;
+
+use crate::{
+    panels::image::overlay::Overlay, twix_painter::TwixPainter, value_buffer::ValueBuffer,
+};
+
+pub struct PenaltyMarkDetection {
+    penalty_mark_candidates: ValueBuffer,
+}
+
+impl Overlay for PenaltyMarkDetection {
+    const NAME: &'static str = "Penalty Mark Detection";
+
+    fn new(nao: std::sync::Arc<crate::nao::Nao>, selected_cycler: Cycler) -> Self {
+        Self {
+            penalty_mark_candidates: nao.subscribe_output(
+                CyclerOutput::from_str(&format!(
+                    "{selected_cycler}.additional.penalty_mark_candidates"
+                ))
+                .unwrap(),
+            ),
+        }
+    }
+
+    fn paint(&self, painter: &TwixPainter) -> Result<()> {
+        let penalty_mark_candidates: Vec<PenaltyMarkCandidate> =
+            self.penalty_mark_candidates.require_latest()?;
+        for candidate in penalty_mark_candidates {
+            let color = if candidate.is_accepted {
+                Color32::GREEN
+            } else {
+                Color32::YELLOW
+            };
+            painter.circle_stroke(
+                candidate.center_in_image,
+                candidate.radius_in_image,
+                Stroke::new(2.0, color),
+            );
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 use color_eyre::eyre::Context;
-use eframe::egui::{Response, Slider, Ui, Widget};
+use communication::client::CyclerOutput;
+use eframe::egui::{Button, Response, Slider, Ui, Widget};
 use log::{error, info};
 use nalgebra::Vector3;
 use serde_json::Value;
-use std::{ops::RangeInclusive, sync::Arc};
+use std::{ops::RangeInclusive, str::FromStr, sync::Arc};
 use tokio::sync::mpsc;
+use types::camera_calibration::CalibrationResult;
 
 use crate::{
     nao::Nao, panel::Panel, repository_parameters::RepositoryParameters, value_buffer::ValueBuffer,
@@ -25,37 +27,54 @@ struct CameraParameterSubscriptions<DeserializedValueType> {
 pub struct ManualCalibrationPanel {
     nao: Arc<Nao>,
     repository_parameters: RepositoryParameters,
-    extrinsic_rotation_subscriptions: [CameraParameterSubscriptions<Option<SubscribedType>>; 2],
+    extrinsic_rotation_subscriptions: [CameraParameterSubscriptions<Option<SubscribedType>>; 3],
+    calibration_result_buffer: ValueBuffer,
 }
 
-const CAMERA_KEY_BASE: &str = "camera_matrix_parameters.vision_";
-const ROTATIONS: &str = ".extrinsic_rotations";
+const ROTATION_PARAMETERS: [(&str, &str); 3] = [
+    (
+        "Top Camera",
+        "camera_matrix_parameters.vision_top.extrinsic_rotations",
+    ),
+    (
+        "Bottom Camera",
+        "camera_matrix_parameters.vision_bottom.extrinsic_rotations",
+    ),
+    (
+        "Robot Correction",
+        "camera_matrix_parameters.robot_rotation_correction",
+    ),
+];
 
 impl Panel for ManualCalibrationPanel {
     const NAME: &'static str = "Manual Calibration";
 
     fn new(nao: Arc<Nao>, _value: Option<&Value>) -> Self {
-        let extrinsic_rotation_subscriptions = ["Top", "Bottom"].map(|name| {
-            let path = CAMERA_KEY_BASE.to_owned() + name.to_lowercase().as_str() + ROTATIONS;
-
+        let extrinsic_rotation_subscriptions = ROTATION_PARAMETERS.map(|(name, path)| {
             let (update_notify_sender, update_notify_receiver) = mpsc::channel(1);
-            let value_buffer = subscribe(nao.clone(), &path, update_notify_sender).unwrap();
+            let value_buffer = subscribe(nao.clone(), path, update_notify_sender).unwrap();
 
             info!("Subscribing to path {}", path);
 
             CameraParameterSubscriptions {
                 human_friendly_label: name.to_string(),
-                path,
+                path: path.to_string(),
                 value_buffer,
                 value: None,
                 update_notify_receiver,
             }
         });
 
+        let calibration_result_buffer = nao.subscribe_output(
+            CyclerOutput::from_str("Control.additional_outputs.calibration_result")
+                .expect("Failed to subscribe to additional_outputs.calibration_result"),
+        );
+
         Self {
             nao,
             repository_parameters: RepositoryParameters::try_new().unwrap(),
             extrinsic_rotation_subscriptions,
+            calibration_result_buffer,
         }
     }
 }
@@ -91,7 +110,7 @@ fn add_extrinsic_calibration_ui_components(
             }
         }
 
-        ui.label(format!("{label:#} Camera"));
+        ui.label(label.as_str());
 
         add_save_button(
             ui,
@@ -144,9 +163,72 @@ fn add_extrinsic_calibration_ui_components(
     }
 }
 
+fn add_calibration_result_ui_components(
+    ui: &mut Ui,
+    calibration_result_buffer: &ValueBuffer,
+    extrinsic_rotation_subscriptions: &mut [CameraParameterSubscriptions<Option<SubscribedType>>],
+) {
+    let calibration_result =
+        match calibration_result_buffer.parse_latest::<Option<CalibrationResult>>() {
+            Ok(Some(calibration_result)) => calibration_result,
+            Ok(None) => {
+                ui.label("No automatic calibration result available.");
+                return;
+            }
+            Err(error) => {
+                ui.label(format!("{error:#?}"));
+                return;
+            }
+        };
+    ui.label(format!(
+        "Automatic calibration: {} measurements ({} outliers), converged: {}, residual {:?} -> {:?}",
+        calibration_result.number_of_measurements,
+        calibration_result.number_of_outliers,
+        calibration_result.is_converged,
+        calibration_result.initial_root_mean_square_residual,
+        calibration_result.root_mean_square_residual,
+    ));
+    if let Some([robot, top, bottom]) = calibration_result.standard_deviations {
+        ui.label(format!(
+            "Standard deviations [deg]: robot {:.2?}, top {:.2?}, bottom {:.2?}",
+            robot.as_slice(),
+            top.as_slice(),
+            bottom.as_slice(),
+        ));
+    }
+    ui.label(format!("Verdict: {:?}", calibration_result.verdict));
+    if ui
+        .add_enabled(
+            calibration_result.verdict.is_accepted(),
+            Button::new("Apply automatic calibration"),
+        )
+        .clicked()
+    {
+        let rotations = [
+            calibration_result.top_extrinsic_rotations,
+            calibration_result.bottom_extrinsic_rotations,
+            calibration_result.robot_rotation_correction,
+        ];
+        for (subscription, rotation) in extrinsic_rotation_subscriptions.iter_mut().zip(rotations) {
+            let rotation: SubscribedType = rotation.cast();
+            subscription.value = Some(rotation);
+            match serde_json::value::to_value(rotation) {
+                Ok(value) => subscription.value_buffer.update_parameter_value(value),
+                Err(error) => error!("Failed to serialize parameter value: {error:#?}"),
+            }
+        }
+    }
+}
+
 impl Widget for &mut ManualCalibrationPanel {
     fn ui(self, ui: &mut Ui) -> Response {
         ui.vertical(|ui| {
+            add_calibration_result_ui_components(
+                ui,
+                &self.calibration_result_buffer,
+                &mut self.extrinsic_rotation_subscriptions,
+            );
+            ui.separator();
             for extrinsic_rotation_subscription in &mut self.extrinsic_rotation_subscriptions {
                 add_extrinsic_calibration_ui_components(
                     ui,
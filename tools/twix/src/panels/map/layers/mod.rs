@@ -12,6 +12,8 @@ mod obstacles;
 mod path;
 mod path_obstacles;
 mod robot_pose;
+mod team_ball;
+mod teammate_intentions;
 
 pub use self::behavior_simulator::BehaviorSimulator;
 pub use ball_filter::BallFilter;
@@ -27,3 +29,5 @@ pub use obstacles::Obstacles;
 pub use path::Path;
 pub use path_obstacles::PathObstacles;
 pub use robot_pose::RobotPose;
+pub use team_ball::TeamBall;
+pub use teammate_intentions::TeammateIntentions;
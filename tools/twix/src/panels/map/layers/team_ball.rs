@@ -0,0 +1,37 @@
+use std::{str::FromStr, sync::Arc};
+
+use color_eyre::Result;
+use communication::client::CyclerOutput;
+use eframe::epaint::{Color32, Stroke};
+use types::field_dimensions::FieldDimensions;
+
+use crate::{
+    nao::Nao, panels::map::layer::Layer, twix_painter::TwixPainter, value_buffer::ValueBuffer,
+};
+
+pub struct TeamBall {
+    team_ball: ValueBuffer,
+}
+
+impl Layer for TeamBall {
+    const NAME: &'static str = "Team Ball";
+
+    fn new(nao: Arc<Nao>) -> Self {
+        let team_ball =
+            nao.subscribe_output(CyclerOutput::from_str("Control.main.fused_team_ball").unwrap());
+        Self { team_ball }
+    }
+
+    fn paint(&self, painter: &TwixPainter, field_dimensions: &FieldDimensions) -> Result<()> {
+        let team_ball: Option<types::team_ball::TeamBall> = self.team_ball.parse_latest()?;
+
+        if let Some(team_ball) = team_ball {
+            let stroke = Stroke::new(0.01, Color32::BLACK);
+            let fill_color = Color32::from_rgba_unmultiplied(0, 0, 255, 100);
+            painter.covariance(team_ball.position, team_ball.covariance, stroke, fill_color);
+            painter.ball(team_ball.position, field_dimensions.ball_radius);
+        }
+
+        Ok(())
+    }
+}
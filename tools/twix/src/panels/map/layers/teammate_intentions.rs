@@ -0,0 +1,72 @@
+use std::{str::FromStr, sync::Arc};
+
+use color_eyre::Result;
+use communication::client::CyclerOutput;
+use eframe::epaint::{Color32, Stroke};
+use types::{
+    field_dimensions::FieldDimensions, players::Players, roles::Role,
+    teammate_intention::TeammateIntention,
+};
+
+use crate::{
+    nao::Nao, panels::map::layer::Layer, twix_painter::TwixPainter, value_buffer::ValueBuffer,
+};
+
+pub struct TeammateIntentions {
+    teammate_intentions: ValueBuffer,
+}
+
+impl Layer for TeammateIntentions {
+    const NAME: &'static str = "Teammate Intentions";
+
+    fn new(nao: Arc<Nao>) -> Self {
+        let teammate_intentions = nao
+            .subscribe_output(CyclerOutput::from_str("Control.main.teammate_intentions").unwrap());
+        Self {
+            teammate_intentions,
+        }
+    }
+
+    fn paint(&self, painter: &TwixPainter, _field_dimensions: &FieldDimensions) -> Result<()> {
+        let teammate_intentions: Players<Option<TeammateIntention>> =
+            self.teammate_intentions.require_latest()?;
+
+        for teammate in teammate_intentions
+            .iter()
+            .filter_map(|(_player_number, teammate)| teammate.as_ref())
+        {
+            let color = role_color(teammate.role);
+            let stroke = Stroke { width: 0.02, color };
+            let position = teammate.robot_to_field.translation.vector.into();
+            if let Some(walk_target) = teammate.intention.walk_target {
+                painter.line_segment(position, walk_target, stroke);
+                painter.circle_stroke(walk_target, 0.1, stroke);
+            }
+            if let Some(kick_target) = teammate.intention.kick_target {
+                painter.target(kick_target, 0.15, stroke, Color32::TRANSPARENT);
+            }
+            painter.pose(
+                teammate.robot_to_field,
+                0.15,
+                0.25,
+                Color32::from_white_alpha(187),
+                Stroke {
+                    width: 0.02,
+                    color: Color32::BLACK,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+fn role_color(role: Role) -> Color32 {
+    match role {
+        Role::Striker => Color32::RED,
+        Role::StrikerSupporter => Color32::from_rgb(255, 128, 0),
+        Role::Keeper | Role::ReplacementKeeper => Color32::YELLOW,
+        Role::DefenderLeft | Role::DefenderRight => Color32::BLUE,
+        Role::MidfielderLeft | Role::MidfielderRight => Color32::GREEN,
+        Role::Loser | Role::Searcher => Color32::GRAY,
+    }
+}
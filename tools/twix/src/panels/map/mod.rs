@@ -30,6 +30,8 @@ pub struct MapPanel {
     feet_detection: EnabledLayer<layers::FeetDetection>,
     ball_filter: EnabledLayer<layers::BallFilter>,
     obstacle_filter: EnabledLayer<layers::ObstacleFilter>,
+    team_ball: EnabledLayer<layers::TeamBall>,
+    teammate_intentions: EnabledLayer<layers::TeammateIntentions>,
 }
 
 impl Panel for MapPanel {
@@ -50,6 +52,8 @@ impl Panel for MapPanel {
         let feet_detection = EnabledLayer::new(nao.clone(), value, false);
         let ball_filter = EnabledLayer::new(nao.clone(), value, false);
         let obstacle_filter = EnabledLayer::new(nao.clone(), value, false);
+        let team_ball = EnabledLayer::new(nao.clone(), value, false);
+        let teammate_intentions = EnabledLayer::new(nao.clone(), value, false);
 
         let field_dimensions = nao.subscribe_parameter("field_dimensions");
         let transformation = Similarity2::identity();
@@ -71,6 +75,8 @@ impl Panel for MapPanel {
             feet_detection,
             ball_filter,
             obstacle_filter,
+            team_ball,
+            teammate_intentions,
         }
     }
 
@@ -90,6 +96,8 @@ impl Panel for MapPanel {
             "feet_detection": self.feet_detection.save(),
             "ball_filter": self.ball_filter.save(),
             "obstacle_filter": self.obstacle_filter.save(),
+            "team_ball": self.team_ball.save(),
+            "teammate_intentions": self.teammate_intentions.save(),
         })
     }
 }
@@ -111,6 +119,8 @@ impl Widget for &mut MapPanel {
             self.feet_detection.checkbox(ui);
             self.ball_filter.checkbox(ui);
             self.obstacle_filter.checkbox(ui);
+            self.team_ball.checkbox(ui);
+            self.teammate_intentions.checkbox(ui);
         });
 
         let field_dimensions: FieldDimensions = match self.field_dimensions.get_latest() {
@@ -136,6 +146,8 @@ impl Widget for &mut MapPanel {
         let _ = self.feet_detection.paint(&painter, &field_dimensions);
         let _ = self.ball_filter.paint(&painter, &field_dimensions);
         let _ = self.obstacle_filter.paint(&painter, &field_dimensions);
+        let _ = self.team_ball.paint(&painter, &field_dimensions);
+        let _ = self.teammate_intentions.paint(&painter, &field_dimensions);
 
         self.apply_zoom_and_pan(ui, &mut painter, &response);
         if response.double_clicked() {